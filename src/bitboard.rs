@@ -0,0 +1,99 @@
+use crate::game::{CellState, Tetromino, GRID_HEIGHT, GRID_WIDTH};
+
+// Each row is packed into a u16, one bit per column
+const _: () = assert!(GRID_WIDTH <= 16);
+
+const FULL_ROW: u16 = ((1u32 << GRID_WIDTH) - 1) as u16;
+
+// ============================================================================
+// Bitboard
+// ============================================================================
+
+/// Occupancy-only board for fast simulation. Bit `x` of `rows[y]` is set
+/// when cell (x, y) is filled; piece colors are not tracked.
+#[derive(Clone, Copy, Eq, Debug, Default)]
+pub struct BitBoard {
+    rows: [u16; GRID_HEIGHT],
+    /// Empty rows above the board a piece may reach into, as in
+    /// `GameConfig::buffer_rows`
+    buffer_rows: usize,
+}
+
+/// Boards are equal when the same cells are filled
+impl PartialEq for BitBoard {
+    fn eq(&self, other: &Self) -> bool {
+        self.rows == other.rows
+    }
+}
+
+impl BitBoard {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn from_grid(grid: &[Vec<CellState>]) -> Self {
+        let mut board = Self::new();
+        for (y, row) in grid.iter().enumerate().take(GRID_HEIGHT) {
+            for (x, cell) in row.iter().enumerate().take(GRID_WIDTH) {
+                if *cell != CellState::Empty {
+                    board.rows[y] |= 1 << x;
+                }
+            }
+        }
+        board
+    }
+
+    pub fn with_buffer_rows(mut self, buffer_rows: usize) -> Self {
+        self.buffer_rows = buffer_rows;
+        self
+    }
+
+    pub fn rows(&self) -> &[u16; GRID_HEIGHT] {
+        &self.rows
+    }
+
+    pub fn is_occupied(&self, x: usize, y: usize) -> bool {
+        self.rows[y] & (1 << x) != 0
+    }
+
+    /// Same rules as `Game::is_valid_position`: in bounds (the buffer rows
+    /// included) and not overlapping
+    pub fn is_valid_position(&self, piece: &Tetromino) -> bool {
+        piece.blocks().iter().all(|block| {
+            block.x >= 0
+                && block.x < GRID_WIDTH as i16
+                && block.y >= -(self.buffer_rows as i16)
+                && block.y < GRID_HEIGHT as i16
+                && (block.y < 0 || self.rows[block.y as usize] & (1 << block.x) == 0)
+        })
+    }
+
+    /// Sets the piece's in-bounds cells
+    pub fn lock(&mut self, piece: &Tetromino) {
+        for block in piece.blocks() {
+            if block.x >= 0 && block.x < GRID_WIDTH as i16 && block.y >= 0 && block.y < GRID_HEIGHT as i16 {
+                self.rows[block.y as usize] |= 1 << block.x;
+            }
+        }
+    }
+
+    /// Removes full rows, shifting everything above down. Returns the count.
+    pub fn clear_lines(&mut self) -> u32 {
+        let mut write = GRID_HEIGHT;
+        for read in (0..GRID_HEIGHT).rev() {
+            if self.rows[read] != FULL_ROW {
+                write -= 1;
+                self.rows[write] = self.rows[read];
+            }
+        }
+        let cleared = write;
+        for row in self.rows.iter_mut().take(cleared) {
+            *row = 0;
+        }
+        cleared as u32
+    }
+
+    pub fn total_filled_cells(&self) -> usize {
+        self.rows.iter().map(|row| row.count_ones() as usize).sum()
+    }
+}
@@ -0,0 +1,84 @@
+use std::{cell::Cell, rc::Rc, time::Instant};
+
+// ============================================================================
+// Clocks
+// ============================================================================
+
+/// Source of monotonic time for the main loop
+pub trait Clock {
+    /// Milliseconds since an arbitrary fixed starting point
+    fn now_ms(&self) -> u64;
+}
+
+/// Wall-clock time used by the real game
+pub struct SystemClock {
+    start: Instant,
+}
+
+impl SystemClock {
+    pub fn new() -> Self {
+        Self {
+            start: Instant::now(),
+        }
+    }
+}
+
+impl Default for SystemClock {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Clock for SystemClock {
+    fn now_ms(&self) -> u64 {
+        self.start.elapsed().as_millis() as u64
+    }
+}
+
+/// Manually advanced clock for tests. Clones share the same time, so a test
+/// can keep a handle while a `FrameTimer` owns another.
+#[derive(Clone, Default)]
+pub struct MockClock {
+    now_ms: Rc<Cell<u64>>,
+}
+
+impl MockClock {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn advance(&self, ms: u64) {
+        self.now_ms.set(self.now_ms.get() + ms);
+    }
+}
+
+impl Clock for MockClock {
+    fn now_ms(&self) -> u64 {
+        self.now_ms.get()
+    }
+}
+
+// ============================================================================
+// Frame Timer
+// ============================================================================
+
+/// Measures the time elapsed between frames of the main loop
+pub struct FrameTimer<C: Clock> {
+    clock: C,
+    last_ms: u64,
+}
+
+impl<C: Clock> FrameTimer<C> {
+    pub fn new(clock: C) -> Self {
+        let last_ms = clock.now_ms();
+        Self { clock, last_ms }
+    }
+
+    /// Milliseconds since the previous call (or since creation)
+    pub fn delta_ms(&mut self) -> u64 {
+        let now = self.clock.now_ms();
+        let delta = now.saturating_sub(self.last_ms);
+        self.last_ms = now;
+        delta
+    }
+}
@@ -0,0 +1,211 @@
+use crate::game::{Game, GameEvent, Position};
+
+// ============================================================================
+// Lock Flash
+// ============================================================================
+
+/// Brief highlight over the cells of the piece that just locked. A lock
+/// that clears lines shows no flash: the rows shift under the cells, so the
+/// clear takes priority and cancels it, as does a restart.
+#[derive(Clone, Debug, Default)]
+pub struct LockFlash {
+    cells: Vec<Position>,
+    remaining_ms: u64,
+}
+
+impl LockFlash {
+    /// Starts or cancels the flash from a frame's events. `duration_ms`
+    /// comes from the theme, so a theme change applies from the next lock.
+    pub fn handle_events(&mut self, events: &[GameEvent], game: &Game, duration_ms: u64) {
+        for event in events {
+            match event {
+                GameEvent::PieceLocked => {
+                    if let Some(piece) = game.last_locked() {
+                        self.cells = piece.blocks();
+                        self.remaining_ms = duration_ms;
+                    }
+                }
+                GameEvent::LinesCleared(_) | GameEvent::GameRestarted => self.cancel(),
+                _ => {}
+            }
+        }
+    }
+
+    /// Counts the flash down by the frame's elapsed time
+    pub fn advance(&mut self, delta_ms: u64) {
+        self.remaining_ms = self.remaining_ms.saturating_sub(delta_ms);
+        if self.remaining_ms == 0 {
+            self.cells.clear();
+        }
+    }
+
+    fn cancel(&mut self) {
+        self.cells.clear();
+        self.remaining_ms = 0;
+    }
+
+    pub fn is_active(&self) -> bool {
+        self.remaining_ms > 0
+    }
+
+    /// Cells to highlight; empty once the flash is over
+    pub fn cells(&self) -> &[Position] {
+        if self.is_active() {
+            &self.cells
+        } else {
+            &[]
+        }
+    }
+
+    /// Milliseconds left on a running flash
+    pub fn remaining_ms(&self) -> Option<u64> {
+        self.is_active().then_some(self.remaining_ms)
+    }
+}
+
+// ============================================================================
+// Settle
+// ============================================================================
+
+/// How long a hard-dropped piece stays squashed, about one frame
+pub const SETTLE_MS: u64 = 50;
+
+/// One-frame squash of a hard-dropped piece as it lands: the cells on its
+/// top face are drawn half height, then the piece snaps back to full size.
+/// Like `LockFlash`, a line clear or restart cancels it, since the rows
+/// shift under the cells.
+#[derive(Clone, Debug, Default)]
+pub struct Settle {
+    cells: Vec<Position>,
+    remaining_ms: u64,
+}
+
+impl Settle {
+    /// Starts or cancels the squash from a frame's events. With `enabled`
+    /// off nothing starts, for players who want instant feedback.
+    pub fn handle_events(&mut self, events: &[GameEvent], enabled: bool) {
+        for event in events {
+            match event {
+                GameEvent::HardDrop(piece) if enabled => {
+                    let blocks = piece.blocks();
+                    // Top face: blocks with no block of the piece right above
+                    self.cells = blocks
+                        .iter()
+                        .filter(|block| !blocks.contains(&Position { x: block.x, y: block.y - 1 }))
+                        .copied()
+                        .collect();
+                    self.remaining_ms = SETTLE_MS;
+                }
+                GameEvent::LinesCleared(_) | GameEvent::GameRestarted => {
+                    self.cells.clear();
+                    self.remaining_ms = 0;
+                }
+                _ => {}
+            }
+        }
+    }
+
+    /// Counts the squash down by the frame's elapsed time
+    pub fn advance(&mut self, delta_ms: u64) {
+        self.remaining_ms = self.remaining_ms.saturating_sub(delta_ms);
+        if self.remaining_ms == 0 {
+            self.cells.clear();
+        }
+    }
+
+    pub fn is_active(&self) -> bool {
+        self.remaining_ms > 0
+    }
+
+    /// Cells to draw half height; empty once the squash is over
+    pub fn squashed_cells(&self) -> &[Position] {
+        if self.is_active() {
+            &self.cells
+        } else {
+            &[]
+        }
+    }
+
+    /// Milliseconds left on a running squash
+    pub fn remaining_ms(&self) -> Option<u64> {
+        self.is_active().then_some(self.remaining_ms)
+    }
+}
+
+// ============================================================================
+// Invisible Blocks
+// ============================================================================
+
+/// How long a locked piece stays visible in the invisible-blocks challenge
+pub const INVISIBLE_AFTER_MS: u64 = 1500;
+
+/// How long a line clear reveals the whole stack, when revealing is on
+pub const REVEAL_MS: u64 = 400;
+
+/// Invisible-blocks challenge: each locked piece stays drawn for
+/// `INVISIBLE_AFTER_MS` from its lock, then only the game remembers it. A
+/// line clear shifts the rows under the fading pieces, so it hides them
+/// at once, optionally showing the whole stack for `REVEAL_MS` first.
+/// Only locked cells are affected; the falling piece and its ghost draw
+/// as usual.
+#[derive(Clone, Debug, Default)]
+pub struct InvisibleStack {
+    /// Cells of recently locked pieces with the time each has left
+    fading: Vec<(Vec<Position>, u64)>,
+    reveal_on_clear: bool,
+    reveal_remaining_ms: u64,
+}
+
+impl InvisibleStack {
+    pub fn new(reveal_on_clear: bool) -> Self {
+        Self {
+            reveal_on_clear,
+            ..Self::default()
+        }
+    }
+
+    /// Starts timing new locks and reacts to clears and restarts
+    pub fn handle_events(&mut self, events: &[GameEvent], game: &Game) {
+        for event in events {
+            match event {
+                GameEvent::PieceLocked => {
+                    if let Some(piece) = game.last_locked() {
+                        self.fading.push((piece.blocks(), INVISIBLE_AFTER_MS));
+                    }
+                }
+                GameEvent::LinesCleared(_) => {
+                    self.fading.clear();
+                    if self.reveal_on_clear {
+                        self.reveal_remaining_ms = REVEAL_MS;
+                    }
+                }
+                GameEvent::GameRestarted => {
+                    self.fading.clear();
+                    self.reveal_remaining_ms = 0;
+                }
+                _ => {}
+            }
+        }
+    }
+
+    /// Counts every fading piece and the reveal down by the frame's
+    /// elapsed time
+    pub fn advance(&mut self, delta_ms: u64) {
+        for (_, remaining_ms) in &mut self.fading {
+            *remaining_ms = remaining_ms.saturating_sub(delta_ms);
+        }
+        self.fading.retain(|(_, remaining_ms)| *remaining_ms > 0);
+        self.reveal_remaining_ms = self.reveal_remaining_ms.saturating_sub(delta_ms);
+    }
+
+    /// Whether a locked cell at `cell` should be left undrawn
+    pub fn hides(&self, cell: Position) -> bool {
+        self.reveal_remaining_ms == 0 && !self.fading.iter().any(|(cells, _)| cells.contains(&cell))
+    }
+
+    /// Milliseconds until the next piece disappears or the reveal ends
+    pub fn remaining_ms(&self) -> Option<u64> {
+        let reveal = (self.reveal_remaining_ms > 0).then_some(self.reveal_remaining_ms);
+        self.fading.iter().map(|(_, remaining_ms)| *remaining_ms).chain(reveal).min()
+    }
+}
@@ -0,0 +1,56 @@
+use crate::game::TetrominoType;
+use crate::rotation::{srs_origin, RotationSystem};
+
+// ============================================================================
+// Finesse
+// ============================================================================
+
+/// Where a piece sits horizontally and which rotation state it is in
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub struct Placement {
+    /// `position.x` of the piece
+    pub column: i16,
+    pub rotation: usize,
+}
+
+/// Fewest key presses (one per tap or rotation) that turn `start` into
+/// `end` on an open board. Rotation states with identical shapes count as
+/// the same end state, so an I can finish flat in state 0 or 2. Wall kicks,
+/// tucks and spins are not modeled.
+pub fn optimal_inputs(
+    rotation_system: RotationSystem,
+    tetromino_type: TetrominoType,
+    start: Placement,
+    end: Placement,
+) -> u32 {
+    let shapes = tetromino_type.shapes();
+    let states = shapes.len();
+    let target_shape = &shapes[end.rotation % states];
+
+    (0..states)
+        .filter(|&rotation| shapes[rotation] == *target_shape)
+        .map(|rotation| {
+            let clockwise = (rotation + states - start.rotation % states) % states;
+            let rotations = clockwise.min(states - clockwise) as u32;
+            let column = start.column
+                + rotation_shift(rotation_system, tetromino_type, start.rotation, rotation);
+            rotations + end.column.abs_diff(column) as u32
+        })
+        .min()
+        .unwrap_or(0)
+}
+
+/// Horizontal move that rotating in place applies to the piece's position
+fn rotation_shift(
+    rotation_system: RotationSystem,
+    tetromino_type: TetrominoType,
+    from: usize,
+    to: usize,
+) -> i16 {
+    match rotation_system {
+        RotationSystem::None | RotationSystem::Classic => 0,
+        RotationSystem::Srs => {
+            srs_origin(tetromino_type, to).0 - srs_origin(tetromino_type, from).0
+        }
+    }
+}
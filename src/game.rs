@@ -1,5 +1,20 @@
-use std::collections::VecDeque;
-use rand::Rng;
+use std::collections::{BTreeMap, VecDeque};
+use std::time::{SystemTime, UNIX_EPOCH};
+use std::{io, path::{Path, PathBuf}};
+use rand::{rngs::StdRng, Rng, SeedableRng};
+use serde::{Deserialize, Serialize};
+
+use crate::bitboard::BitBoard;
+use crate::finesse::{self, Placement};
+use crate::garbage::{
+    self, CheeseConfig, GarbageAttackConfig, GarbageScheduler, RisingFloor, RisingFloorConfig,
+};
+use crate::rotation::{srs_kicks, srs_origin, RotationState, RotationSystem, CLASSIC_KICKS};
+use crate::pieces;
+use crate::puzzle::{Puzzle, PuzzleError};
+use crate::snapshot::GameSnapshot;
+use crate::solver;
+use crate::stats::SessionStats;
 
 // ============================================================================
 // Configuration
@@ -9,29 +24,222 @@ pub const GRID_WIDTH: usize = 10;
 pub const GRID_HEIGHT: usize = 20;
 pub const PREVIEW_COUNT: usize = 4;
 
+/// Format version of `Game::to_cell_matrix`, bumped whenever its layout or
+/// cell meaning changes
+pub const CELL_MATRIX_VERSION: u32 = 1;
+
 // Timing (in milliseconds)
 const BASE_TICK_MS: u64 = 800;
 const MIN_TICK_MS: u64 = 100;
 const SPEED_INCREASE_PER_LEVEL: u64 = 50;
 pub const LINES_PER_LEVEL: u32 = 10;
+/// Play time between `GameEvent::PaceUpdate`s
+pub const PACE_UPDATE_MS: u64 = 1000;
 
 // Scoring
 pub const SCORE_SINGLE: u32 = 100;
 pub const SCORE_DOUBLE: u32 = 300;
 pub const SCORE_TRIPLE: u32 = 500;
 pub const SCORE_TETRIS: u32 = 800;
+pub const SCORE_TSPIN: u32 = 400;
+pub const SCORE_TSPIN_SINGLE: u32 = 800;
+pub const SCORE_TSPIN_DOUBLE: u32 = 1200;
+pub const SCORE_TSPIN_TRIPLE: u32 = 1600;
+pub const SCORE_TSPIN_MINI: u32 = 100;
+pub const SCORE_TSPIN_MINI_SINGLE: u32 = 200;
+pub const SCORE_TSPIN_MINI_DOUBLE: u32 = 400;
+/// Per row the player soft drops; not multiplied by level
+pub const SCORE_SOFT_DROP: u32 = 1;
+
+/// Goal that ends a run, if any
+#[derive(Clone, Copy, PartialEq, Eq, Debug, Default, Serialize, Deserialize)]
+pub enum GameMode {
+    /// Endless play until the stack tops out
+    #[default]
+    Marathon,
+    /// Clear this many lines as fast as possible
+    Sprint { lines: u32 },
+    /// Score as much as possible in this much play time
+    Ultra { duration_ms: u64 },
+    /// Clear `lines` lines before `pieces` pieces have been placed
+    Puzzle { lines: u32, pieces: u32 },
+}
+
+/// Which way pieces fall, and so which lines clear
+#[derive(Clone, Copy, PartialEq, Eq, Debug, Default, Serialize, Deserialize)]
+pub enum GravityDirection {
+    /// Pieces fall to the floor and full rows clear
+    #[default]
+    Down,
+    /// Sideways novelty: pieces spawn at the right wall, fall to the left
+    /// wall, and full columns clear
+    Left,
+}
+
+impl GravityDirection {
+    /// `(dx, dy)` of one cell of fall
+    pub fn step(self) -> (i16, i16) {
+        match self {
+            GravityDirection::Down => (0, 1),
+            GravityDirection::Left => (-1, 0),
+        }
+    }
+
+    /// `(dx, dy)` of a move-left input, as seen with the board turned so
+    /// that gravity points down. Move-right is the opposite.
+    pub fn left(self) -> (i16, i16) {
+        match self {
+            GravityDirection::Down => (-1, 0),
+            GravityDirection::Left => (0, -1),
+        }
+    }
+}
+
+/// What moved the falling piece down a row
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+enum Descent {
+    SoftDrop,
+    Gravity,
+}
+
+/// Which held piece a hold brings back once the hold queue is full
+#[derive(Clone, Copy, PartialEq, Eq, Debug, Default, Serialize, Deserialize)]
+pub enum HoldOrder {
+    /// The most recently held piece
+    #[default]
+    Lifo,
+    /// The piece held longest ago
+    Fifo,
+}
+
+/// How much of a piece must lock in the hidden buffer rows to top out
+#[derive(Clone, Copy, PartialEq, Eq, Debug, Default, Serialize, Deserialize)]
+pub enum LockOut {
+    /// Any block above the visible board ends the game
+    #[default]
+    Partial,
+    /// Only a piece locking entirely above the visible board ends the game;
+    /// blocks of a partly hidden piece that land in the buffer are lost
+    Full,
+}
+
+/// How far a goal-based mode is from finishing
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum ModeProgress {
+    LinesRemaining(u32),
+    TimeRemainingMs(u64),
+}
+
+/// Rule-set options that vary between game variants
+#[derive(Clone, PartialEq, Eq, Debug, Serialize, Deserialize)]
+pub struct GameConfig {
+    pub mode: GameMode,
+    /// Simulated opponent sending garbage on a timer, run by the game
+    /// alongside gravity (off by default)
+    pub garbage_attack: Option<GarbageAttackConfig>,
+    /// Seeded single-hole garbage rows the board starts (and restarts) with
+    pub cheese: Option<CheeseConfig>,
+    /// Garbage rows rising from the floor on a speeding-up timer; clearing
+    /// lines puts off the next one (off by default)
+    pub rising_floor: Option<RisingFloorConfig>,
+    /// How rotations that collide in place are resolved
+    pub rotation_system: RotationSystem,
+    /// Rotation each standard piece spawns in, indexed like `TetrominoType::ALL`
+    pub spawn_rotations: [usize; 7],
+    /// Entry delay after a line clear: the next piece is spawned but ignores
+    /// input and gravity for this long (0 disables it)
+    pub are_ms: u64,
+    /// Entry delay after every lock, cleared lines or not, the same way
+    /// `are_ms` works; a clear waits for both (0 spawns instantly)
+    pub spawn_delay_ms: u64,
+    /// Highest level that still raises the score multiplier and gravity;
+    /// the level counter itself keeps going (uncapped by default)
+    pub level_cap: Option<u32>,
+    /// Gravity that speeds up with play time to punish stalling: every
+    /// this many milliseconds of play counts as one more level for gravity
+    /// alone, and the faster of that and the real level applies. Also
+    /// limited by `level_cap` (off by default).
+    pub gravity_ramp_ms: Option<u64>,
+    pub gravity: GravityDirection,
+    /// Whether a soft drop that can't descend locks the piece right away.
+    /// When off the piece can still slide until the next gravity tick
+    /// locks it, the same window `sonic_drop` leaves. On by default.
+    pub soft_drop_locks_instantly: bool,
+    /// Award `SCORE_SOFT_DROP` for each row the player soft drops; rows
+    /// gravity pulls the piece down never score. Off by default.
+    pub soft_drop_scoring: bool,
+    /// Pieces the hold queue keeps before a hold swaps one back out;
+    /// 1 is the classic hold slot and 0 disables holding
+    pub hold_capacity: usize,
+    pub hold_order: HoldOrder,
+    /// Flat points added per Tetris already in an unbroken Tetris chain:
+    /// the second in a row earns this once more, the third twice, and so on.
+    /// Any other clear breaks the chain (0, the default, disables it).
+    pub tetris_chain_bonus: u32,
+    /// Give every spawned piece a random color of its own, kept by its
+    /// locked cells (off by default)
+    pub chaos_colors: bool,
+    /// Hidden rows above the visible board that the falling piece may
+    /// reach into, e.g. when rotating right after spawning. A piece that
+    /// locks up there tops out according to `lock_out`. 0 (the default)
+    /// keeps pieces inside the visible rows. Ignored under sideways
+    /// gravity, where the top of the board is a wall.
+    pub buffer_rows: usize,
+    pub lock_out: LockOut,
+}
+
+impl Default for GameConfig {
+    fn default() -> Self {
+        Self {
+            mode: GameMode::default(),
+            garbage_attack: None,
+            cheese: None,
+            rising_floor: None,
+            rotation_system: RotationSystem::default(),
+            spawn_rotations: [0; 7],
+            are_ms: 0,
+            spawn_delay_ms: 0,
+            level_cap: None,
+            gravity_ramp_ms: None,
+            gravity: GravityDirection::default(),
+            soft_drop_locks_instantly: true,
+            soft_drop_scoring: false,
+            hold_capacity: 1,
+            hold_order: HoldOrder::default(),
+            tetris_chain_bonus: 0,
+            chaos_colors: false,
+            buffer_rows: 0,
+            lock_out: LockOut::default(),
+        }
+    }
+}
+
+impl GameConfig {
+    /// Custom pieces always spawn in their first rotation state
+    pub fn spawn_rotation(&self, tetromino_type: TetrominoType) -> usize {
+        tetromino_type
+            .standard_index()
+            .map_or(0, |index| self.spawn_rotations[index])
+    }
+
+    pub fn set_spawn_rotation(&mut self, tetromino_type: TetrominoType, rotation: usize) {
+        if let Some(index) = tetromino_type.standard_index() {
+            self.spawn_rotations[index] = rotation % 4;
+        }
+    }
+}
 
 // ============================================================================
 // Types
 // ============================================================================
 
-#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+#[derive(Clone, Copy, PartialEq, Eq, Debug, Serialize, Deserialize)]
 pub struct Position {
     pub x: i16,
     pub y: i16,
 }
 
-#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+#[derive(Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Debug, Serialize, Deserialize)]
 pub enum TetrominoType {
     I,
     O,
@@ -40,6 +248,8 @@ pub enum TetrominoType {
     Z,
     J,
     L,
+    /// Piece added at runtime through `pieces::register_piece`
+    Custom(u8),
 }
 
 impl TetrominoType {
@@ -87,28 +297,75 @@ impl TetrominoType {
                 vec![(0, 0), (1, 0), (2, 0), (0, 1)],
                 vec![(0, 0), (1, 0), (1, 1), (1, 2)],
             ],
+            // Loaders reject unregistered ids (`pieces::is_known`), so
+            // reaching this without one is a bug in the caller
+            TetrominoType::Custom(id) => {
+                pieces::custom_piece(*id)
+                    .expect("custom piece used before it was registered")
+                    .rotations
+            }
         }
     }
 
+    /// The seven standard pieces, in declaration order
+    pub const ALL: [TetrominoType; 7] = [
+        TetrominoType::I,
+        TetrominoType::O,
+        TetrominoType::T,
+        TetrominoType::S,
+        TetrominoType::Z,
+        TetrominoType::J,
+        TetrominoType::L,
+    ];
+
+    /// The standard piece named by `letter` (`I O T S Z J L`, either case)
+    pub fn from_letter(letter: char) -> Option<Self> {
+        match letter.to_ascii_uppercase() {
+            'I' => Some(TetrominoType::I),
+            'O' => Some(TetrominoType::O),
+            'T' => Some(TetrominoType::T),
+            'S' => Some(TetrominoType::S),
+            'Z' => Some(TetrominoType::Z),
+            'J' => Some(TetrominoType::J),
+            'L' => Some(TetrominoType::L),
+            _ => None,
+        }
+    }
+
+    /// Uppercase letter naming a standard piece, or None for a custom one
+    pub fn letter(self) -> Option<char> {
+        match self {
+            TetrominoType::I => Some('I'),
+            TetrominoType::O => Some('O'),
+            TetrominoType::T => Some('T'),
+            TetrominoType::S => Some('S'),
+            TetrominoType::Z => Some('Z'),
+            TetrominoType::J => Some('J'),
+            TetrominoType::L => Some('L'),
+            TetrominoType::Custom(_) => None,
+        }
+    }
+
+    /// Position in `ALL`, or None for a custom piece
+    pub fn standard_index(self) -> Option<usize> {
+        Self::ALL.iter().position(|&t| t == self)
+    }
+
     fn random() -> Self {
         let mut rng = rand::thread_rng();
-        match rng.gen_range(0..7) {
-            0 => TetrominoType::I,
-            1 => TetrominoType::O,
-            2 => TetrominoType::T,
-            3 => TetrominoType::S,
-            4 => TetrominoType::Z,
-            5 => TetrominoType::J,
-            _ => TetrominoType::L,
-        }
+        Self::ALL[rng.gen_range(0..Self::ALL.len())]
     }
 }
 
-#[derive(Clone)]
+#[derive(Clone, PartialEq, Eq, Debug, Serialize, Deserialize)]
 pub struct Tetromino {
     pub tetromino_type: TetrominoType,
     pub position: Position,
     pub rotation: usize,
+    /// RGB color this piece is drawn and locked in instead of its type's
+    /// (chaos colors); None for the usual color
+    #[serde(default)]
+    pub color: Option<(u8, u8, u8)>,
 }
 
 impl Tetromino {
@@ -120,6 +377,7 @@ impl Tetromino {
                 y: 0,
             },
             rotation: 0,
+            color: None,
         }
     }
 
@@ -128,6 +386,15 @@ impl Tetromino {
             tetromino_type,
             position: Position { x, y },
             rotation: 0,
+            color: None,
+        }
+    }
+
+    /// What this piece leaves in a grid cell
+    pub fn cell_state(&self) -> CellState {
+        match self.color {
+            Some(color) => CellState::Tinted(self.tetromino_type, color),
+            None => CellState::Filled(self.tetromino_type),
         }
     }
 
@@ -143,6 +410,22 @@ impl Tetromino {
             .collect()
     }
 
+    /// Named rotation state, counting `rotation` clockwise turns from spawn
+    pub fn rotation_state(&self) -> RotationState {
+        RotationState::from_index(self.rotation)
+    }
+
+    /// `(min_x, min_y, max_x, max_y)` of the blocks at the current rotation,
+    /// in grid coordinates (inclusive)
+    pub fn bounding_box(&self) -> (i16, i16, i16, i16) {
+        let blocks = self.blocks();
+        let min_x = blocks.iter().map(|b| b.x).min().unwrap_or(self.position.x);
+        let min_y = blocks.iter().map(|b| b.y).min().unwrap_or(self.position.y);
+        let max_x = blocks.iter().map(|b| b.x).max().unwrap_or(self.position.x);
+        let max_y = blocks.iter().map(|b| b.y).max().unwrap_or(self.position.y);
+        (min_x, min_y, max_x, max_y)
+    }
+
     fn rotated(&self, clockwise: bool) -> Self {
         let shapes = self.tetromino_type.shapes();
         let rotation = if clockwise {
@@ -154,6 +437,7 @@ impl Tetromino {
             tetromino_type: self.tetromino_type,
             position: self.position,
             rotation,
+            color: self.color,
         }
     }
 
@@ -165,14 +449,53 @@ impl Tetromino {
                 y: self.position.y + dy,
             },
             rotation: self.rotation,
+            color: self.color,
         }
     }
 }
 
-#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+#[derive(Clone, Copy, PartialEq, Eq, Debug, Serialize, Deserialize)]
 pub enum CellState {
     Empty,
     Filled(TetrominoType),
+    /// Part of a garbage row sent by an opponent; clears like any filled cell
+    Garbage,
+    /// Filled by a piece that had its own color (chaos colors)
+    Tinted(TetrominoType, (u8, u8, u8)),
+}
+
+impl CellState {
+    /// The piece that filled this cell, if any
+    pub fn piece_type(self) -> Option<TetrominoType> {
+        match self {
+            CellState::Filled(tetromino_type) | CellState::Tinted(tetromino_type, _) => {
+                Some(tetromino_type)
+            }
+            CellState::Empty | CellState::Garbage => None,
+        }
+    }
+
+    /// The color a piece brought with it, overriding its type's
+    pub fn color_override(self) -> Option<(u8, u8, u8)> {
+        match self {
+            CellState::Tinted(_, color) => Some(color),
+            _ => None,
+        }
+    }
+}
+
+/// One cell of `Game::render_grid_detailed`, telling the renderer which
+/// layer it came from and the piece that filled it. The color, when
+/// present, replaces the type's own.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum RenderCell {
+    Empty,
+    Locked(TetrominoType, Option<(u8, u8, u8)>),
+    Garbage,
+    /// Part of the falling piece
+    Active(TetrominoType, Option<(u8, u8, u8)>),
+    /// Where the falling piece would land; never drawn over locked cells or the piece
+    Ghost(TetrominoType, Option<(u8, u8, u8)>),
 }
 
 #[derive(Clone, Copy, PartialEq, Eq, Debug)]
@@ -187,14 +510,64 @@ pub enum GameEvent {
     PieceMoved,
     PieceRotated,
     PieceLocked,
+    /// The piece as it landed, pushed just before it locks
+    HardDrop(Tetromino),
+    /// Key presses spent placing the piece that just locked, next to the fewest possible
+    Finesse { optimal: u32, actual: u32 },
+    PieceSpawned(TetrominoType),
+    PieceHeld(TetrominoType),
     LinesCleared(u32),
+    /// Indices of the lines just cleared as they were before the board
+    /// shifted, ascending: rows top to bottom, or columns left to right
+    /// under sideways gravity. Follows the matching `LinesCleared`.
+    LinesClearedAt(Vec<usize>),
+    TSpin { lines: u32, mini: bool },
     LevelUp(u32),
+    /// This run's score just passed the stored high score. Sent at most
+    /// once per run, and not while there is no high score yet.
+    NewHighScore,
+    /// `lines_per_minute` in hundredths of a line, sent after every
+    /// `PACE_UPDATE_MS` of play
+    PaceUpdate(u32),
     Paused,
     Unpaused,
     GameRestarted,
     GameOver,
 }
 
+/// Moves open to the current piece, from `Game::movable`
+#[derive(Clone, Copy, PartialEq, Eq, Debug, Default)]
+pub struct Movability {
+    pub left: bool,
+    pub right: bool,
+    pub down: bool,
+    pub rotate_cw: bool,
+    pub rotate_ccw: bool,
+}
+
+/// A single player action, as recorded in replays
+#[derive(Clone, Copy, PartialEq, Eq, Debug, Serialize, Deserialize)]
+pub enum Input {
+    MoveLeft,
+    MoveRight,
+    SoftDrop,
+    HardDrop,
+    RotateCw,
+    RotateCcw,
+    Hold,
+    TogglePause,
+}
+
+impl Input {
+    /// Whether holding the key down should keep repeating the action.
+    /// Movement and soft drop auto-shift; rotating, holding, hard dropping
+    /// and pausing happen once per press so a held key can't spin the
+    /// piece, drop the next one, or flicker the pause screen.
+    pub fn repeats_when_held(self) -> bool {
+        matches!(self, Input::MoveLeft | Input::MoveRight | Input::SoftDrop)
+    }
+}
+
 // ============================================================================
 // Piece Provider Trait
 // ============================================================================
@@ -203,7 +576,8 @@ pub trait PieceProvider {
     fn next_piece(&mut self) -> TetrominoType;
 }
 
-struct RandomPieceProvider;
+/// Uniformly random pieces from the thread RNG, the default for `Game::new`
+pub struct RandomPieceProvider;
 
 impl PieceProvider for RandomPieceProvider {
     fn next_piece(&mut self) -> TetrominoType {
@@ -230,6 +604,136 @@ impl PieceProvider for SequencePieceProvider {
     }
 }
 
+/// Deals only the allowed types by skipping everything else the wrapped
+/// provider produces, for drilling specific pieces. The wrapped provider
+/// must eventually produce an allowed type.
+pub struct FilteredPieceProvider {
+    inner: Box<dyn PieceProvider>,
+    allowed: Vec<TetrominoType>,
+}
+
+impl FilteredPieceProvider {
+    pub fn new(inner: Box<dyn PieceProvider>, allowed: Vec<TetrominoType>) -> Self {
+        assert!(!allowed.is_empty(), "at least one piece type must be allowed");
+        Self { inner, allowed }
+    }
+}
+
+impl PieceProvider for FilteredPieceProvider {
+    fn next_piece(&mut self) -> TetrominoType {
+        loop {
+            let piece = self.inner.next_piece();
+            if self.allowed.contains(&piece) {
+                return piece;
+            }
+        }
+    }
+}
+
+/// Uniformly random pieces from a seeded RNG, so a seed always deals the
+/// same sequence
+pub struct SeededPieceProvider {
+    rng: StdRng,
+}
+
+impl SeededPieceProvider {
+    pub fn new(seed: u64) -> Self {
+        Self {
+            rng: StdRng::seed_from_u64(seed),
+        }
+    }
+}
+
+impl PieceProvider for SeededPieceProvider {
+    fn next_piece(&mut self) -> TetrominoType {
+        TetrominoType::ALL[self.rng.gen_range(0..TetrominoType::ALL.len())]
+    }
+}
+
+/// Random pieces from a seeded RNG, biased toward types that have not
+/// come up for a while. Each type is weighted by `(draws since seen + 1)`
+/// raised to `strength * WEIGHT_EXPONENT`: strength 0 is uniform and 1 all
+/// but rules out repeats, close to a 7-bag without its fixed cycle.
+pub struct WeightedRandomProvider {
+    rng: StdRng,
+    strength: f64,
+    /// Draws since each type was last dealt, indexed like `TetrominoType::ALL`
+    since_seen: [u32; 7],
+}
+
+impl WeightedRandomProvider {
+    /// Exponent applied at full strength
+    pub const WEIGHT_EXPONENT: f64 = 4.0;
+
+    /// `strength` is clamped to 0..=1
+    pub fn new(strength: f64, seed: u64) -> Self {
+        Self {
+            rng: StdRng::seed_from_u64(seed),
+            strength: strength.clamp(0.0, 1.0),
+            since_seen: [0; 7],
+        }
+    }
+}
+
+impl PieceProvider for WeightedRandomProvider {
+    fn next_piece(&mut self) -> TetrominoType {
+        let exponent = self.strength * Self::WEIGHT_EXPONENT;
+        let weights = self.since_seen.map(|since| (since as f64 + 1.0).powf(exponent));
+        let mut pick = self.rng.gen::<f64>() * weights.iter().sum::<f64>();
+        let index = weights
+            .iter()
+            .position(|&weight| {
+                pick -= weight;
+                pick < 0.0
+            })
+            .unwrap_or(weights.len() - 1);
+
+        for since in &mut self.since_seen {
+            *since += 1;
+        }
+        self.since_seen[index] = 0;
+        TetrominoType::ALL[index]
+    }
+}
+
+/// Seed for a daily challenge on the given date (month and day count from
+/// 1), so everyone playing that day is dealt the same pieces
+pub fn seed_for_date(year: i32, month: u32, day: u32) -> u64 {
+    // SplitMix64 finalizer over the date packed as YYYYMMDD, so neighboring
+    // dates give unrelated seeds
+    let mut z = (year as i64 * 10_000 + month as i64 * 100 + day as i64) as u64;
+    z = z.wrapping_add(0x9e37_79b9_7f4a_7c15);
+    z = (z ^ (z >> 30)).wrapping_mul(0xbf58_476d_1ce4_e5b9);
+    z = (z ^ (z >> 27)).wrapping_mul(0x94d0_49bb_1331_11eb);
+    z ^ (z >> 31)
+}
+
+/// `seed_for_date` for today's UTC date
+pub fn daily_seed() -> u64 {
+    let secs = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map_or(0, |d| d.as_secs());
+    let (year, month, day) = utc_date(secs);
+    seed_for_date(year, month, day)
+}
+
+/// `(year, month, day)` in UTC of a Unix timestamp, using Howard Hinnant's
+/// days-to-civil conversion
+pub fn utc_date(unix_secs: u64) -> (i32, u32, u32) {
+    let z = (unix_secs / 86_400) as i64 + 719_468;
+    let era = z.div_euclid(146_097);
+    let day_of_era = z.rem_euclid(146_097);
+    let year_of_era =
+        (day_of_era - day_of_era / 1_460 + day_of_era / 36_524 - day_of_era / 146_096) / 365;
+    let day_of_year = day_of_era - (365 * year_of_era + year_of_era / 4 - year_of_era / 100);
+    // Months counted from March, so the leap day falls at the end
+    let march_month = (5 * day_of_year + 2) / 153;
+    let day = (day_of_year - (153 * march_month + 2) / 5 + 1) as u32;
+    let month = if march_month < 10 { march_month + 3 } else { march_month - 9 } as u32;
+    let year = (year_of_era + era * 400 + i64::from(month <= 2)) as i32;
+    (year, month, day)
+}
+
 // ============================================================================
 // Game
 // ============================================================================
@@ -238,13 +742,41 @@ pub struct Game {
     pub grid: Vec<Vec<CellState>>,
     pub current_piece: Tetromino,
     pub preview_queue: VecDeque<TetrominoType>,
+    /// Held pieces, oldest first, at most `config.hold_capacity` of them
+    pub held_pieces: VecDeque<TetrominoType>,
+    /// False once a piece has been held, until the next piece locks
+    pub can_hold: bool,
     pub score: u32,
     pub lines_cleared: u32,
     pub level: u32,
     pub high_score: u32,
     pub state: GameState,
+    pub config: GameConfig,
     piece_provider: Box<dyn PieceProvider>,
     events: Vec<GameEvent>,
+    last_kick: Option<(i16, i16)>,
+    last_move_was_rotation: bool,
+    piece_inputs: u32,
+    gravity_timer_ms: u64,
+    are_remaining_ms: u64,
+    elapsed_ms: u64,
+    pieces_placed: BTreeMap<TetrominoType, u32>,
+    combo: u32,
+    max_combo: u32,
+    tetrises: u32,
+    /// Tetrises since the last clear of any other kind
+    consecutive_tetris: u32,
+    /// Locks in a row that cleared nothing
+    pieces_since_clear: u32,
+    /// The last clear was a Tetris or a T-spin that cleared lines
+    back_to_back: bool,
+    tspins: u32,
+    rising_floor: Option<RisingFloor>,
+    garbage_attack: Option<GarbageScheduler>,
+    last_locked: Option<Tetromino>,
+    /// Where the high score is loaded from and saved to. None keeps it in
+    /// memory only, so the game never writes a high score to disk.
+    high_score_file: Option<PathBuf>,
 }
 
 // ============================================================================
@@ -253,15 +785,59 @@ pub struct Game {
 
 const HIGH_SCORE_FILE: &str = "highscore.txt";
 
-fn load_high_score() -> u32 {
-    std::fs::read_to_string(HIGH_SCORE_FILE)
+
+fn load_high_score(path: &Path) -> u32 {
+    std::fs::read_to_string(path)
         .ok()
         .and_then(|s| s.trim().parse().ok())
         .unwrap_or(0)
 }
 
-fn save_high_score(score: u32) {
-    let _ = std::fs::write(HIGH_SCORE_FILE, score.to_string());
+fn save_high_score(path: &Path, score: u32) {
+    let _ = std::fs::write(path, score.to_string());
+}
+
+/// Empty grid, or one topped up with the configured cheese rows
+fn starting_grid(config: &GameConfig) -> Vec<Vec<CellState>> {
+    let mut grid = vec![vec![CellState::Empty; GRID_WIDTH]; GRID_HEIGHT];
+    if let Some(cheese) = config.cheese {
+        let holes = garbage::cheese_holes(cheese);
+        for (row, hole) in grid.iter_mut().rev().zip(holes.into_iter().rev()) {
+            *row = garbage_row(hole);
+        }
+    }
+    grid
+}
+
+/// A full garbage row except for a hole at column `hole`
+fn garbage_row(hole: usize) -> Vec<CellState> {
+    (0..GRID_WIDTH)
+        .map(|x| if x == hole { CellState::Empty } else { CellState::Garbage })
+        .collect()
+}
+
+/// Bright enough to stand out against a dark board
+fn random_piece_color() -> (u8, u8, u8) {
+    let mut rng = rand::thread_rng();
+    (rng.gen_range(64..=255), rng.gen_range(64..=255), rng.gen_range(64..=255))
+}
+
+/// A new piece at the spawn point in the configured spawn rotation. With
+/// leftward gravity the spawn point is against the right wall, halfway down.
+fn spawn_tetromino(config: &GameConfig, tetromino_type: TetrominoType) -> Tetromino {
+    let mut piece = Tetromino::new(tetromino_type);
+    piece.rotation = config.spawn_rotation(tetromino_type);
+    if config.chaos_colors {
+        piece.color = Some(random_piece_color());
+    }
+    if config.gravity == GravityDirection::Left {
+        let (_, _, max_x, _) = piece.bounding_box();
+        piece.position = Position {
+            x: GRID_WIDTH as i16 - (max_x - piece.position.x + 1),
+            y: GRID_HEIGHT as i16 / 2 - 1,
+        };
+    }
+    piece
 }
 
 impl Game {
@@ -269,8 +845,12 @@ impl Game {
         Self::with_provider(Box::new(RandomPieceProvider))
     }
 
-    pub fn with_provider(mut provider: Box<dyn PieceProvider>) -> Self {
-        let grid = vec![vec![CellState::Empty; GRID_WIDTH]; GRID_HEIGHT];
+    pub fn with_provider(provider: Box<dyn PieceProvider>) -> Self {
+        Self::with_config(GameConfig::default(), provider)
+    }
+
+    pub fn with_config(config: GameConfig, mut provider: Box<dyn PieceProvider>) -> Self {
+        let grid = starting_grid(&config);
 
         let mut preview_queue = VecDeque::new();
         for _ in 0..PREVIEW_COUNT {
@@ -278,39 +858,167 @@ impl Game {
         }
 
         let current_type = provider.next_piece();
-        let current_piece = Tetromino::new(current_type);
+        let current_piece = spawn_tetromino(&config, current_type);
+        let rising_floor = config.rising_floor.map(RisingFloor::new);
+        let garbage_attack = GarbageScheduler::from_config(&config);
 
         Self {
             grid,
             current_piece,
             preview_queue,
+            held_pieces: VecDeque::new(),
+            can_hold: true,
             score: 0,
             lines_cleared: 0,
             level: 1,
-            high_score: load_high_score(),
+            high_score: load_high_score(Path::new(HIGH_SCORE_FILE)),
             state: GameState::Playing,
+            config,
             piece_provider: provider,
             events: Vec::new(),
+            last_kick: None,
+            last_move_was_rotation: false,
+            piece_inputs: 0,
+            gravity_timer_ms: 0,
+            are_remaining_ms: 0,
+            elapsed_ms: 0,
+            pieces_placed: BTreeMap::new(),
+            combo: 0,
+            max_combo: 0,
+            tetrises: 0,
+            consecutive_tetris: 0,
+            pieces_since_clear: 0,
+            back_to_back: false,
+            tspins: 0,
+            rising_floor,
+            garbage_attack,
+            last_locked: None,
+            high_score_file: Some(PathBuf::from(HIGH_SCORE_FILE)),
+        }
+    }
+
+    /// Starts a puzzle: its board, its pieces dealt in order (repeating if
+    /// the goal allows more pieces than the sequence has) and its goal as
+    /// the game mode
+    pub fn from_puzzle(puzzle: &Puzzle) -> Result<Self, PuzzleError> {
+        let grid = board_from_string(&puzzle.board)?;
+        if puzzle.sequence.is_empty() {
+            return Err(PuzzleError::EmptySequence);
         }
+        if let Some(&piece) = puzzle.sequence.iter().find(|&&piece| !pieces::is_known(piece)) {
+            return Err(PuzzleError::UnknownPiece(piece));
+        }
+
+        let config = GameConfig {
+            mode: GameMode::Puzzle {
+                lines: puzzle.goal.lines,
+                pieces: puzzle.goal.pieces,
+            },
+            ..GameConfig::default()
+        };
+        let deal = || Box::new(SequencePieceProvider::new(puzzle.sequence.clone()));
+        let mut game = Self::with_config(config, deal());
+        // Deal again from the top so the first piece of the sequence is in play
+        game.piece_provider = deal();
+        game.grid = grid;
+        game.set_preview_queue(Vec::new());
+        game.spawn_next_piece();
+        game.events.clear();
+        Ok(game)
+    }
+
+    /// Keeps the high score in `path` instead of `highscore.txt` in the
+    /// working directory, loading it from there right away
+    pub fn with_high_score_file(mut self, path: impl Into<PathBuf>) -> Self {
+        let path = path.into();
+        self.high_score = load_high_score(&path);
+        self.high_score_file = Some(path);
+        self
+    }
+
+    /// Keeps the high score in memory only, starting from zero, so nothing
+    /// this game scores is ever saved. For games that aren't the player's
+    /// own, such as replays.
+    pub fn without_high_score_file(mut self) -> Self {
+        self.high_score_file = None;
+        self.high_score = 0;
+        self
+    }
+
+    /// Swaps where upcoming pieces come from. The preview queue is kept, so
+    /// call it before `restart` to deal the next run from the new provider.
+    pub fn set_piece_provider(&mut self, provider: Box<dyn PieceProvider>) {
+        self.piece_provider = provider;
     }
 
     pub fn with_grid(grid: Vec<Vec<CellState>>, current_piece: Tetromino) -> Self {
-        let mut preview_queue = VecDeque::new();
-        for _ in 0..PREVIEW_COUNT {
-            preview_queue.push_back(TetrominoType::random());
+        let mut game = Self::new();
+        game.grid = grid;
+        game.current_piece = current_piece;
+        game
+    }
+
+    /// Rebuilds a grid from a copy of the terminal board, as pasted into bug
+    /// reports. A board row is any line with a `│` or `|` border around
+    /// exactly `GRID_WIDTH` two-column cells; other lines and the hold,
+    /// preview and info panels beside the board are skipped. `▓▓` is
+    /// garbage, blank, trail (`░░`), outlined ghost (`[]`) and held-piece
+    /// ghost (`··`) cells are empty, and any other cell is filled as an I
+    /// since colors are lost.
+    /// Rows are aligned to the bottom as in `board_from_string`.
+    pub fn board_from_rendered(text: &str) -> Result<Vec<Vec<CellState>>, BoardParseError> {
+        let rows: Vec<Vec<CellState>> = text
+            .lines()
+            .filter_map(|line| {
+                let chars: Vec<char> = line.chars().collect();
+                let segments: Vec<&[char]> = chars.split(|c| *c == '│' || *c == '|').collect();
+                // Text before the first border and after the last isn't enclosed
+                let inner = segments.get(1..segments.len().saturating_sub(1))?;
+                let cells = inner.iter().find(|segment| segment.len() == GRID_WIDTH * 2)?;
+                Some(
+                    cells
+                        .chunks(2)
+                        .map(|cell| match cell {
+                            [' ', ' '] | ['░', '░'] | ['[', ']'] | ['·', '·'] => {
+                                CellState::Empty
+                            }
+                            ['▓', '▓'] => CellState::Garbage,
+                            _ => CellState::Filled(TetrominoType::I),
+                        })
+                        .collect(),
+                )
+            })
+            .collect();
+        if rows.len() > GRID_HEIGHT {
+            return Err(BoardParseError::TooManyRows(rows.len()));
         }
 
-        Self {
-            grid,
-            current_piece,
-            preview_queue,
-            score: 0,
-            lines_cleared: 0,
-            level: 1,
-            high_score: load_high_score(),
-            state: GameState::Playing,
-            piece_provider: Box::new(RandomPieceProvider),
-            events: Vec::new(),
+        let mut grid = vec![vec![CellState::Empty; GRID_WIDTH]; GRID_HEIGHT - rows.len()];
+        grid.extend(rows);
+        Ok(grid)
+    }
+
+    /// Cells a `tetromino_type` piece would cover at `(x, y)` in
+    /// `rotation`, the same as `Tetromino::blocks` without building one
+    pub fn piece_cells_at(
+        tetromino_type: TetrominoType,
+        x: i16,
+        y: i16,
+        rotation: usize,
+    ) -> Vec<Position> {
+        let shapes = tetromino_type.shapes();
+        shapes[rotation % shapes.len()]
+            .iter()
+            .map(|(dx, dy)| Position { x: x + dx, y: y + dy })
+            .collect()
+    }
+
+    /// Hidden rows above the board in play. They sit on the spawn side, so
+    /// only downward gravity has them; sideways the top is a wall.
+    fn buffer_rows(&self) -> usize {
+        match self.config.gravity {
+            GravityDirection::Down => self.config.buffer_rows,
+            GravityDirection::Left => 0,
         }
     }
 
@@ -320,9 +1028,13 @@ impl Game {
             if block.x < 0 || block.x >= GRID_WIDTH as i16 {
                 return false;
             }
-            if block.y < 0 || block.y >= GRID_HEIGHT as i16 {
+            if block.y < -(self.buffer_rows() as i16) || block.y >= GRID_HEIGHT as i16 {
                 return false;
             }
+            // The buffer rows above the board are always empty
+            if block.y < 0 {
+                continue;
+            }
             // Check collision with placed blocks
             if self.grid[block.y as usize][block.x as usize] != CellState::Empty {
                 return false;
@@ -332,38 +1044,100 @@ impl Game {
     }
 
     fn lock_piece(&mut self) {
-        let piece_type = self.current_piece.tetromino_type;
+        let cell = self.current_piece.cell_state();
         for block in self.current_piece.blocks() {
             if block.y >= 0 && block.y < GRID_HEIGHT as i16 {
-                self.grid[block.y as usize][block.x as usize] = CellState::Filled(piece_type);
+                self.grid[block.y as usize][block.x as usize] = cell;
             }
         }
+        self.last_locked = Some(self.current_piece.clone());
         self.events.push(GameEvent::PieceLocked);
     }
 
-    pub fn clear_lines(&mut self) -> u32 {
-        let mut cleared_count = 0;
-        let mut y = 0;
+    /// The piece most recently locked into the grid, where it locked
+    pub fn last_locked(&self) -> Option<&Tetromino> {
+        self.last_locked.as_ref()
+    }
 
-        while y < GRID_HEIGHT {
-            if self.grid[y].iter().all(|cell| *cell != CellState::Empty) {
-                self.grid.remove(y);
-                self.grid.insert(0, vec![CellState::Empty; GRID_WIDTH]);
-                cleared_count += 1;
-                // Don't increment y - the next row has shifted into this position
-            } else {
-                y += 1;
-            }
-        }
+    /// Removes full lines (rows, or columns under leftward gravity) and
+    /// returns how many were cleared
+    pub fn clear_lines(&mut self) -> u32 {
+        let cleared = match self.config.gravity {
+            GravityDirection::Down => self.clear_rows(),
+            GravityDirection::Left => self.clear_columns(),
+        };
+        let cleared_count = cleared.len() as u32;
 
         if cleared_count > 0 {
             self.events.push(GameEvent::LinesCleared(cleared_count));
+            self.events.push(GameEvent::LinesClearedAt(cleared));
         }
 
         cleared_count
     }
 
-    pub fn add_score(&mut self, lines: u32) {
+    /// Removes every full row and returns their indices, top to bottom,
+    /// as they were before anything shifted
+    fn clear_rows(&mut self) -> Vec<usize> {
+        let full_rows: Vec<usize> = (0..GRID_HEIGHT)
+            .filter(|&y| self.grid[y].iter().all(|cell| *cell != CellState::Empty))
+            .collect();
+
+        // Bottom-up, so removing a row leaves the indices above it valid
+        for &y in full_rows.iter().rev() {
+            self.grid.remove(y);
+        }
+        for _ in 0..full_rows.len() {
+            self.grid.insert(0, vec![CellState::Empty; GRID_WIDTH]);
+        }
+
+        full_rows
+    }
+
+    /// Column version of `clear_rows`: everything right of a full column
+    /// shifts one cell left, toward the wall gravity pulls to
+    fn clear_columns(&mut self) -> Vec<usize> {
+        let full_columns: Vec<usize> = (0..GRID_WIDTH)
+            .filter(|&x| self.grid.iter().all(|row| row[x] != CellState::Empty))
+            .collect();
+
+        for &x in full_columns.iter().rev() {
+            for row in &mut self.grid {
+                row.remove(x);
+                row.push(CellState::Empty);
+            }
+        }
+
+        full_columns
+    }
+
+    /// Pushes one garbage row per entry onto the bottom of the stack, each
+    /// with a single hole at the given column. A falling piece the stack
+    /// rises into is lifted along with it; the game tops out if the piece
+    /// is lifted past the buffer rows or locked blocks are pushed over the
+    /// top.
+    /// The ghost is derived from the board, so it follows immediately.
+    pub fn add_garbage_lines(&mut self, hole_columns: &[usize]) {
+        let mut topped_out = false;
+        for &hole in hole_columns {
+            let top_row = self.grid.remove(0);
+            topped_out |= top_row.iter().any(|cell| *cell != CellState::Empty);
+            self.grid.push(garbage_row(hole));
+
+            if !self.is_valid_position(&self.current_piece) {
+                self.current_piece = self.current_piece.moved(0, -1);
+            }
+        }
+
+        topped_out |= !self.is_valid_position(&self.current_piece);
+        if topped_out && self.state != GameState::GameOver {
+            self.end_game();
+        }
+    }
+
+    /// Points `add_score` would award for clearing `lines` right now. Every
+    /// score calculation saturates at `u32::MAX` rather than overflowing.
+    pub fn score_for_lines(&self, lines: u32) -> u32 {
         let base_score = match lines {
             1 => SCORE_SINGLE,
             2 => SCORE_DOUBLE,
@@ -371,7 +1145,107 @@ impl Game {
             4 => SCORE_TETRIS,
             _ => 0,
         };
-        self.score += base_score * self.level;
+        let score = base_score.saturating_mul(self.effective_level());
+        if lines == 4 {
+            // Each Tetris in an unbroken run pays the chain bonus once more
+            let chain_bonus = self.config.tetris_chain_bonus.saturating_mul(self.consecutive_tetris);
+            score.saturating_add(chain_bonus)
+        } else {
+            score
+        }
+    }
+
+    /// Points a T-spin clearing `lines` is worth at the current level
+    pub fn score_for_tspin(&self, lines: u32, mini: bool) -> u32 {
+        let base_score = match (mini, lines) {
+            (false, 0) => SCORE_TSPIN,
+            (false, 1) => SCORE_TSPIN_SINGLE,
+            (false, 2) => SCORE_TSPIN_DOUBLE,
+            (false, _) => SCORE_TSPIN_TRIPLE,
+            (true, 0) => SCORE_TSPIN_MINI,
+            (true, 1) => SCORE_TSPIN_MINI_SINGLE,
+            (true, _) => SCORE_TSPIN_MINI_DOUBLE,
+        };
+        base_score.saturating_mul(self.effective_level())
+    }
+
+    /// Level used for the score multiplier and gravity, limited by `level_cap`
+    pub fn effective_level(&self) -> u32 {
+        self.capped_level(self.level)
+    }
+
+    fn capped_level(&self, level: u32) -> u32 {
+        self.config.level_cap.map_or(level, |cap| level.min(cap))
+    }
+
+    /// Level gravity runs at: `effective_level`, or the level play time
+    /// has reached under `gravity_ramp_ms` if that is higher
+    pub fn gravity_level(&self) -> u32 {
+        let time_level = match self.config.gravity_ramp_ms {
+            Some(ramp_ms) if ramp_ms > 0 => {
+                let steps = u32::try_from(self.elapsed_ms / ramp_ms).unwrap_or(u32::MAX);
+                self.capped_level(steps.saturating_add(1))
+            }
+            _ => 1,
+        };
+        self.effective_level().max(time_level)
+    }
+
+    pub fn add_score(&mut self, lines: u32) {
+        self.award(self.score_for_lines(lines));
+        if lines == 4 {
+            self.consecutive_tetris += 1;
+        } else if lines > 0 {
+            self.consecutive_tetris = 0;
+        }
+        self.add_lines(lines);
+    }
+
+    fn add_tspin_score(&mut self, lines: u32, mini: bool) {
+        self.award(self.score_for_tspin(lines, mini));
+        if lines > 0 {
+            self.consecutive_tetris = 0;
+        }
+        self.add_lines(lines);
+    }
+
+    /// Adds `points`, announcing the moment the run passes the stored high
+    /// score. The high score only changes between runs, so that crossing
+    /// happens once.
+    fn award(&mut self, points: u32) {
+        let before = self.score;
+        self.score = self.score.saturating_add(points);
+        if self.high_score > 0 && before <= self.high_score && self.score > self.high_score {
+            self.events.push(GameEvent::NewHighScore);
+        }
+    }
+
+    /// Pieces locked since the last one that cleared a line (or since the
+    /// start), for drought stats
+    pub fn pieces_since_clear(&self) -> u32 {
+        self.pieces_since_clear
+    }
+
+    /// Guideline combo counter: -1 while the last lock cleared nothing, 0
+    /// after the first clearing lock of a streak, and one more for each
+    /// clearing lock after that
+    pub fn combo(&self) -> i32 {
+        self.combo as i32 - 1
+    }
+
+    /// Whether the last line clear was a Tetris or a T-spin clear, so the
+    /// next one of those would be back-to-back. Locks that clear nothing
+    /// keep it; any other clear ends it.
+    pub fn back_to_back(&self) -> bool {
+        self.back_to_back
+    }
+
+    /// Length of the current unbroken chain of Tetrises
+    pub fn consecutive_tetris(&self) -> u32 {
+        self.consecutive_tetris
+    }
+
+    fn add_lines(&mut self, lines: u32) {
         self.lines_cleared += lines;
 
         // Level up
@@ -382,108 +1256,532 @@ impl Game {
         }
     }
 
+    /// Jumps to `level`, for picking a speed mid-game. The level never drops
+    /// below what the lines cleared so far have earned, and it keeps rising
+    /// with further clears once those catch up.
+    pub fn set_level(&mut self, level: u32) {
+        let earned = self.lines_cleared / LINES_PER_LEVEL + 1;
+        self.level = level.max(earned);
+    }
+
+    /// Replaces the upcoming pieces. Extra pieces past `PREVIEW_COUNT` are
+    /// dropped and a short queue is topped up from the piece provider.
+    pub fn set_preview_queue(&mut self, pieces: Vec<TetrominoType>) {
+        self.preview_queue = pieces.into_iter().take(PREVIEW_COUNT).collect();
+        while self.preview_queue.len() < PREVIEW_COUNT {
+            self.preview_queue.push_back(self.piece_provider.next_piece());
+        }
+    }
+
+    /// The next `count` pieces in spawn order. Asking for more than the queue
+    /// holds (`PREVIEW_COUNT`) returns the whole queue.
+    pub fn preview(&self, count: usize) -> Vec<TetrominoType> {
+        self.preview_queue.iter().take(count).copied().collect()
+    }
+
+    /// The piece `spawn_next_piece` would bring into play, without touching
+    /// the queue
+    pub fn peek_spawn(&self) -> TetrominoType {
+        self.preview_queue
+            .front()
+            .copied()
+            .expect("preview queue is refilled on every spawn")
+    }
+
     pub fn spawn_next_piece(&mut self) {
         // Get next piece from queue
         let next_type = self.preview_queue.pop_front().unwrap_or_else(TetrominoType::random);
         self.preview_queue.push_back(self.piece_provider.next_piece());
 
-        self.current_piece = Tetromino::new(next_type);
+        self.spawn_piece(next_type);
+    }
+
+    /// Puts a fresh piece of `tetromino_type` at the spawn point, ending the
+    /// game if it does not fit
+    fn spawn_piece(&mut self, tetromino_type: TetrominoType) {
+        self.current_piece = spawn_tetromino(&self.config, tetromino_type);
+        self.last_kick = None;
+        self.last_move_was_rotation = false;
+        self.piece_inputs = 0;
 
         // Check if new piece can be placed
         if !self.is_valid_position(&self.current_piece) {
-            self.state = GameState::GameOver;
-            self.events.push(GameEvent::GameOver);
-
-            // Update and save high score if beaten
-            if self.score > self.high_score {
-                self.high_score = self.score;
-                save_high_score(self.high_score);
-            }
+            self.end_game();
+        } else {
+            self.events.push(GameEvent::PieceSpawned(tetromino_type));
         }
     }
 
-    pub fn move_piece(&mut self, dx: i16, dy: i16) -> bool {
-        if self.state != GameState::Playing {
+    /// Moves the current piece into the hold queue. Until the queue is full
+    /// the next piece comes from the preview; after that a held piece is
+    /// swapped back out, chosen by `config.hold_order`. Allowed once per
+    /// piece: `can_hold` comes back when the next piece locks.
+    pub fn hold_piece(&mut self) -> bool {
+        if !self.is_controllable() || !self.can_hold || self.config.hold_capacity == 0 {
             return false;
         }
-        let moved = self.current_piece.moved(dx, dy);
-        if self.is_valid_position(&moved) {
-            self.current_piece = moved;
-            self.events.push(GameEvent::PieceMoved);
-            true
+
+        let current_type = self.current_piece.tetromino_type;
+        self.events.push(GameEvent::PieceHeld(current_type));
+        self.can_hold = false;
+        let released = if self.held_pieces.len() < self.config.hold_capacity {
+            None
         } else {
-            false
+            match self.config.hold_order {
+                HoldOrder::Lifo => self.held_pieces.pop_back(),
+                HoldOrder::Fifo => self.held_pieces.pop_front(),
+            }
+        };
+        self.held_pieces.push_back(current_type);
+        match released {
+            Some(held_type) => self.spawn_piece(held_type),
+            None => self.spawn_next_piece(),
+        }
+        true
+    }
+
+    /// The held piece next in line to come back out, going by
+    /// `config.hold_order`. With the classic single slot, the held piece.
+    pub fn held_piece(&self) -> Option<TetrominoType> {
+        match self.config.hold_order {
+            HoldOrder::Lifo => self.held_pieces.back().copied(),
+            HoldOrder::Fifo => self.held_pieces.front().copied(),
+        }
+    }
+
+    pub fn move_piece(&mut self, dx: i16, dy: i16) -> bool {
+        if !self.is_controllable() {
+            return false;
+        }
+        if dx != 0 {
+            self.piece_inputs += 1;
+        }
+        let moved = self.current_piece.moved(dx, dy);
+        if self.is_valid_position(&moved) {
+            self.current_piece = moved;
+            self.last_move_was_rotation = false;
+            self.events.push(GameEvent::PieceMoved);
+            true
+        } else {
+            false
         }
     }
 
     pub fn rotate_piece(&mut self, clockwise: bool) -> bool {
-        if self.state != GameState::Playing {
+        if !self.is_controllable() {
             return false;
         }
-        let rotated = self.current_piece.rotated(clockwise);
-        if self.is_valid_position(&rotated) {
-            self.current_piece = rotated;
-            self.events.push(GameEvent::PieceRotated);
-            return true;
-        }
-
-        // Wall kick attempts
-        let kicks = [(1, 0), (-1, 0), (0, -1), (2, 0), (-2, 0)];
-        for (dx, dy) in kicks {
-            let kicked = Tetromino {
-                position: Position {
-                    x: rotated.position.x + dx,
-                    y: rotated.position.y + dy,
-                },
-                ..rotated.clone()
-            };
-            if self.is_valid_position(&kicked) {
+        self.piece_inputs += 1;
+        match self.kicked_rotation(clockwise) {
+            Some((kicked, kick)) => {
                 self.current_piece = kicked;
+                self.last_kick = kick;
+                self.last_move_was_rotation = true;
                 self.events.push(GameEvent::PieceRotated);
-                return true;
+                true
             }
+            None => false,
+        }
+    }
+
+    /// Where rotating the current piece would put it, and the kick used (if
+    /// not the in-place rotation), or `None` if every kick collides
+    fn kicked_rotation(&self, clockwise: bool) -> Option<(Tetromino, Option<(i16, i16)>)> {
+        let piece_type = self.current_piece.tetromino_type;
+        let from = self.current_piece.rotation;
+        let rotated = self.current_piece.rotated(clockwise);
+
+        // SRS rotates around the bounding box center, which shifts the
+        // top-left anchored shapes; the classic system rotates in place
+        let (shift, kicks): ((i16, i16), &[(i16, i16)]) = match self.config.rotation_system {
+            RotationSystem::None => ((0, 0), &CLASSIC_KICKS[..1]),
+            RotationSystem::Classic => ((0, 0), &CLASSIC_KICKS),
+            RotationSystem::Srs => {
+                let (from_x, from_y) = srs_origin(piece_type, from);
+                let (to_x, to_y) = srs_origin(piece_type, rotated.rotation);
+                ((to_x - from_x, to_y - from_y), srs_kicks(piece_type, from, rotated.rotation))
+            }
+        };
+
+        // Try the in-place rotation first, then each wall kick
+        kicks.iter().enumerate().find_map(|(i, &(dx, dy))| {
+            let kicked = rotated.moved(shift.0 + dx, shift.1 + dy);
+            let kick = if i == 0 { None } else { Some((dx, dy)) };
+            self.is_valid_position(&kicked).then_some((kicked, kick))
+        })
+    }
+
+    /// Which moves the current piece could make right now, checked on trial
+    /// copies without changing anything. Directions follow the inputs:
+    /// `down` is the way gravity pulls and left/right are `Input::MoveLeft`
+    /// and `Input::MoveRight`. Rotations include wall kicks.
+    pub fn movable(&self) -> Movability {
+        let (left_x, left_y) = self.config.gravity.left();
+        let (down_x, down_y) = self.config.gravity.step();
+        let fits = |dx, dy| self.is_valid_position(&self.current_piece.moved(dx, dy));
+        Movability {
+            left: fits(left_x, left_y),
+            right: fits(-left_x, -left_y),
+            down: fits(down_x, down_y),
+            rotate_cw: self.kicked_rotation(true).is_some(),
+            rotate_ccw: self.kicked_rotation(false).is_some(),
+        }
+    }
+
+    /// Checks whether locking the current piece now counts as a T-spin using
+    /// the three-corner rule. Returns `Some(mini)` for a T-spin.
+    fn detect_tspin(&self) -> Option<bool> {
+        let piece = &self.current_piece;
+        if piece.tetromino_type != TetrominoType::T || !self.last_move_was_rotation {
+            return None;
         }
-        false
+
+        // The T's center sits at (1, 1) of its SRS box
+        let (origin_x, origin_y) = srs_origin(TetrominoType::T, piece.rotation);
+        let cx = piece.position.x - origin_x + 1;
+        let cy = piece.position.y - origin_y + 1;
+
+        // Walls and floor count as occupied corners
+        let occupied = |dx: i16, dy: i16| {
+            let (x, y) = (cx + dx, cy + dy);
+            x < 0
+                || x >= GRID_WIDTH as i16
+                || y < 0
+                || y >= GRID_HEIGHT as i16
+                || self.grid[y as usize][x as usize] != CellState::Empty
+        };
+
+        let corners = [(-1, -1), (1, -1), (-1, 1), (1, 1)];
+        if corners.iter().filter(|(dx, dy)| occupied(*dx, *dy)).count() < 3 {
+            return None;
+        }
+
+        // The two corners on the side the T points towards
+        let front = match piece.rotation % 4 {
+            0 => [(-1, -1), (1, -1)],
+            1 => [(1, -1), (1, 1)],
+            2 => [(-1, 1), (1, 1)],
+            _ => [(-1, -1), (-1, 1)],
+        };
+        let front_filled = front.iter().all(|(dx, dy)| occupied(*dx, *dy));
+
+        // The last SRS kick test (one across, two down/up) always upgrades a
+        // mini to a full T-spin, which is what makes the T-spin triple work
+        let long_kick = matches!(self.last_kick, Some((dx, dy)) if dx.abs() == 1 && dy.abs() == 2);
+
+        Some(!(front_filled || long_kick))
     }
 
     pub fn hard_drop(&mut self) {
-        if self.state != GameState::Playing {
+        if !self.is_controllable() {
             return;
         }
-        while self.move_piece(0, 1) {}
+        let (dx, dy) = self.config.gravity.step();
+        while self.move_piece(dx, dy) {}
         // Remove the PieceMoved events from the hard drop moves (optional, but cleaner)
         self.events.retain(|e| *e != GameEvent::PieceMoved);
+        self.events.push(GameEvent::HardDrop(self.current_piece.clone()));
         self.lock_and_spawn();
     }
 
+    /// Drops the piece straight to its ghost position without locking it, so
+    /// it can still slide or rotate. Emits one `PieceMoved` if it fell at
+    /// all. There is no lock delay, so the next gravity tick locks it.
+    pub fn sonic_drop(&mut self) -> bool {
+        if !self.is_controllable() {
+            return false;
+        }
+        let landed = self.ghost_piece();
+        if landed == self.current_piece {
+            return false;
+        }
+        self.current_piece = landed;
+        self.last_move_was_rotation = false;
+        self.events.push(GameEvent::PieceMoved);
+        true
+    }
+
     pub fn soft_drop(&mut self) {
-        if self.state != GameState::Playing {
+        if !self.is_controllable() {
             return;
         }
-        if !self.move_piece(0, 1) {
+        if !self.descend(Descent::SoftDrop) && self.config.soft_drop_locks_instantly {
             self.lock_and_spawn();
         }
     }
 
+    /// Moves the piece one row along gravity, returning whether it moved.
+    /// With `soft_drop_scoring` on, only the player's soft drops score;
+    /// gravity pulling the piece down never does.
+    fn descend(&mut self, source: Descent) -> bool {
+        let (dx, dy) = self.config.gravity.step();
+        let moved = self.move_piece(dx, dy);
+        if moved && source == Descent::SoftDrop && self.config.soft_drop_scoring {
+            self.award(SCORE_SOFT_DROP);
+        }
+        moved
+    }
+
     fn lock_and_spawn(&mut self) {
+        let tspin = self.detect_tspin();
+        *self.pieces_placed.entry(self.current_piece.tetromino_type).or_insert(0) += 1;
+        let blocks = self.current_piece.blocks();
+        let above = |block: &Position| block.y < 0;
+        let locked_out = match self.config.lock_out {
+            LockOut::Partial => blocks.iter().any(above),
+            LockOut::Full => blocks.iter().all(above),
+        };
         self.lock_piece();
+        if locked_out {
+            // Blocks above the board have nowhere to stay
+            self.end_game();
+            return;
+        }
+        self.report_finesse();
         let lines = self.clear_lines();
+        self.record_clear_stats(lines, tspin.is_some());
+        match tspin {
+            Some(mini) => {
+                self.events.push(GameEvent::TSpin { lines, mini });
+                self.add_tspin_score(lines, mini);
+            }
+            None if lines > 0 => self.add_score(lines),
+            None => {}
+        }
+        self.are_remaining_ms = self.config.spawn_delay_ms;
         if lines > 0 {
-            self.add_score(lines);
+            self.are_remaining_ms += self.config.are_ms;
+            if let Some(floor) = &mut self.rising_floor {
+                floor.delay();
+            }
+        }
+        self.can_hold = true;
+
+        if let GameMode::Sprint { lines: goal } = self.config.mode {
+            if self.lines_cleared >= goal {
+                self.end_game();
+                return;
+            }
+        }
+        if let GameMode::Puzzle { lines: goal, pieces } = self.config.mode {
+            if self.lines_cleared >= goal || self.pieces_placed.values().sum::<u32>() >= pieces {
+                self.end_game();
+                return;
+            }
         }
         self.spawn_next_piece();
     }
 
+    /// Compares the presses spent on the current piece with the fewest that
+    /// reach the same spot from its spawn
+    fn report_finesse(&mut self) {
+        // Optimal paths are worked out in columns, which only fits downward gravity
+        if self.config.gravity != GravityDirection::Down {
+            return;
+        }
+        let piece = &self.current_piece;
+        let spawn = spawn_tetromino(&self.config, piece.tetromino_type);
+        let optimal = finesse::optimal_inputs(
+            self.config.rotation_system,
+            piece.tetromino_type,
+            Placement {
+                column: spawn.position.x,
+                rotation: spawn.rotation,
+            },
+            Placement {
+                column: piece.position.x,
+                rotation: piece.rotation,
+            },
+        );
+        self.events.push(GameEvent::Finesse {
+            optimal,
+            actual: self.piece_inputs,
+        });
+    }
+
+    /// Updates the combo streak and clear counters after a lock
+    fn record_clear_stats(&mut self, lines: u32, tspin: bool) {
+        if lines > 0 {
+            self.combo += 1;
+            self.max_combo = self.max_combo.max(self.combo);
+            self.pieces_since_clear = 0;
+            self.back_to_back = lines == 4 || tspin;
+        } else {
+            self.combo = 0;
+            self.pieces_since_clear += 1;
+        }
+        if lines == 4 {
+            self.tetrises += 1;
+        }
+        if tspin {
+            self.tspins += 1;
+        }
+    }
+
     pub fn tick(&mut self) {
-        if !matches!(self.state, GameState::Playing) {
+        if !self.is_controllable() {
             return;
         }
 
-        if !self.move_piece(0, 1) {
+        if !self.descend(Descent::Gravity) {
             self.lock_and_spawn();
         }
     }
 
+    /// Advances game time by `delta_ms`, applying one gravity tick for every
+    /// full tick duration that has elapsed. Time only accumulates while playing.
+    pub fn tick_with_delta(&mut self, delta_ms: u64) {
+        if self.state != GameState::Playing {
+            return;
+        }
+
+        let pace_updates_before = self.elapsed_ms / PACE_UPDATE_MS;
+        self.elapsed_ms += delta_ms;
+        if self.elapsed_ms / PACE_UPDATE_MS > pace_updates_before {
+            let pace = (self.lines_per_minute() * 100.0).round() as u32;
+            self.events.push(GameEvent::PaceUpdate(pace));
+        }
+        if let GameMode::Ultra { duration_ms } = self.config.mode {
+            if self.elapsed_ms >= duration_ms {
+                self.elapsed_ms = duration_ms;
+                self.end_game();
+                return;
+            }
+        }
+
+        if let Some(floor) = &mut self.rising_floor {
+            let holes = floor.advance(delta_ms);
+            if !holes.is_empty() {
+                self.add_garbage_lines(&holes);
+                if self.state != GameState::Playing {
+                    return;
+                }
+            }
+        }
+
+        if let Some(attack) = &mut self.garbage_attack {
+            let holes = attack.advance(delta_ms);
+            if !holes.is_empty() {
+                self.add_garbage_lines(&holes);
+                if self.state != GameState::Playing {
+                    return;
+                }
+            }
+        }
+
+        // Entry delay runs out before gravity picks up the rest of the time
+        let are_ms = delta_ms.min(self.are_remaining_ms);
+        self.are_remaining_ms -= are_ms;
+        self.gravity_timer_ms += delta_ms - are_ms;
+        while self.is_controllable() && self.gravity_timer_ms >= self.tick_duration_ms() {
+            self.gravity_timer_ms -= self.tick_duration_ms();
+            self.tick();
+        }
+    }
+
+    /// Milliseconds spent playing this run, excluding pauses
+    pub fn elapsed_ms(&self) -> u64 {
+        self.elapsed_ms
+    }
+
+    /// Clearing pace over the run so far; 0 until the first line clears
+    pub fn lines_per_minute(&self) -> f32 {
+        if self.lines_cleared == 0 || self.elapsed_ms == 0 {
+            return 0.0;
+        }
+        self.lines_cleared as f32 * 60_000.0 / self.elapsed_ms as f32
+    }
+
+    /// Pieces locked so far this run
+    pub fn pieces_locked(&self) -> u32 {
+        self.pieces_placed.values().sum()
+    }
+
+    /// Placement speed over the run so far; 0 before any play time
+    pub fn pieces_per_second(&self) -> f32 {
+        if self.elapsed_ms == 0 {
+            return 0.0;
+        }
+        self.pieces_locked() as f32 * 1000.0 / self.elapsed_ms as f32
+    }
+
+    /// Milliseconds of play left before the next gravity tick
+    pub fn time_until_tick_ms(&self) -> u64 {
+        self.are_remaining_ms + self.tick_duration_ms().saturating_sub(self.gravity_timer_ms)
+    }
+
+    /// Distance to the goal of a Sprint or Ultra run; None in Marathon
+    pub fn mode_progress(&self) -> Option<ModeProgress> {
+        match self.config.mode {
+            GameMode::Marathon => None,
+            GameMode::Sprint { lines } | GameMode::Puzzle { lines, .. } => Some(
+                ModeProgress::LinesRemaining(lines.saturating_sub(self.lines_cleared)),
+            ),
+            GameMode::Ultra { duration_ms } => Some(ModeProgress::TimeRemainingMs(
+                duration_ms.saturating_sub(self.elapsed_ms),
+            )),
+        }
+    }
+
+    /// Whether a finished puzzle reached its goal; `None` outside puzzle mode
+    /// or while the puzzle is still being played. Topping out counts as a loss.
+    pub fn puzzle_solved(&self) -> Option<bool> {
+        match self.config.mode {
+            GameMode::Puzzle { lines, .. } if self.state == GameState::GameOver => {
+                Some(self.lines_cleared >= lines)
+            }
+            _ => None,
+        }
+    }
+
+    /// Milliseconds left in the entry delay after a lock: the spawn delay,
+    /// plus the line-clear delay if lines cleared
+    pub fn are_remaining_ms(&self) -> u64 {
+        self.are_remaining_ms
+    }
+
+    /// Playing and outside the entry delay, so input and gravity apply
+    fn is_controllable(&self) -> bool {
+        self.state == GameState::Playing && self.are_remaining_ms == 0
+    }
+
+    /// Performs one player action, the same way the keyboard controls do
+    pub fn apply_input(&mut self, input: Input) {
+        match input {
+            Input::MoveLeft => {
+                let (dx, dy) = self.config.gravity.left();
+                self.move_piece(dx, dy);
+            }
+            Input::MoveRight => {
+                let (dx, dy) = self.config.gravity.left();
+                self.move_piece(-dx, -dy);
+            }
+            Input::SoftDrop => self.soft_drop(),
+            Input::HardDrop => self.hard_drop(),
+            Input::RotateCw => {
+                self.rotate_piece(true);
+            }
+            Input::RotateCcw => {
+                self.rotate_piece(false);
+            }
+            Input::Hold => {
+                self.hold_piece();
+            }
+            Input::TogglePause => self.toggle_pause(),
+        }
+    }
+
+    /// Performs `inputs` in order with no time passing in between, and
+    /// returns the events they raised. Events queued before the call stay
+    /// queued for `take_events`.
+    pub fn apply_inputs(&mut self, inputs: &[Input]) -> Vec<GameEvent> {
+        let start = self.events.len();
+        for &input in inputs {
+            self.apply_input(input);
+        }
+        self.events.split_off(start)
+    }
+
     pub fn toggle_pause(&mut self) {
         match self.state {
             GameState::Playing => {
@@ -500,19 +1798,68 @@ impl Game {
         }
     }
 
+    /// Stops the run, whether it topped out or reached its mode's goal
+    fn end_game(&mut self) {
+        self.state = GameState::GameOver;
+        self.events.push(GameEvent::GameOver);
+        self.record_high_score();
+    }
+
+    /// Call before exiting so a run abandoned mid-game (playing or paused)
+    /// still keeps a beaten high score
+    pub fn on_quit(&mut self) {
+        self.record_high_score();
+    }
+
+    /// Points left to reach the stored high score, or None once this run
+    /// has beaten it
+    pub fn score_to_high_score(&self) -> Option<u32> {
+        (self.score <= self.high_score).then(|| self.high_score.saturating_sub(self.score))
+    }
+
+    /// Updates and saves the high score if the current score beats it
+    fn record_high_score(&mut self) {
+        if self.score > self.high_score {
+            self.high_score = self.score;
+            if let Some(path) = &self.high_score_file {
+                save_high_score(path, self.high_score);
+            }
+        }
+    }
+
+    /// Starts a fresh game with the same piece provider. The outgoing
+    /// score is saved as the high score if it beats it, then any unconsumed
+    /// events are dropped so `take_events` yields exactly `GameRestarted`.
     pub fn restart(&mut self) {
-        // Clear the grid
-        self.grid = vec![vec![CellState::Empty; GRID_WIDTH]; GRID_HEIGHT];
+        // Keep the outgoing run's high score before anything is reset
+        self.record_high_score();
+
+        // Clear the grid (back to the same cheese rows, if any)
+        self.grid = starting_grid(&self.config);
 
         // Reset score, lines, and level
         self.score = 0;
         self.lines_cleared = 0;
         self.level = 1;
+        self.gravity_timer_ms = 0;
+        self.are_remaining_ms = 0;
+        self.elapsed_ms = 0;
+        self.pieces_placed.clear();
+        self.combo = 0;
+        self.max_combo = 0;
+        self.tetrises = 0;
+        self.consecutive_tetris = 0;
+        self.pieces_since_clear = 0;
+        self.back_to_back = false;
+        self.tspins = 0;
+        self.rising_floor = self.config.rising_floor.map(RisingFloor::new);
+        self.garbage_attack = GarbageScheduler::from_config(&self.config);
+        self.last_locked = None;
 
         // Reset state to Playing
         self.state = GameState::Playing;
 
-        // Clear events
+        // Drop events from the previous run
         self.events.clear();
 
         // Rebuild preview queue with new pieces
@@ -521,19 +1868,169 @@ impl Game {
             self.preview_queue.push_back(self.piece_provider.next_piece());
         }
 
+        // Empty the hold queue
+        self.held_pieces.clear();
+        self.can_hold = true;
+
         // Spawn new current piece
         let current_type = self.piece_provider.next_piece();
-        self.current_piece = Tetromino::new(current_type);
+        self.current_piece = spawn_tetromino(&self.config, current_type);
+        self.last_kick = None;
+        self.last_move_was_rotation = false;
+        self.piece_inputs = 0;
 
         // Emit restart event
         self.events.push(GameEvent::GameRestarted);
     }
 
+    /// Summary of the current run for `export_stats`
+    pub fn session_stats(&self) -> SessionStats {
+        SessionStats {
+            score: self.score,
+            lines: self.lines_cleared,
+            level: self.level,
+            duration_ms: self.elapsed_ms,
+            pieces_placed: TetrominoType::ALL
+                .iter()
+                .map(|&t| (t, 0))
+                .chain(self.pieces_placed.iter().map(|(&t, &count)| (t, count)))
+                .collect(),
+            max_combo: self.max_combo,
+            tetrises: self.tetrises,
+            tspins: self.tspins,
+        }
+    }
+
+    /// Everything needed to pick this run back up later. The piece provider
+    /// isn't captured, so pieces past the preview queue are dealt fresh.
+    pub fn to_snapshot(&self) -> GameSnapshot {
+        GameSnapshot {
+            grid: self.grid.clone(),
+            current_piece: self.current_piece.clone(),
+            preview_queue: self.preview_queue.iter().copied().collect(),
+            held_pieces: self.held_pieces.iter().copied().collect(),
+            can_hold: self.can_hold,
+            score: self.score,
+            lines_cleared: self.lines_cleared,
+            level: self.level,
+            elapsed_ms: self.elapsed_ms,
+            pieces_placed: self.pieces_placed.clone(),
+            combo: self.combo,
+            max_combo: self.max_combo,
+            tetrises: self.tetrises,
+            consecutive_tetris: self.consecutive_tetris,
+            pieces_since_clear: self.pieces_since_clear,
+            back_to_back: self.back_to_back,
+            tspins: self.tspins,
+        }
+    }
+
+    /// Continues the run saved in `snapshot`, keeping this game's config and
+    /// piece provider. The game comes back paused, as it was when saved.
+    pub fn restore_snapshot(&mut self, snapshot: &GameSnapshot) {
+        self.grid = snapshot.grid.clone();
+        self.current_piece = snapshot.current_piece.clone();
+        self.preview_queue = snapshot.preview_queue.iter().copied().collect();
+        self.held_pieces = snapshot.held_pieces.iter().copied().collect();
+        self.can_hold = snapshot.can_hold;
+        self.score = snapshot.score;
+        self.lines_cleared = snapshot.lines_cleared;
+        self.level = snapshot.level;
+        self.elapsed_ms = snapshot.elapsed_ms;
+        self.pieces_placed = snapshot.pieces_placed.clone();
+        self.combo = snapshot.combo;
+        self.max_combo = snapshot.max_combo;
+        self.tetrises = snapshot.tetrises;
+        self.consecutive_tetris = snapshot.consecutive_tetris;
+        self.pieces_since_clear = snapshot.pieces_since_clear;
+        self.back_to_back = snapshot.back_to_back;
+        self.tspins = snapshot.tspins;
+
+        self.state = GameState::Paused;
+        self.gravity_timer_ms = 0;
+        self.are_remaining_ms = 0;
+        self.last_kick = None;
+        self.last_move_was_rotation = false;
+        self.piece_inputs = 0;
+        self.events.clear();
+    }
+
+    /// Writes `session_stats` to `path` as pretty-printed JSON
+    pub fn export_stats(&self, path: impl AsRef<Path>) -> io::Result<()> {
+        let json = serde_json::to_string_pretty(&self.session_stats())?;
+        std::fs::write(path, json)
+    }
+
     pub fn tick_duration_ms(&self) -> u64 {
-        let speed_reduction = (self.level - 1) as u64 * SPEED_INCREASE_PER_LEVEL;
+        let speed_reduction = (self.gravity_level() - 1) as u64 * SPEED_INCREASE_PER_LEVEL;
         BASE_TICK_MS.saturating_sub(speed_reduction).max(MIN_TICK_MS)
     }
 
+    /// Gravity speed in cells per second, the reciprocal of
+    /// `tick_duration_ms`, so it follows the level, `level_cap` and any
+    /// time ramp
+    pub fn gravity_cells_per_second(&self) -> f32 {
+        1000.0 / self.tick_duration_ms() as f32
+    }
+
+    /// Where the current piece would land if hard dropped now. Computed from
+    /// the current piece alone, so it is valid from the frame a piece spawns.
+    pub fn ghost_piece(&self) -> Tetromino {
+        self.dropped(self.current_piece.clone())
+    }
+
+    /// Where the piece a hold would swap back in right now would land if
+    /// hard dropped straight from its spawn. `None` while holding is not
+    /// possible or would bring in the next preview piece instead.
+    pub fn held_ghost(&self) -> Option<Tetromino> {
+        let swaps_back = self.config.hold_capacity > 0
+            && self.held_pieces.len() >= self.config.hold_capacity;
+        if self.state != GameState::Playing || !self.can_hold || !swaps_back {
+            return None;
+        }
+        let mut piece = spawn_tetromino(&self.config, self.held_piece()?);
+        piece.color = None;
+        self.is_valid_position(&piece).then(|| self.dropped(piece))
+    }
+
+    /// `piece` moved along gravity as far as it fits
+    fn dropped(&self, piece: Tetromino) -> Tetromino {
+        let (dx, dy) = self.config.gravity.step();
+        let mut ghost = piece;
+        loop {
+            let next = ghost.moved(dx, dy);
+            if !self.is_valid_position(&next) {
+                return ghost;
+            }
+            ghost = next;
+        }
+    }
+
+    /// Rows a hard drop would clear right now, top to bottom, found by
+    /// placing the ghost on the board without touching the game. Always
+    /// empty under sideways gravity, where columns clear instead.
+    pub fn rows_cleared_by_ghost(&self) -> Vec<usize> {
+        if self.config.gravity != GravityDirection::Down {
+            return Vec::new();
+        }
+
+        let ghost_blocks = self.ghost_piece().blocks();
+        let mut rows: Vec<usize> = ghost_blocks
+            .iter()
+            .filter(|block| (0..GRID_HEIGHT as i16).contains(&block.y))
+            .map(|block| block.y as usize)
+            .collect();
+        rows.sort_unstable();
+        rows.dedup();
+        rows.retain(|&y| {
+            (0..GRID_WIDTH).all(|x| {
+                self.grid[y][x] != CellState::Empty
+                    || ghost_blocks.contains(&Position { x: x as i16, y: y as i16 })
+            })
+        });
+        rows
+    }
+
     /// Returns the visual grid state with the current piece overlaid
     pub fn render_grid(&self) -> Vec<Vec<CellState>> {
         let mut visual_grid = self.grid.clone();
@@ -541,13 +2038,119 @@ impl Game {
         // Overlay current piece
         for block in self.current_piece.blocks() {
             if block.y >= 0 && block.y < GRID_HEIGHT as i16 && block.x >= 0 && block.x < GRID_WIDTH as i16 {
-                visual_grid[block.y as usize][block.x as usize] = CellState::Filled(self.current_piece.tetromino_type);
+                visual_grid[block.y as usize][block.x as usize] = self.current_piece.cell_state();
             }
         }
 
         visual_grid
     }
 
+    /// Like `render_grid`, but keeps the locked cells, ghost and active piece
+    /// apart so each can be styled differently. The ghost shows from the
+    /// spawn frame on; where it coincides with the piece only the piece shows.
+    pub fn render_grid_detailed(&self) -> Vec<Vec<RenderCell>> {
+        let mut visual_grid: Vec<Vec<RenderCell>> = self
+            .grid
+            .iter()
+            .map(|row| {
+                row.iter()
+                    .map(|cell| match *cell {
+                        CellState::Empty => RenderCell::Empty,
+                        CellState::Filled(piece_type) => RenderCell::Locked(piece_type, None),
+                        CellState::Tinted(piece_type, color) => {
+                            RenderCell::Locked(piece_type, Some(color))
+                        }
+                        CellState::Garbage => RenderCell::Garbage,
+                    })
+                    .collect()
+            })
+            .collect();
+
+        let piece_type = self.current_piece.tetromino_type;
+        let color = self.current_piece.color;
+        let layers = [
+            (self.ghost_piece(), RenderCell::Ghost(piece_type, color)),
+            (self.current_piece.clone(), RenderCell::Active(piece_type, color)),
+        ];
+        for (piece, render_cell) in layers {
+            for block in piece.blocks() {
+                if block.y >= 0 && block.y < GRID_HEIGHT as i16 && block.x >= 0 && block.x < GRID_WIDTH as i16 {
+                    visual_grid[block.y as usize][block.x as usize] = render_cell;
+                }
+            }
+        }
+
+        visual_grid
+    }
+
+    /// Yields `(x, y, cell)` for every non-empty cell. With
+    /// `include_current_piece` the active piece's in-bounds cells follow the
+    /// locked cells.
+    pub fn occupied_cells(
+        &self,
+        include_current_piece: bool,
+    ) -> impl Iterator<Item = (usize, usize, CellState)> + '_ {
+        let locked = self.grid.iter().enumerate().flat_map(|(y, row)| {
+            row.iter()
+                .enumerate()
+                .filter(|(_, cell)| **cell != CellState::Empty)
+                .map(move |(x, cell)| (x, y, *cell))
+        });
+
+        let piece_cell = self.current_piece.cell_state();
+        let piece_blocks = if include_current_piece {
+            self.current_piece.blocks()
+        } else {
+            Vec::new()
+        };
+        let active = piece_blocks
+            .into_iter()
+            .filter(|b| b.x >= 0 && b.x < GRID_WIDTH as i16 && b.y >= 0 && b.y < GRID_HEIGHT as i16)
+            .map(move |b| (b.x as usize, b.y as usize, piece_cell));
+
+        locked.chain(active)
+    }
+
+    /// Board export for external tools: `GRID_HEIGHT` rows of `GRID_WIDTH`
+    /// cells, top row first, each holding the piece type that filled it.
+    /// Garbage has no piece type and reads as `None`, like an empty cell.
+    /// See `CELL_MATRIX_VERSION`.
+    pub fn to_cell_matrix(&self, include_current_piece: bool) -> Vec<Vec<Option<TetrominoType>>> {
+        let mut matrix = vec![vec![None; GRID_WIDTH]; GRID_HEIGHT];
+        for (x, y, cell) in self.occupied_cells(include_current_piece) {
+            matrix[y][x] = cell.piece_type();
+        }
+        matrix
+    }
+
+    /// Occupancy-only copy of the locked cells for fast simulation
+    pub fn bitboard(&self) -> BitBoard {
+        BitBoard::from_grid(&self.grid).with_buffer_rows(self.buffer_rows())
+    }
+
+    /// Every spot a `piece_type` could come to rest by rotating at the top of
+    /// the board and dropping straight down, one per distinct shape and
+    /// column. Spots reachable only by sliding or spinning under an overhang
+    /// are left out, as are columns blocked at the top. Assumes downward
+    /// gravity.
+    pub fn legal_placements(&self, piece_type: TetrominoType) -> Vec<Tetromino> {
+        solver::drop_placements(&self.bitboard(), piece_type)
+    }
+
+    /// Whether the current piece followed by the preview queue can empty the
+    /// board, searching drop placements only as deep as the known pieces
+    pub fn is_perfect_clear_possible(&self) -> bool {
+        let mut pieces = vec![self.current_piece.tetromino_type];
+        pieces.extend(self.preview_queue.iter().copied());
+        solver::can_perfect_clear(&self.bitboard(), &pieces)
+    }
+
+    /// Kick offset applied by the last successful rotation of the current
+    /// piece, or `None` if it rotated in place (or hasn't rotated)
+    pub fn last_kick(&self) -> Option<(i16, i16)> {
+        self.last_kick
+    }
+
     /// Takes and clears all pending events
     pub fn take_events(&mut self) -> Vec<GameEvent> {
         std::mem::take(&mut self.events)
@@ -568,10 +2171,189 @@ impl Game {
         self.state == GameState::GameOver
     }
 
+    /// Check if game is paused
+    pub fn is_paused(&self) -> bool {
+        self.state == GameState::Paused
+    }
+
+    /// Check if game is running (not paused or over). The entry delay
+    /// after a clear still counts as playing.
+    pub fn is_playing(&self) -> bool {
+        self.state == GameState::Playing
+    }
+
+    /// Fills each `(x, y, piece type)` cell of the grid, for setting up
+    /// boards in code. Coordinates outside the grid are skipped.
+    pub fn fill_cells(&mut self, cells: &[(usize, usize, TetrominoType)]) {
+        for &(x, y, piece_type) in cells {
+            if x < GRID_WIDTH && y < GRID_HEIGHT {
+                self.grid[y][x] = CellState::Filled(piece_type);
+            }
+        }
+    }
+
     /// Count total filled cells in grid
     pub fn total_filled_cells(&self) -> usize {
         self.grid.iter().flatten().filter(|cell| **cell != CellState::Empty).count()
     }
+
+    /// Panics (in debug builds) if the game state is internally inconsistent.
+    /// Meant for fuzzing and for debugging new features that touch the grid.
+    pub fn assert_invariants(&self) {
+        debug_assert_eq!(self.grid.len(), GRID_HEIGHT, "grid has the wrong number of rows");
+        for (y, row) in self.grid.iter().enumerate() {
+            debug_assert_eq!(row.len(), GRID_WIDTH, "row {} has the wrong width", y);
+        }
+
+        // The piece may poke above the top (a blocked spawn) but never out
+        // the sides or bottom, and only overlaps the stack once the game is over
+        for block in self.current_piece.blocks() {
+            let in_columns = (0..GRID_WIDTH as i16).contains(&block.x);
+            let in_rows = (-(GRID_HEIGHT as i16)..GRID_HEIGHT as i16).contains(&block.y);
+            debug_assert!(
+                in_columns && in_rows,
+                "current piece block {:?} is out of bounds",
+                block
+            );
+        }
+        if self.state != GameState::GameOver {
+            debug_assert!(
+                self.is_valid_position(&self.current_piece),
+                "current piece overlaps the stack"
+            );
+        }
+
+        debug_assert_eq!(
+            self.preview_queue.len(),
+            PREVIEW_COUNT,
+            "preview queue has the wrong length"
+        );
+        debug_assert!(self.can_hold || !self.held_pieces.is_empty(), "hold used but queue is empty");
+        debug_assert!(
+            self.held_pieces.len() <= self.config.hold_capacity,
+            "hold queue is over capacity"
+        );
+
+        // `set_level` can jump ahead of the lines cleared, never behind
+        debug_assert!(
+            self.level > self.lines_cleared / LINES_PER_LEVEL,
+            "level is below what the lines cleared have earned"
+        );
+        // Every cleared line is worth at least a single at level 1
+        debug_assert!(
+            self.score >= self.lines_cleared.saturating_mul(SCORE_SINGLE),
+            "score {} is too low for {} lines",
+            self.score,
+            self.lines_cleared
+        );
+    }
+
+    /// Height of each column's highest locked cell above the floor (0 when empty)
+    pub fn column_heights(&self) -> [usize; GRID_WIDTH] {
+        let mut heights = [0; GRID_WIDTH];
+        for (x, height) in heights.iter_mut().enumerate() {
+            if let Some(top) = self.grid.iter().position(|row| row[x] != CellState::Empty) {
+                *height = GRID_HEIGHT - top;
+            }
+        }
+        heights
+    }
+
+    /// Column and depth of the deepest well: how far a column sits below its
+    /// lower neighbor (edge columns only have one). Ties go to the leftmost.
+    pub fn deepest_well(&self) -> (usize, usize) {
+        let heights = self.column_heights();
+        let mut deepest = (0, 0);
+        for x in 0..GRID_WIDTH {
+            let left = x.checked_sub(1).map(|l| heights[l]);
+            let right = heights.get(x + 1).copied();
+            let rim = match (left, right) {
+                (Some(l), Some(r)) => l.min(r),
+                (Some(side), None) | (None, Some(side)) => side,
+                (None, None) => continue,
+            };
+            let depth = rim.saturating_sub(heights[x]);
+            if depth > deepest.1 {
+                deepest = (x, depth);
+            }
+        }
+        deepest
+    }
+
+    /// Filled/empty changes walking across every row, with both side walls
+    /// counted as filled (Dellacherie's row transitions). An empty row
+    /// scores 2. The current piece is ignored.
+    pub fn row_transitions(&self) -> usize {
+        self.grid
+            .iter()
+            .map(|row| {
+                let cells = row.iter().map(|cell| *cell != CellState::Empty);
+                std::iter::once(true)
+                    .chain(cells)
+                    .chain(std::iter::once(true))
+                    .collect::<Vec<_>>()
+                    .windows(2)
+                    .filter(|pair| pair[0] != pair[1])
+                    .count()
+            })
+            .sum()
+    }
+
+    /// Filled/empty changes walking down every column onto the floor, which
+    /// counts as filled; the space above the board does not. An empty column
+    /// scores 1. The current piece is ignored.
+    pub fn column_transitions(&self) -> usize {
+        (0..GRID_WIDTH)
+            .map(|x| {
+                let cells = self.grid.iter().map(|row| row[x] != CellState::Empty);
+                cells
+                    .chain(std::iter::once(true))
+                    .collect::<Vec<_>>()
+                    .windows(2)
+                    .filter(|pair| pair[0] != pair[1])
+                    .count()
+            })
+            .sum()
+    }
+
+    /// Sum of the height differences between neighboring columns, ignoring
+    /// the current piece. Flat stacks score 0.
+    pub fn bumpiness(&self) -> usize {
+        self.column_heights()
+            .windows(2)
+            .map(|pair| pair[0].abs_diff(pair[1]))
+            .sum()
+    }
+
+    /// Centers of T-slots on the board: a three-wide gap over a one-cell
+    /// notch, with both lower corners filled and an overhang on exactly one
+    /// upper corner, so a T rotated in points down into the notch and scores
+    /// a T-spin. Walls and the floor count as filled; the current piece is
+    /// ignored. Listed top to bottom, then left to right.
+    pub fn find_t_slots(&self) -> Vec<(usize, usize)> {
+        let occupied = |x: i16, y: i16| {
+            x < 0
+                || x >= GRID_WIDTH as i16
+                || y >= GRID_HEIGHT as i16
+                || (y >= 0 && self.grid[y as usize][x as usize] != CellState::Empty)
+        };
+
+        let mut slots = Vec::new();
+        for y in 0..GRID_HEIGHT as i16 - 1 {
+            for x in 1..GRID_WIDTH as i16 - 1 {
+                let gap = [(x - 1, y), (x, y), (x + 1, y), (x, y + 1), (x, y - 1)];
+                if gap.iter().any(|&(gx, gy)| occupied(gx, gy)) {
+                    continue;
+                }
+                let notch = occupied(x - 1, y + 1) && occupied(x + 1, y + 1) && occupied(x, y + 2);
+                let overhang = occupied(x - 1, y - 1) != occupied(x + 1, y - 1);
+                if notch && overhang {
+                    slots.push((x as usize, y as usize));
+                }
+            }
+        }
+        slots
+    }
 }
 
 impl Default for Game {
@@ -580,6 +2362,120 @@ impl Default for Game {
     }
 }
 
+// ============================================================================
+// Board Text Format
+// ============================================================================
+
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub enum BoardParseError {
+    TooManyRows(usize),
+    WrongWidth { row: usize, width: usize },
+    InvalidCell { row: usize, column: usize, found: char },
+}
+
+impl std::fmt::Display for BoardParseError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            BoardParseError::TooManyRows(rows) => {
+                write!(f, "board has {} rows, at most {} allowed", rows, GRID_HEIGHT)
+            }
+            BoardParseError::WrongWidth { row, width } => {
+                write!(f, "row {} is {} cells wide, expected {}", row + 1, width, GRID_WIDTH)
+            }
+            BoardParseError::InvalidCell { row, column, found } => {
+                write!(f, "unexpected '{}' at row {}, column {}", found, row + 1, column + 1)
+            }
+        }
+    }
+}
+
+impl std::error::Error for BoardParseError {}
+
+/// Parses a board drawn one row per line: `.` is empty, a piece letter
+/// (`I O T S Z J L`) is a cell filled by that piece and `G` is garbage.
+/// Blank lines are ignored and boards shorter than the grid are aligned to
+/// the bottom, so fixtures only need to draw the interesting rows.
+pub fn board_from_string(text: &str) -> Result<Vec<Vec<CellState>>, BoardParseError> {
+    let rows: Vec<&str> = text.lines().map(str::trim).filter(|line| !line.is_empty()).collect();
+    if rows.len() > GRID_HEIGHT {
+        return Err(BoardParseError::TooManyRows(rows.len()));
+    }
+
+    let mut grid = vec![vec![CellState::Empty; GRID_WIDTH]; GRID_HEIGHT - rows.len()];
+    for (row, line) in rows.iter().enumerate() {
+        let width = line.chars().count();
+        if width != GRID_WIDTH {
+            return Err(BoardParseError::WrongWidth { row, width });
+        }
+
+        let cells = line
+            .chars()
+            .enumerate()
+            .map(|(column, found)| {
+                Ok(match found {
+                    '.' => CellState::Empty,
+                    'I' => CellState::Filled(TetrominoType::I),
+                    'O' => CellState::Filled(TetrominoType::O),
+                    'T' => CellState::Filled(TetrominoType::T),
+                    'S' => CellState::Filled(TetrominoType::S),
+                    'Z' => CellState::Filled(TetrominoType::Z),
+                    'J' => CellState::Filled(TetrominoType::J),
+                    'L' => CellState::Filled(TetrominoType::L),
+                    'G' => CellState::Garbage,
+                    _ => return Err(BoardParseError::InvalidCell { row, column, found }),
+                })
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+        grid.push(cells);
+    }
+
+    Ok(grid)
+}
+
+#[derive(Debug)]
+pub enum BoardFileError {
+    Io(io::Error),
+    Parse(BoardParseError),
+}
+
+impl std::fmt::Display for BoardFileError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            BoardFileError::Io(err) => write!(f, "could not read board file: {}", err),
+            BoardFileError::Parse(err) => write!(f, "malformed board file: {}", err),
+        }
+    }
+}
+
+impl std::error::Error for BoardFileError {}
+
+/// Reads a starting stack from a file in the `board_from_string` format,
+/// such as one written from `board_to_string`
+pub fn board_from_file(path: impl AsRef<Path>) -> Result<Vec<Vec<CellState>>, BoardFileError> {
+    let text = std::fs::read_to_string(path).map_err(BoardFileError::Io)?;
+    board_from_string(&text).map_err(BoardFileError::Parse)
+}
+
+/// Draws a grid in the `board_from_string` format, one line per row from
+/// the top. Cells of custom pieces have no letter and are written as `#`,
+/// which `board_from_string` rejects.
+pub fn board_to_string(grid: &[Vec<CellState>]) -> String {
+    grid.iter()
+        .map(|row| {
+            row.iter()
+                .map(|cell| match cell {
+                    CellState::Empty => '.',
+                    CellState::Filled(tetromino_type) | CellState::Tinted(tetromino_type, _) => {
+                        tetromino_type.letter().unwrap_or('#')
+                    }
+                    CellState::Garbage => 'G',
+                })
+                .chain(std::iter::once('\n'))
+                .collect::<String>()
+        })
+        .collect()
+}
+
 // ============================================================================
 // Test Helpers
 // ============================================================================
@@ -591,16 +2487,16 @@ pub mod test_helpers {
         vec![vec![CellState::Empty; GRID_WIDTH]; GRID_HEIGHT]
     }
 
-    pub fn fill_row(grid: &mut Vec<Vec<CellState>>, y: usize) {
-        for x in 0..GRID_WIDTH {
-            grid[y][x] = CellState::Filled(TetrominoType::T);
+    pub fn fill_row(grid: &mut [Vec<CellState>], y: usize) {
+        for cell in grid[y].iter_mut().take(GRID_WIDTH) {
+            *cell = CellState::Filled(TetrominoType::T);
         }
     }
 
-    pub fn fill_row_with_gap(grid: &mut Vec<Vec<CellState>>, y: usize, gap_x: usize) {
-        for x in 0..GRID_WIDTH {
+    pub fn fill_row_with_gap(grid: &mut [Vec<CellState>], y: usize, gap_x: usize) {
+        for (x, cell) in grid[y].iter_mut().enumerate().take(GRID_WIDTH) {
             if x != gap_x {
-                grid[y][x] = CellState::Filled(TetrominoType::T);
+                *cell = CellState::Filled(TetrominoType::T);
             }
         }
     }
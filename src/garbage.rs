@@ -0,0 +1,166 @@
+use rand::{rngs::StdRng, Rng, SeedableRng};
+use serde::{Deserialize, Serialize};
+
+use crate::game::{Game, GameConfig, GRID_WIDTH};
+
+// ============================================================================
+// Garbage Attack
+// ============================================================================
+
+/// Settings for a simulated opponent that sends garbage on a fixed schedule
+#[derive(Clone, Copy, PartialEq, Eq, Debug, Serialize, Deserialize)]
+pub struct GarbageAttackConfig {
+    pub interval_ms: u64,
+    pub lines_per_attack: u32,
+    pub seed: u64,
+}
+
+/// Garbage rows the board starts with, for digging ("cheese") practice
+#[derive(Clone, Copy, PartialEq, Eq, Debug, Serialize, Deserialize)]
+pub struct CheeseConfig {
+    pub rows: usize,
+    pub seed: u64,
+}
+
+/// Settings for a floor that rises one garbage row at a time, whatever the
+/// player clears. Each rise comes `speedup_ms` sooner than the last, down
+/// to `min_interval_ms`.
+#[derive(Clone, Copy, PartialEq, Eq, Debug, Serialize, Deserialize)]
+pub struct RisingFloorConfig {
+    pub rise_interval_ms: u64,
+    pub speedup_ms: u64,
+    pub min_interval_ms: u64,
+    pub seed: u64,
+}
+
+impl RisingFloorConfig {
+    /// Wait before the next rise once `rises` rows have already risen
+    pub fn interval_after(&self, rises: u32) -> u64 {
+        let speedup = self.speedup_ms.saturating_mul(rises as u64);
+        self.rise_interval_ms
+            .saturating_sub(speedup)
+            .max(self.min_interval_ms)
+            .max(1)
+    }
+}
+
+/// Hole column for each cheese row, bottom row last. The same seed always
+/// gives the same holes, and no hole sits directly above the one below it.
+pub fn cheese_holes(config: CheeseConfig) -> Vec<usize> {
+    let mut rng = StdRng::seed_from_u64(config.seed);
+    let mut holes: Vec<usize> = Vec::with_capacity(config.rows);
+    for _ in 0..config.rows {
+        let hole = match holes.last() {
+            // Skip over the previous hole so the two can't line up
+            Some(&previous) => {
+                let hole = rng.gen_range(0..GRID_WIDTH - 1);
+                if hole >= previous {
+                    hole + 1
+                } else {
+                    hole
+                }
+            }
+            None => rng.gen_range(0..GRID_WIDTH),
+        };
+        holes.push(hole);
+    }
+    holes
+}
+
+/// Sends garbage to a game every `interval_ms` of play, with hole columns
+/// drawn from a seeded RNG so runs are reproducible. A game whose config
+/// sets `garbage_attack` owns one and advances it with its play time; use
+/// `update` to drive one by hand for a game without that setting.
+#[derive(Clone, Debug)]
+pub struct GarbageScheduler {
+    config: GarbageAttackConfig,
+    rng: StdRng,
+    elapsed_ms: u64,
+}
+
+impl GarbageScheduler {
+    pub fn new(config: GarbageAttackConfig) -> Self {
+        Self {
+            config,
+            rng: StdRng::seed_from_u64(config.seed),
+            elapsed_ms: 0,
+        }
+    }
+
+    /// Returns a scheduler if the game config opts into garbage attacks
+    pub fn from_config(config: &GameConfig) -> Option<Self> {
+        config.garbage_attack.map(Self::new)
+    }
+
+    /// Advances the schedule by `delta_ms` and sends every attack that came
+    /// due. Time only counts while the game is being played. Returns the
+    /// number of garbage rows added.
+    pub fn update(&mut self, game: &mut Game, delta_ms: u64) -> u32 {
+        if !game.is_playing() {
+            return 0;
+        }
+
+        let holes = self.advance(delta_ms);
+        if !holes.is_empty() {
+            game.add_garbage_lines(&holes);
+        }
+        holes.len() as u32
+    }
+
+    /// Advances the schedule by `delta_ms` and returns the hole column of
+    /// every garbage row that came due, oldest first
+    pub fn advance(&mut self, delta_ms: u64) -> Vec<usize> {
+        if self.config.interval_ms == 0 {
+            return Vec::new();
+        }
+
+        self.elapsed_ms += delta_ms;
+        let mut holes = Vec::new();
+        while self.elapsed_ms >= self.config.interval_ms {
+            self.elapsed_ms -= self.config.interval_ms;
+            holes.extend(
+                (0..self.config.lines_per_attack).map(|_| self.rng.gen_range(0..GRID_WIDTH)),
+            );
+        }
+        holes
+    }
+}
+
+/// Timer behind a rising floor, owned by the game and advanced with its
+/// play time
+#[derive(Clone, Debug)]
+pub struct RisingFloor {
+    config: RisingFloorConfig,
+    rng: StdRng,
+    timer_ms: u64,
+    rises: u32,
+}
+
+impl RisingFloor {
+    pub fn new(config: RisingFloorConfig) -> Self {
+        Self {
+            config,
+            rng: StdRng::seed_from_u64(config.seed),
+            timer_ms: 0,
+            rises: 0,
+        }
+    }
+
+    /// Advances the timer by `delta_ms` and returns the hole column of every
+    /// row that came due, oldest first
+    pub fn advance(&mut self, delta_ms: u64) -> Vec<usize> {
+        self.timer_ms += delta_ms;
+        let mut holes = Vec::new();
+        while self.timer_ms >= self.config.interval_after(self.rises) {
+            self.timer_ms -= self.config.interval_after(self.rises);
+            self.rises += 1;
+            holes.push(self.rng.gen_range(0..GRID_WIDTH));
+        }
+        holes
+    }
+
+    /// Starts the wait for the next rise over, as a reward for a clear
+    pub fn delay(&mut self) {
+        self.timer_ms = 0;
+    }
+}
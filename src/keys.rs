@@ -0,0 +1,149 @@
+use crossterm::event::KeyCode;
+use serde::{Deserialize, Serialize};
+
+use crate::game::Input;
+
+// ============================================================================
+// Key Bindings
+// ============================================================================
+
+/// Keys bound to each game action. Letters are stored lowercase and match
+/// either case. Keys the app reserves (quit, pause, theme, F-keys) are
+/// handled before these bindings are consulted.
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub struct KeyBindings {
+    pub move_left: Vec<KeyCode>,
+    pub move_right: Vec<KeyCode>,
+    pub soft_drop: Vec<KeyCode>,
+    pub hard_drop: Vec<KeyCode>,
+    pub rotate_cw: Vec<KeyCode>,
+    pub rotate_ccw: Vec<KeyCode>,
+    pub hold: Vec<KeyCode>,
+}
+
+impl Default for KeyBindings {
+    fn default() -> Self {
+        Self {
+            move_left: vec![KeyCode::Char('a')],
+            move_right: vec![KeyCode::Char('d')],
+            soft_drop: vec![KeyCode::Char('s'), KeyCode::Char('j')],
+            hard_drop: vec![KeyCode::Char('w'), KeyCode::Char('k')],
+            rotate_cw: vec![KeyCode::Right, KeyCode::Char('l')],
+            rotate_ccw: vec![KeyCode::Left, KeyCode::Char('h')],
+            hold: vec![KeyCode::Char('c')],
+        }
+    }
+}
+
+impl KeyBindings {
+    /// Every action on the left half of the keyboard: WASD to move and
+    /// drop, Z/X to rotate, C to hold
+    pub fn left_hand() -> Self {
+        Self {
+            move_left: vec![KeyCode::Char('a')],
+            move_right: vec![KeyCode::Char('d')],
+            soft_drop: vec![KeyCode::Char('s')],
+            hard_drop: vec![KeyCode::Char('w')],
+            rotate_cw: vec![KeyCode::Char('x')],
+            rotate_ccw: vec![KeyCode::Char('z')],
+            hold: vec![KeyCode::Char('c')],
+        }
+    }
+
+    /// Every action on the right half of the keyboard: IJKL to move and
+    /// drop, U/O to rotate, M to hold
+    pub fn right_hand() -> Self {
+        Self {
+            move_left: vec![KeyCode::Char('j')],
+            move_right: vec![KeyCode::Char('l')],
+            soft_drop: vec![KeyCode::Char('k')],
+            hard_drop: vec![KeyCode::Char('i')],
+            rotate_cw: vec![KeyCode::Char('o')],
+            rotate_ccw: vec![KeyCode::Char('u')],
+            hold: vec![KeyCode::Char('m')],
+        }
+    }
+
+    /// The same bindings with left/right movement and CW/CCW rotation
+    /// swapped
+    pub fn mirrored(&self) -> Self {
+        Self {
+            move_left: self.move_right.clone(),
+            move_right: self.move_left.clone(),
+            rotate_cw: self.rotate_ccw.clone(),
+            rotate_ccw: self.rotate_cw.clone(),
+            ..self.clone()
+        }
+    }
+
+    /// One line naming the bound keys, left before right and CCW before CW,
+    /// e.g. `A D: Move | S/J W/K: Drop | ←/H →/L: Rotate | C: Hold`
+    pub fn hint(&self) -> String {
+        format!(
+            "{} {}: Move | {} {}: Drop | {} {}: Rotate | {}: Hold",
+            key_labels(&self.move_left),
+            key_labels(&self.move_right),
+            key_labels(&self.soft_drop),
+            key_labels(&self.hard_drop),
+            key_labels(&self.rotate_ccw),
+            key_labels(&self.rotate_cw),
+            key_labels(&self.hold),
+        )
+    }
+
+    /// The action bound to `code`, if any
+    pub fn input_for_key(&self, code: KeyCode) -> Option<Input> {
+        let code = match code {
+            KeyCode::Char(c) => KeyCode::Char(c.to_ascii_lowercase()),
+            other => other,
+        };
+        [
+            (&self.move_left, Input::MoveLeft),
+            (&self.move_right, Input::MoveRight),
+            (&self.soft_drop, Input::SoftDrop),
+            (&self.hard_drop, Input::HardDrop),
+            (&self.rotate_cw, Input::RotateCw),
+            (&self.rotate_ccw, Input::RotateCcw),
+            (&self.hold, Input::Hold),
+        ]
+        .into_iter()
+        .find(|(keys, _)| keys.contains(&code))
+        .map(|(_, input)| input)
+    }
+}
+
+/// How `keys` read on screen: letters uppercase, arrows as arrows,
+/// alternatives separated by slashes
+fn key_labels(keys: &[KeyCode]) -> String {
+    keys.iter()
+        .map(|&key| match key {
+            KeyCode::Char(c) => c.to_ascii_uppercase().to_string(),
+            KeyCode::Left => "←".to_string(),
+            KeyCode::Right => "→".to_string(),
+            KeyCode::Up => "↑".to_string(),
+            KeyCode::Down => "↓".to_string(),
+            other => format!("{:?}", other),
+        })
+        .collect::<Vec<_>>()
+        .join("/")
+}
+
+/// Built-in binding layouts that can be chosen in the settings
+#[derive(Clone, Copy, PartialEq, Eq, Debug, Default, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum KeyPreset {
+    #[default]
+    Standard,
+    LeftHand,
+    RightHand,
+}
+
+impl KeyPreset {
+    pub fn bindings(self) -> KeyBindings {
+        match self {
+            KeyPreset::Standard => KeyBindings::default(),
+            KeyPreset::LeftHand => KeyBindings::left_hand(),
+            KeyPreset::RightHand => KeyBindings::right_hand(),
+        }
+    }
+}
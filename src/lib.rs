@@ -1 +1,18 @@
+pub mod bitboard;
+pub mod clock;
+pub mod effects;
+pub mod finesse;
 pub mod game;
+pub mod garbage;
+pub mod keys;
+pub mod pieces;
+pub mod puzzle;
+pub mod replay;
+pub mod rotation;
+pub mod settings;
+pub mod snapshot;
+pub mod solver;
+pub mod sound;
+pub mod stats;
+pub mod theme;
+pub mod versus;
@@ -1,22 +1,35 @@
 use crossterm::{
-    event::{self, Event, KeyCode, KeyEventKind},
-    terminal::{disable_raw_mode, enable_raw_mode, EnterAlternateScreen, LeaveAlternateScreen},
+    event::{self, DisableFocusChange, EnableFocusChange, Event, KeyCode, KeyEventKind},
+    terminal::{self, disable_raw_mode, enable_raw_mode, EnterAlternateScreen, LeaveAlternateScreen},
     ExecutableCommand,
 };
+use rand::Rng;
 use ratatui::{
     backend::CrosstermBackend,
     layout::{Alignment, Constraint, Layout, Rect},
-    style::{Color, Style},
+    style::{Color, Modifier, Style},
     text::{Line, Span},
-    widgets::{Block, Borders, Paragraph},
+    widgets::{Block, Borders, Clear, Paragraph},
     Frame, Terminal,
 };
 use std::{
-    io::{self, stdout},
-    time::{Duration, Instant},
+    io::{self, stdout, Stdout},
+    time::{Duration, SystemTime, UNIX_EPOCH},
 };
 
-use tetris::game::{CellState, Game, GameState, TetrominoType, GRID_HEIGHT, GRID_WIDTH, PREVIEW_COUNT};
+use tetris::clock::{FrameTimer, SystemClock};
+use tetris::effects::{InvisibleStack, LockFlash, Settle};
+use tetris::replay::{Playback, Replay, FRAME_MS};
+use tetris::settings::Settings;
+use tetris::snapshot::GameSnapshot;
+use tetris::sound::{play_events, NoSound, SoundSink, TerminalBell};
+use tetris::theme::{next_theme_index, theme_index, CellShape, GhostStyle, Theme, THEMES};
+use tetris::game::{
+    board_from_file, board_to_string, daily_seed, CellState, FilteredPieceProvider, Game,
+    GameConfig, GameEvent, GameState, HoldOrder, Input, ModeProgress, PieceProvider, Position,
+    RenderCell, SeededPieceProvider, SequencePieceProvider, Tetromino, TetrominoType,
+    GRID_HEIGHT, GRID_WIDTH,
+};
 
 // ============================================================================
 // Visual Constants
@@ -25,20 +38,245 @@ use tetris::game::{CellState, Game, GameState, TetrominoType, GRID_HEIGHT, GRID_
 const CELL_WIDTH: u16 = 2;
 const BLOCK_CHAR: &str = "██";
 const EMPTY_CHAR: &str = "  ";
+const GARBAGE_CHAR: &str = "▓▓";
+const GARBAGE_COLOR: Color = Color::Gray;
+
+const TRAIL_CHAR: &str = "░░";
+const GHOST_OUTLINE_CHAR: &str = "[]";
+/// Landing spot of the held piece, drawn dim in its color
+const HELD_GHOST_CHAR: &str = "··";
+const HIDDEN_PREVIEW_CHAR: &str = "??";
+/// Lower half block, for a cell squashed as its piece lands
+const SETTLE_CHAR: &str = "▄▄";
+
+/// Background tint of rows the ghost would complete
+const CLEAR_PREVIEW_BG: Color = Color::Rgb(48, 48, 48);
+
+/// How long the landing cells of a hard drop stay highlighted
+const HARD_DROP_FLASH_MS: u64 = 120;
+
+/// How long a moved piece's previous cells stay visible (about one frame)
+const TRAIL_MS: u64 = 40;
+
+/// How long the banner for passing the high score stays up
+const NEW_BEST_BANNER_MS: u64 = 2000;
 
 // ============================================================================
-// Color Mapping
+// UI State
 // ============================================================================
 
-fn tetromino_color(t: TetrominoType) -> Color {
-    match t {
-        TetrominoType::I => Color::Cyan,
-        TetrominoType::O => Color::Yellow,
-        TetrominoType::T => Color::Magenta,
-        TetrominoType::S => Color::Green,
-        TetrominoType::Z => Color::Red,
-        TetrominoType::J => Color::Blue,
-        TetrominoType::L => Color::Rgb(255, 165, 0),
+/// Presentation toggles that live outside the game logic
+#[derive(Default)]
+struct UiState {
+    show_debug: bool,
+    show_trail: bool,
+    /// Draw the playfield upside down, row 0 at the bottom
+    flip_vertical: bool,
+    /// Practice mode: draw only the ghost, never the falling piece itself
+    ghost_only: bool,
+    /// Blind play: the next queue is drawn as placeholders
+    hide_preview: bool,
+    /// Upcoming pieces drawn in the Next panel
+    preview_shown: usize,
+    hard_drop_flash: Option<HardDropFlash>,
+    /// Time left on the "NEW BEST!" banner
+    new_best_ms: u64,
+    lock_flash: LockFlash,
+    settle: Settle,
+    /// Set in the invisible-blocks challenge
+    invisible: Option<InvisibleStack>,
+    /// Inputs applied this run, for the APM readout
+    actions: u32,
+    /// The bound game keys, from `KeyBindings::hint`
+    controls_hint: String,
+    /// Message shown under the controls hint, such as where a freeze
+    /// frame was written
+    status: Option<String>,
+    /// The current pause came from the terminal losing focus, so regaining
+    /// it resumes play
+    auto_paused: bool,
+    trail: Option<Trail>,
+    /// Current piece cells as of the previous frame
+    last_blocks: Vec<Position>,
+    /// Index into `THEMES`
+    theme_index: usize,
+    ghost_style: GhostStyle,
+    /// Also draw the held piece's landing spot
+    held_ghost: bool,
+    cell_shape: CellShape,
+    pause_menu: PauseMenu,
+    /// Slowed-down replay of the run's last moments, shown in place of the
+    /// game-over screen until it ends or a key skips it
+    instant_replay: Option<Playback>,
+}
+
+/// Highlight over the cells where a hard-dropped piece locked
+struct HardDropFlash {
+    piece: Tetromino,
+    remaining_ms: u64,
+}
+
+/// Faint copy of where the current piece was before its last move
+struct Trail {
+    blocks: Vec<Position>,
+    tetromino_type: TetrominoType,
+    remaining_ms: u64,
+}
+
+impl UiState {
+    fn theme(&self) -> &'static Theme {
+        &THEMES[self.theme_index]
+    }
+
+    /// Leaves a trail when the current piece moved or rotated since the last
+    /// frame. A new piece (spawn, hold, hard drop, restart) jumps rather than
+    /// moves, so it clears the trail instead.
+    fn track_piece(&mut self, piece: &Tetromino, events: &[GameEvent]) {
+        let blocks = piece.blocks();
+        let new_piece = events.iter().any(|event| {
+            matches!(
+                event,
+                GameEvent::PieceSpawned(_) | GameEvent::HardDrop(_) | GameEvent::GameRestarted
+            )
+        });
+
+        if new_piece {
+            self.trail = None;
+        } else if self.show_trail && blocks != self.last_blocks {
+            self.trail = Some(Trail {
+                blocks: std::mem::take(&mut self.last_blocks),
+                tetromino_type: piece.tetromino_type,
+                remaining_ms: TRAIL_MS,
+            });
+        }
+        self.last_blocks = blocks;
+    }
+
+    /// Starts or cancels effects based on this frame's game events
+    fn handle_events(&mut self, events: &[GameEvent]) {
+        for event in events {
+            match event {
+                GameEvent::HardDrop(piece) => {
+                    self.hard_drop_flash = Some(HardDropFlash {
+                        piece: piece.clone(),
+                        remaining_ms: HARD_DROP_FLASH_MS,
+                    });
+                }
+                // Cleared rows shift the board under the flash, so the clear
+                // takes over and the flash is dropped
+                GameEvent::LinesCleared(_) => {
+                    self.hard_drop_flash = None;
+                }
+                GameEvent::NewHighScore => {
+                    self.new_best_ms = NEW_BEST_BANNER_MS;
+                }
+                GameEvent::GameRestarted => {
+                    self.hard_drop_flash = None;
+                    self.new_best_ms = 0;
+                    self.actions = 0;
+                }
+                _ => {}
+            }
+        }
+    }
+
+    /// Actions per minute of play time this run
+    fn actions_per_minute(&self, game: &Game) -> u32 {
+        let elapsed_ms = game.elapsed_ms();
+        if elapsed_ms == 0 {
+            return 0;
+        }
+        (self.actions as u64 * 60_000 / elapsed_ms) as u32
+    }
+
+    /// Counts down running effects by the frame's elapsed time
+    fn advance(&mut self, delta_ms: u64) {
+        if let Some(flash) = &mut self.hard_drop_flash {
+            flash.remaining_ms = flash.remaining_ms.saturating_sub(delta_ms);
+            if flash.remaining_ms == 0 {
+                self.hard_drop_flash = None;
+            }
+        }
+        if let Some(trail) = &mut self.trail {
+            trail.remaining_ms = trail.remaining_ms.saturating_sub(delta_ms);
+            if trail.remaining_ms == 0 {
+                self.trail = None;
+            }
+        }
+        self.new_best_ms = self.new_best_ms.saturating_sub(delta_ms);
+        self.lock_flash.advance(delta_ms);
+        self.settle.advance(delta_ms);
+        if let Some(invisible) = &mut self.invisible {
+            invisible.advance(delta_ms);
+        }
+        if let Some(playback) = &mut self.instant_replay {
+            playback.advance(delta_ms);
+            if playback.is_finished() {
+                self.instant_replay = None;
+            }
+        }
+    }
+
+    /// Milliseconds until a running effect needs the next redraw
+    fn redraw_in_ms(&self) -> Option<u64> {
+        let flash = self.hard_drop_flash.as_ref().map(|flash| flash.remaining_ms);
+        let trail = self.trail.as_ref().map(|trail| trail.remaining_ms);
+        let lock_flash = self.lock_flash.remaining_ms();
+        let settle = self.settle.remaining_ms();
+        let invisible = self.invisible.as_ref().and_then(InvisibleStack::remaining_ms);
+        let new_best = (self.new_best_ms > 0).then_some(self.new_best_ms);
+        let replay = self.instant_replay.as_ref().map(Playback::time_until_frame_ms);
+        flash
+            .into_iter()
+            .chain(trail)
+            .chain(new_best)
+            .chain(replay)
+            .chain(lock_flash)
+            .chain(settle)
+            .chain(invisible)
+            .min()
+    }
+}
+
+// ============================================================================
+// Pause Menu
+// ============================================================================
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+enum PauseOption {
+    Resume,
+    Restart,
+    ChangeLevel,
+    Quit,
+}
+
+impl PauseOption {
+    const ALL: [PauseOption; 4] = [
+        PauseOption::Resume,
+        PauseOption::Restart,
+        PauseOption::ChangeLevel,
+        PauseOption::Quit,
+    ];
+}
+
+/// Options list shown while paused, navigated with the arrow keys
+#[derive(Default)]
+struct PauseMenu {
+    /// Index into `PauseOption::ALL`
+    selected: usize,
+}
+
+impl PauseMenu {
+    fn selected(&self) -> PauseOption {
+        PauseOption::ALL[self.selected]
+    }
+
+    fn select_previous(&mut self) {
+        self.selected = (self.selected + PauseOption::ALL.len() - 1) % PauseOption::ALL.len();
+    }
+
+    fn select_next(&mut self) {
+        self.selected = (self.selected + 1) % PauseOption::ALL.len();
     }
 }
 
@@ -46,53 +284,101 @@ fn tetromino_color(t: TetrominoType) -> Color {
 // Rendering
 // ============================================================================
 
-fn render(frame: &mut Frame, game: &Game) {
+fn render(frame: &mut Frame, game: &Game, ui: &UiState) {
     let area = frame.size();
 
-    match game.state {
-        GameState::Playing => render_game(frame, game, area),
-        GameState::Paused => render_paused(frame, game, area),
-        GameState::GameOver => render_game_over(frame, game, area),
+    if let Some(playback) = &ui.instant_replay {
+        render_game(frame, playback.game(), ui, area);
+    } else {
+        match game.state {
+            GameState::Playing => render_game(frame, game, ui, area),
+            GameState::Paused => render_paused(frame, game, ui, area),
+            GameState::GameOver => render_game_over(frame, game, ui, area),
+        }
+    }
+
+    if ui.show_debug {
+        render_debug(frame, game, area);
     }
 }
 
-fn render_game(frame: &mut Frame, game: &Game, area: Rect) {
+const HOLD_WIDTH: u16 = 12;
+const PREVIEW_WIDTH: u16 = 12;
+const INFO_WIDTH: u16 = 14;
+
+/// Height of the side panels, which keep the full-height layout whatever
+/// the cell shape
+const PANEL_HEIGHT: u16 = GRID_HEIGHT as u16 + 2;
+
+/// Width and height of the bordered playfield
+fn grid_display_size(shape: CellShape) -> (u16, u16) {
+    match shape {
+        CellShape::Wide => ((GRID_WIDTH as u16 * CELL_WIDTH) + 2, GRID_HEIGHT as u16 + 2),
+        CellShape::HalfBlock => (GRID_WIDTH as u16 + 2, GRID_HEIGHT.div_ceil(2) as u16 + 2),
+    }
+}
+
+/// Terminal size the game screen needs: the hold, grid, preview and info
+/// panels side by side, plus the controls hint below. 64x25 for the
+/// standard 10x20 board with wide cells.
+fn required_terminal_size(shape: CellShape) -> (u16, u16) {
+    let (grid_width, grid_height) = grid_display_size(shape);
+    let width = HOLD_WIDTH + grid_width + PREVIEW_WIDTH + INFO_WIDTH + 4;
+    (width, grid_height.max(PANEL_HEIGHT) + 3)
+}
+
+fn render_game(frame: &mut Frame, game: &Game, ui: &UiState, area: Rect) {
     // Calculate dimensions
-    let grid_display_width = (GRID_WIDTH as u16 * CELL_WIDTH) + 2;
-    let grid_display_height = GRID_HEIGHT as u16 + 2;
-    let preview_width = 12;
-    let info_width = 14;
-    let total_width = grid_display_width + preview_width + info_width + 4;
-    let total_height = grid_display_height + 3;
+    let (grid_display_width, grid_display_height) = grid_display_size(ui.cell_shape);
+    let (total_width, total_height) = required_terminal_size(ui.cell_shape);
 
     // Center everything
     let main_area = centered_rect(total_width, total_height, area);
 
     // Split vertically first: game area and controls
     let vertical = Layout::vertical([
-        Constraint::Length(grid_display_height),
+        Constraint::Length(grid_display_height.max(PANEL_HEIGHT)),
         Constraint::Fill(1),
     ])
     .split(main_area);
 
     let game_row = vertical[0];
 
-    // Layout: [Grid][Preview][Info]
+    // Layout: [Hold][Grid][Preview][Info]
     let horizontal = Layout::horizontal([
+        Constraint::Length(HOLD_WIDTH),
         Constraint::Length(grid_display_width),
-        Constraint::Length(preview_width),
-        Constraint::Length(info_width),
+        Constraint::Length(PREVIEW_WIDTH),
+        Constraint::Length(INFO_WIDTH),
     ])
     .split(game_row);
 
-    // Render game grid
-    render_grid(frame, game, horizontal[0]);
+    // Render hold slot
+    render_hold(frame, game, ui, horizontal[0]);
+
+    // Render game grid, top-aligned when it is shorter than the panels
+    let grid_area = Rect {
+        height: grid_display_height.min(horizontal[1].height),
+        ..horizontal[1]
+    };
+    render_grid(frame, game, ui, grid_area);
+    if ui.instant_replay.is_some() {
+        let replay = Span::styled("REPLAY", Style::default().fg(Color::Cyan));
+        let skip = Span::styled("any key skips", Style::default().fg(Color::DarkGray));
+        render_banner(frame, ui, grid_area, vec![Line::from(replay), Line::from(skip)]);
+    } else if ui.new_best_ms > 0 {
+        let new_best = Span::styled(
+            "NEW BEST!",
+            Style::default().fg(Color::Yellow).add_modifier(Modifier::BOLD),
+        );
+        render_banner(frame, ui, grid_area, vec![Line::from(new_best)]);
+    }
 
     // Render preview
-    render_preview(frame, game, horizontal[1]);
+    render_preview(frame, game, ui, horizontal[2]);
 
     // Render info panel
-    render_info(frame, game, horizontal[2]);
+    render_info(frame, game, ui, horizontal[3]);
 
     // Render controls hint below
     let controls_area = Rect {
@@ -103,126 +389,356 @@ fn render_game(frame: &mut Frame, game: &Game, area: Rect) {
     };
 
     if controls_area.y + 1 < area.height {
-        let controls = Paragraph::new(vec![Line::from(
-            "WASD/JK: Move/Drop | ←→/HL: Rotate | P: Pause | Q/ESC: Quit",
-        )])
+        let mut lines = vec![Line::from(format!(
+            "{} | T: Theme | P: Pause | Q/ESC: Quit",
+            ui.controls_hint
+        ))];
+        if let Some(status) = &ui.status {
+            lines.push(Line::from(status.as_str()));
+        }
+        let controls = Paragraph::new(lines)
         .alignment(Alignment::Center)
         .style(Style::default().fg(Color::DarkGray));
         frame.render_widget(controls, controls_area);
     }
 }
 
-fn render_grid(frame: &mut Frame, game: &Game, area: Rect) {
-    let block = Block::default()
-        .borders(Borders::ALL)
-        .title(" Tetris ")
-        .title_alignment(Alignment::Center);
+fn render_grid(frame: &mut Frame, game: &Game, ui: &UiState, area: Rect) {
+    let block = panel(" Tetris ", ui.theme());
 
     let inner = block.inner(area);
     frame.render_widget(block, area);
 
     // Get the complete visual grid state from game logic
     // This ensures rendering always matches game state
-    let visual_grid = game.render_grid();
+    let visual_grid = game.render_grid_detailed();
+    let flash_blocks = ui
+        .hard_drop_flash
+        .as_ref()
+        .map(|flash| flash.piece.blocks())
+        .unwrap_or_default();
+    let trail_style = ui.trail.as_ref().map_or(Style::default(), |trail| {
+        Style::default().fg(ui.theme().piece_color(trail.tetromino_type))
+    });
 
-    // Build grid display
-    let mut lines: Vec<Line> = Vec::new();
+    // In ghost-only practice the falling piece is hidden, except where it
+    // already sits on its landing spot: those cells show as the ghost
+    let ghost_blocks = if ui.ghost_only {
+        game.ghost_piece().blocks()
+    } else {
+        Vec::new()
+    };
 
-    for y in 0..GRID_HEIGHT {
-        let mut spans: Vec<Span> = Vec::new();
+    let held_ghost = if ui.held_ghost { game.held_ghost() } else { None };
+    let held_ghost_blocks = held_ghost.as_ref().map(Tetromino::blocks).unwrap_or_default();
+    let held_ghost_style = held_ghost.as_ref().map_or(Style::default(), |ghost| {
+        Style::default()
+            .fg(ui.theme().piece_color(ghost.tetromino_type))
+            .add_modifier(Modifier::DIM)
+    });
+
+    // Rows a hard drop would clear, shown only alongside a visible ghost
+    let clearing_rows = if ui.ghost_style == GhostStyle::Hidden && !ui.ghost_only {
+        Vec::new()
+    } else {
+        game.rows_cleared_by_ghost()
+    };
 
-        for x in 0..GRID_WIDTH {
-            let (symbol, style) = match visual_grid[y][x] {
-                CellState::Empty => (EMPTY_CHAR, Style::default()),
-                CellState::Filled(piece_type) => {
-                    (BLOCK_CHAR, Style::default().fg(tetromino_color(piece_type)))
+    // Styled cells, one row per grid row
+    let mut rows: Vec<Vec<(&str, Style)>> = Vec::new();
+
+    for (y, row) in visual_grid.iter().enumerate().take(GRID_HEIGHT) {
+        let mut cells: Vec<(&str, Style)> = Vec::new();
+
+        for (x, cell) in row.iter().enumerate().take(GRID_WIDTH) {
+            let flashing = flash_blocks
+                .iter()
+                .any(|block| block.x == x as i16 && block.y == y as i16);
+            let lock_flashing = ui
+                .lock_flash
+                .cells()
+                .iter()
+                .any(|block| block.x == x as i16 && block.y == y as i16);
+            let squashed = ui
+                .settle
+                .squashed_cells()
+                .iter()
+                .any(|block| block.x == x as i16 && block.y == y as i16);
+            let trailing = ui.trail.as_ref().is_some_and(|trail| {
+                trail.blocks.iter().any(|block| block.x == x as i16 && block.y == y as i16)
+            });
+            let held_ghosting = held_ghost_blocks
+                .iter()
+                .any(|block| block.x == x as i16 && block.y == y as i16);
+            let invisible = ui.invisible.as_ref().is_some_and(|invisible| {
+                invisible.hides(Position { x: x as i16, y: y as i16 })
+            });
+            let on_ghost = ghost_blocks
+                .iter()
+                .any(|block| block.x == x as i16 && block.y == y as i16);
+            let cell = match *cell {
+                RenderCell::Active(piece_type, color) if ui.ghost_only && on_ghost => {
+                    RenderCell::Ghost(piece_type, color)
+                }
+                RenderCell::Active(..) if ui.ghost_only => RenderCell::Empty,
+                // The ghost is all there is to see in ghost-only practice
+                RenderCell::Ghost(..) if ui.ghost_style == GhostStyle::Hidden && !ui.ghost_only => {
+                    RenderCell::Empty
+                }
+                RenderCell::Locked(..) if invisible => RenderCell::Empty,
+                cell => cell,
+            };
+            let (symbol, style) = match cell {
+                // A trail would give away where the hidden piece is moving
+                RenderCell::Empty if trailing && !ui.ghost_only => (TRAIL_CHAR, trail_style),
+                // Only on empty cells, so it never hides the current ghost
+                RenderCell::Empty if held_ghosting => (HELD_GHOST_CHAR, held_ghost_style),
+                RenderCell::Empty => (EMPTY_CHAR, Style::default()),
+                RenderCell::Locked(piece_type, color) | RenderCell::Active(piece_type, color)
+                    if flashing =>
+                {
+                    let highlight = ui.theme().cell_highlight_color(piece_type, color);
+                    (BLOCK_CHAR, Style::default().fg(highlight))
+                }
+                // The hard drop flash above wins over the plain lock flash
+                RenderCell::Locked(..) if lock_flashing => {
+                    (BLOCK_CHAR, Style::default().fg(ui.theme().lock_flash))
+                }
+                RenderCell::Locked(piece_type, color) | RenderCell::Active(piece_type, color) => {
+                    (BLOCK_CHAR, Style::default().fg(ui.theme().cell_color(piece_type, color)))
                 }
+                RenderCell::Ghost(piece_type, color) if ui.ghost_style == GhostStyle::Outline => {
+                    let outline = ui.theme().cell_color(piece_type, color);
+                    (GHOST_OUTLINE_CHAR, Style::default().fg(outline))
+                }
+                RenderCell::Ghost(piece_type, color) => (
+                    BLOCK_CHAR,
+                    Style::default()
+                        .fg(ui.theme().cell_color(piece_type, color))
+                        .add_modifier(Modifier::DIM),
+                ),
+                RenderCell::Garbage => (GARBAGE_CHAR, Style::default().fg(GARBAGE_COLOR)),
+            };
+            let symbol = if squashed && matches!(cell, RenderCell::Locked(..)) {
+                SETTLE_CHAR
+            } else {
+                symbol
             };
 
-            spans.push(Span::styled(symbol, style));
+            let style = if clearing_rows.contains(&y) {
+                style.bg(CLEAR_PREVIEW_BG)
+            } else {
+                style
+            };
+            cells.push((symbol, style));
         }
 
-        lines.push(Line::from(spans));
+        rows.push(cells);
     }
 
+    // Only the draw order flips: `x`/`y` above, and every index the game
+    // logic uses, still count row 0 from the top
+    if ui.flip_vertical {
+        rows.reverse();
+    }
+
+    let lines: Vec<Line> = match ui.cell_shape {
+        CellShape::Wide => rows
+            .into_iter()
+            .map(|row| {
+                let spans: Vec<Span> =
+                    row.into_iter().map(|(symbol, style)| Span::styled(symbol, style)).collect();
+                Line::from(spans)
+            })
+            .collect(),
+        CellShape::HalfBlock => rows
+            .chunks(2)
+            .map(|pair| {
+                let spans: Vec<Span> = (0..GRID_WIDTH)
+                    .map(|x| {
+                        let upper = half_block_color(pair[0][x]);
+                        let lower = pair.get(1).and_then(|row| half_block_color(row[x]));
+                        half_block_span(upper, lower)
+                    })
+                    .collect();
+                Line::from(spans)
+            })
+            .collect(),
+    };
+
     let paragraph = Paragraph::new(lines);
     frame.render_widget(paragraph, inner);
 }
 
-fn render_preview(frame: &mut Frame, game: &Game, area: Rect) {
-    let block = Block::default()
-        .borders(Borders::ALL)
-        .title(" Next ")
-        .title_alignment(Alignment::Center);
+/// Color a wide cell becomes in half-block mode. Ghosts can't be dimmed or
+/// outlined in half a character, so they turn gray; trails are dropped.
+fn half_block_color((symbol, style): (&str, Style)) -> Option<Color> {
+    match symbol {
+        EMPTY_CHAR | TRAIL_CHAR => style.bg,
+        GHOST_OUTLINE_CHAR => Some(Color::DarkGray),
+        _ if style.add_modifier.contains(Modifier::DIM) => Some(Color::DarkGray),
+        _ => style.fg,
+    }
+}
+
+/// One terminal character showing two vertically stacked cells
+fn half_block_span(upper: Option<Color>, lower: Option<Color>) -> Span<'static> {
+    match (upper, lower) {
+        (None, None) => Span::raw(" "),
+        (Some(upper), None) => Span::styled("▀", Style::default().fg(upper)),
+        (None, Some(lower)) => Span::styled("▄", Style::default().fg(lower)),
+        (Some(upper), Some(lower)) => Span::styled("▀", Style::default().fg(upper).bg(lower)),
+    }
+}
+
+fn render_hold(frame: &mut Frame, game: &Game, ui: &UiState, area: Rect) {
+    let block = panel(" Hold ", ui.theme());
 
     let inner = block.inner(area);
     frame.render_widget(block, area);
 
-    let mut lines: Vec<Line> = Vec::new();
+    // Held pieces listed in the order they come back out
+    let held: Vec<TetrominoType> = match game.config.hold_order {
+        HoldOrder::Lifo => game.held_pieces.iter().rev().copied().collect(),
+        HoldOrder::Fifo => game.held_pieces.iter().copied().collect(),
+    };
 
-    for (i, &tetromino_type) in game.preview_queue.iter().take(PREVIEW_COUNT).enumerate() {
+    let mut lines: Vec<Line> = Vec::new();
+    for (i, held_type) in held.into_iter().enumerate() {
         if i > 0 {
             lines.push(Line::from(""));
         }
 
-        let shapes = tetromino_type.shapes();
-        let shape = &shapes[0];
-        let color = tetromino_color(tetromino_type);
+        // Dark gray while the hold is used up for this piece, back to the
+        // piece's own color once the next piece locks
+        let color = if game.can_hold {
+            ui.theme().piece_color(held_type)
+        } else {
+            Color::DarkGray
+        };
+        lines.extend(piece_lines(held_type, Style::default().fg(color), inner.width));
+    }
+
+    let paragraph = Paragraph::new(lines);
+    frame.render_widget(paragraph, inner);
+}
+
+/// Rows of a piece in its spawn rotation, centered horizontally in `width`
+fn piece_lines(tetromino_type: TetrominoType, style: Style, width: u16) -> Vec<Line<'static>> {
+    let piece = Tetromino::new_at(tetromino_type, 0, 0);
+    let blocks = piece.blocks();
 
-        // Find bounding box
-        let max_y = shape.iter().map(|(_, y)| *y).max().unwrap_or(0);
+    // Find bounding box and center it horizontally in the panel
+    let (min_x, _, max_x, max_y) = piece.bounding_box();
+    let shape_width = (max_x - min_x + 1) as u16 * CELL_WIDTH;
+    let padding = " ".repeat(width.saturating_sub(shape_width) as usize / 2);
 
-        for y in 0i16..=max_y {
-            let mut spans: Vec<Span> = Vec::new();
-            spans.push(Span::raw(" "));
+    let mut lines = Vec::new();
+    for y in 0i16..=max_y {
+        let mut spans: Vec<Span> = Vec::new();
+        spans.push(Span::raw(padding.clone()));
 
-            for x in 0i16..4i16 {
-                if shape.contains(&(x, y)) {
-                    spans.push(Span::styled(BLOCK_CHAR, Style::default().fg(color)));
-                } else {
-                    spans.push(Span::raw(EMPTY_CHAR));
-                }
+        for x in min_x..=max_x {
+            if blocks.contains(&Position { x, y }) {
+                spans.push(Span::styled(BLOCK_CHAR, style));
+            } else {
+                spans.push(Span::raw(EMPTY_CHAR));
             }
+        }
+
+        lines.push(Line::from(spans));
+    }
+    lines
+}
+
+fn render_preview(frame: &mut Frame, game: &Game, ui: &UiState, area: Rect) {
+    let block = panel(" Next ", ui.theme());
+
+    let inner = block.inner(area);
+    frame.render_widget(block, area);
+
+    let mut lines: Vec<Line> = Vec::new();
 
-            lines.push(Line::from(spans));
+    for (i, tetromino_type) in game.preview(ui.preview_shown).into_iter().enumerate() {
+        if i > 0 {
+            lines.push(Line::from(""));
         }
+
+        if ui.hide_preview {
+            // Same slots, but each is a gray 2x2 block of question marks
+            // so nothing about the piece shows through
+            let placeholder = HIDDEN_PREVIEW_CHAR.repeat(2);
+            let line = Line::from(placeholder).alignment(Alignment::Center);
+            let style = Style::default().fg(Color::DarkGray);
+            lines.extend([line.clone().style(style), line.style(style)]);
+            continue;
+        }
+
+        let style = Style::default().fg(ui.theme().piece_color(tetromino_type));
+        lines.extend(piece_lines(tetromino_type, style, inner.width));
     }
 
     let paragraph = Paragraph::new(lines);
     frame.render_widget(paragraph, inner);
 }
 
-fn render_info(frame: &mut Frame, game: &Game, area: Rect) {
-    let block = Block::default()
-        .borders(Borders::ALL)
-        .title(" Info ")
-        .title_alignment(Alignment::Center);
+fn render_info(frame: &mut Frame, game: &Game, ui: &UiState, area: Rect) {
+    let block = panel(" Info ", ui.theme());
 
     let inner = block.inner(area);
     frame.render_widget(block, area);
 
-    let lines = vec![
+    let mut lines = vec![
         Line::from(""),
         Line::from(Span::styled("High Score", Style::default().fg(Color::Magenta))),
         Line::from(format!("{}", game.high_score)),
         Line::from(""),
         Line::from(Span::styled("Score", Style::default().fg(Color::Yellow))),
         Line::from(format!("{}", game.score)),
+        Line::from(Span::styled(
+            match game.score_to_high_score() {
+                Some(_) if game.high_score == 0 => String::new(),
+                Some(remaining) => format!("{} to beat", remaining),
+                None => "New best!".to_string(),
+            },
+            Style::default().fg(Color::DarkGray),
+        )),
         Line::from(""),
         Line::from(Span::styled("Lines", Style::default().fg(Color::Cyan))),
         Line::from(format!("{}", game.lines_cleared)),
         Line::from(""),
         Line::from(Span::styled("Level", Style::default().fg(Color::Green))),
         Line::from(format!("{}", game.level)),
+        Line::from(""),
+        Line::from(Span::styled("PPS   APM", Style::default().fg(Color::Blue))),
+        Line::from(format!(
+            "{:<5.2} {:>3}",
+            game.pieces_per_second(),
+            ui.actions_per_minute(game)
+        )),
     ];
 
+    // Goal countdown for Sprint and Ultra
+    if let Some(progress) = game.mode_progress() {
+        let (label, value) = match progress {
+            ModeProgress::LinesRemaining(lines) => ("Lines Left", lines.to_string()),
+            ModeProgress::TimeRemainingMs(ms) => {
+                let secs = ms.div_ceil(1000);
+                ("Time Left", format!("{}:{:02}", secs / 60, secs % 60))
+            }
+        };
+        lines.push(Line::from(""));
+        lines.push(Line::from(Span::styled(label, Style::default().fg(Color::Red))));
+        lines.push(Line::from(value));
+    }
+
     let paragraph = Paragraph::new(lines).alignment(Alignment::Center);
     frame.render_widget(paragraph, inner);
 }
 
-fn render_game_over(frame: &mut Frame, game: &Game, area: Rect) {
+fn render_game_over(frame: &mut Frame, game: &Game, ui: &UiState, area: Rect) {
     // First render the game in background
-    render_game(frame, game, area);
+    render_game(frame, game, ui, area);
 
     // Then overlay game over popup
     let text = vec![
@@ -255,24 +771,34 @@ fn render_game_over(frame: &mut Frame, game: &Game, area: Rect) {
     frame.render_widget(paragraph, popup_area);
 }
 
-fn render_paused(frame: &mut Frame, game: &Game, area: Rect) {
-    // First render the game in background
-    render_game(frame, game, area);
+fn render_paused(frame: &mut Frame, game: &Game, ui: &UiState, area: Rect) {
+    // First render the game in background, dimmed behind the menu
+    render_game(frame, game, ui, area);
+    frame.buffer_mut().set_style(area, Style::default().add_modifier(Modifier::DIM));
 
-    // Then overlay paused popup
-    let text = vec![
+    // Then overlay the menu
+    let mut text = vec![
         Line::from(""),
         Line::from(Span::styled("PAUSED", Style::default().fg(Color::Yellow))),
         Line::from(""),
-        Line::from(Span::styled(
-            "Press P to continue",
-            Style::default().fg(Color::DarkGray),
-        )),
-        Line::from(Span::styled(
-            "Press ESC to quit",
-            Style::default().fg(Color::DarkGray),
-        )),
     ];
+    for (index, option) in PauseOption::ALL.iter().enumerate() {
+        let label = match option {
+            PauseOption::Resume => "Resume".to_string(),
+            PauseOption::Restart => "Restart".to_string(),
+            PauseOption::ChangeLevel => format!("Level < {} >", game.level),
+            PauseOption::Quit => "Quit".to_string(),
+        };
+        let line = if index == ui.pause_menu.selected {
+            Span::styled(
+                format!("> {} <", label),
+                Style::default().fg(Color::White).add_modifier(Modifier::BOLD),
+            )
+        } else {
+            Span::styled(label, Style::default().fg(Color::Gray))
+        };
+        text.push(Line::from(line));
+    }
 
     let paragraph = Paragraph::new(text).alignment(Alignment::Center).block(
         Block::default()
@@ -286,6 +812,74 @@ fn render_paused(frame: &mut Frame, game: &Game, area: Rect) {
     frame.render_widget(paragraph, popup_area);
 }
 
+/// Boxed banner across the top of the playfield, such as for passing the
+/// high score
+fn render_banner(frame: &mut Frame, ui: &UiState, grid_area: Rect, lines: Vec<Line>) {
+    let text_width = lines.iter().map(Line::width).max().unwrap_or(0) as u16;
+    let width = (text_width + 4).min(grid_area.width);
+    let banner_area = Rect {
+        x: grid_area.x + (grid_area.width - width) / 2,
+        y: grid_area.y + 2,
+        width,
+        height: (lines.len() as u16 + 2).min(grid_area.height.saturating_sub(2)),
+    };
+    let banner = Paragraph::new(lines)
+        .alignment(Alignment::Center)
+        .block(panel("", ui.theme()));
+    frame.render_widget(Clear, banner_area);
+    frame.render_widget(banner, banner_area);
+}
+
+fn render_debug(frame: &mut Frame, game: &Game, area: Rect) {
+    let piece = &game.current_piece;
+    let blocks = piece.blocks();
+    // Two blocks per line, however many cells the piece has
+    let block_lines = blocks.chunks(2).map(|pair| {
+        let text = pair
+            .iter()
+            .map(|b| format!("({},{})", b.x, b.y))
+            .collect::<Vec<_>>()
+            .join(" ");
+        Line::from(text)
+    });
+
+    let mut text = vec![
+        Line::from(format!("Type: {:?}", piece.tetromino_type)),
+        Line::from(format!("Rotation: {:?}", piece.rotation_state())),
+        Line::from(format!("Pos: ({}, {})", piece.position.x, piece.position.y)),
+        Line::from("Blocks:"),
+    ];
+    text.extend(block_lines);
+    text.push(Line::from(format!("Ghost y: {}", game.ghost_piece().position.y)));
+    let height = text.len() as u16 + 2;
+
+    let paragraph = Paragraph::new(text).block(
+        Block::default()
+            .borders(Borders::ALL)
+            .title(" Debug ")
+            .style(Style::default().fg(Color::DarkGray).bg(Color::Black)),
+    );
+
+    let debug_area = Rect {
+        x: area.x,
+        y: area.y,
+        width: 20.min(area.width),
+        height: height.min(area.height),
+    };
+    frame.render_widget(paragraph, debug_area);
+}
+
+/// Bordered panel in the theme's border, title and background colors
+fn panel<'a>(title: &'a str, theme: &Theme) -> Block<'a> {
+    Block::default()
+        .borders(Borders::ALL)
+        .title(title)
+        .title_alignment(Alignment::Center)
+        .border_style(Style::default().fg(theme.border))
+        .title_style(Style::default().fg(theme.title))
+        .style(Style::default().bg(theme.background))
+}
+
 fn centered_rect(width: u16, height: u16, area: Rect) -> Rect {
     let horizontal = Layout::horizontal([
         Constraint::Fill(1),
@@ -304,71 +898,560 @@ fn centered_rect(width: u16, height: u16, area: Rect) -> Rect {
     vertical[1]
 }
 
+// ============================================================================
+// Intro
+// ============================================================================
+
+/// Redraw interval of the title screen
+const INTRO_FRAME_MS: u64 = 50;
+
+/// Time between new pieces starting to fall behind the title
+const RAIN_SPAWN_MS: u64 = 300;
+
+/// Range of fall speeds, in terminal rows per second
+const RAIN_SPEED: std::ops::Range<f32> = 4.0..12.0;
+
+/// One background piece on the title screen
+struct RainDrop {
+    piece: Tetromino,
+    /// Terminal row of the piece's top, fractional so slow pieces keep
+    /// their speed between whole-row steps
+    y: f32,
+    rows_per_second: f32,
+}
+
+/// Random pieces falling behind the title, in the theme's colors. Purely
+/// cosmetic: it never touches the game.
+#[derive(Default)]
+struct PieceRain {
+    drops: Vec<RainDrop>,
+    spawn_timer_ms: u64,
+}
+
+impl PieceRain {
+    /// Moves every piece down by `delta_ms` of its own speed, forgets the
+    /// ones below the screen, and starts a new one every `RAIN_SPAWN_MS` in
+    /// a random one of `columns` cell columns
+    fn advance(&mut self, delta_ms: u64, columns: u16, rows: u16) {
+        for drop in &mut self.drops {
+            drop.y += drop.rows_per_second * delta_ms as f32 / 1000.0;
+        }
+        self.drops.retain(|drop| drop.y < rows as f32);
+
+        let mut rng = rand::thread_rng();
+        self.spawn_timer_ms += delta_ms;
+        while self.spawn_timer_ms >= RAIN_SPAWN_MS {
+            self.spawn_timer_ms -= RAIN_SPAWN_MS;
+            let tetromino_type = TetrominoType::ALL[rng.gen_range(0..TetrominoType::ALL.len())];
+            let x = rng.gen_range(0..columns.max(1)) as i16;
+            let mut piece = Tetromino::new_at(tetromino_type, x, 0);
+            piece.rotation = rng.gen_range(0..4);
+            self.drops.push(RainDrop {
+                piece,
+                y: -4.0,
+                rows_per_second: rng.gen_range(RAIN_SPEED),
+            });
+        }
+    }
+}
+
+/// Shows the title over falling pieces until a key is pressed. The rain
+/// advances by the measured time between frames, which are drawn every
+/// `INTRO_FRAME_MS` or right after a non-key event. Returns false when the
+/// key was a quit key, true to start playing.
+fn run_intro(terminal: &mut Terminal<CrosstermBackend<Stdout>>, theme: &Theme) -> io::Result<bool> {
+    let mut rain = PieceRain::default();
+    let mut frame_timer = FrameTimer::new(SystemClock::new());
+    loop {
+        let size = terminal.size()?;
+        rain.advance(frame_timer.delta_ms(), size.width / CELL_WIDTH, size.height);
+        terminal.draw(|frame| render_intro(frame, &rain, theme))?;
+
+        if event::poll(Duration::from_millis(INTRO_FRAME_MS))? {
+            if let Event::Key(key) = event::read()? {
+                if key.kind == KeyEventKind::Press {
+                    let quit = matches!(
+                        key.code,
+                        KeyCode::Esc | KeyCode::Char('q') | KeyCode::Char('Q')
+                    );
+                    return Ok(!quit);
+                }
+            }
+        }
+    }
+}
+
+fn render_intro(frame: &mut Frame, rain: &PieceRain, theme: &Theme) {
+    let area = frame.size();
+    let buffer = frame.buffer_mut();
+    buffer.set_style(area, Style::default().bg(theme.background));
+    for drop in &rain.drops {
+        let style = Style::default()
+            .fg(theme.piece_color(drop.piece.tetromino_type))
+            .add_modifier(Modifier::DIM);
+        for block in drop.piece.blocks() {
+            let x = block.x * CELL_WIDTH as i16;
+            let y = drop.y.floor() as i16 + block.y;
+            let on_screen = x >= 0
+                && y >= 0
+                && x as u16 + CELL_WIDTH <= area.width
+                && (y as u16) < area.height;
+            if on_screen {
+                buffer.set_string(area.x + x as u16, area.y + y as u16, BLOCK_CHAR, style);
+            }
+        }
+    }
+
+    let title_area = centered_rect(28, 7, area);
+    let text = vec![
+        Line::from(""),
+        Line::from(Span::styled("T E T R I S", Style::default().add_modifier(Modifier::BOLD))),
+        Line::from(""),
+        Line::from("Press any key to start"),
+        Line::from("Q to quit"),
+    ];
+    let paragraph = Paragraph::new(text)
+        .alignment(Alignment::Center)
+        .block(panel("", theme));
+    frame.render_widget(paragraph, title_area);
+}
+
+// ============================================================================
+// Settings
+// ============================================================================
+
+/// Preferences such as the color theme, saved on quit
+const SETTINGS_FILE: &str = "settings.json";
+
+// ============================================================================
+// Autosave
+// ============================================================================
+
+/// Snapshot of the run, written whenever the game is paused so a crash
+/// doesn't lose it. Removed on a clean quit or when the game ends.
+const AUTOSAVE_FILE: &str = "autosave.json";
+
+/// Asks on the plain terminal, before raw mode, whether to continue a saved
+/// run. Anything but an explicit no resumes.
+fn confirm_resume() -> bool {
+    print!("A saved game was found. Resume it? [Y/n] ");
+    let _ = io::Write::flush(&mut stdout());
+    let mut answer = String::new();
+    if io::stdin().read_line(&mut answer).is_err() {
+        return false;
+    }
+    !answer.trim().eq_ignore_ascii_case("n")
+}
+
+// ============================================================================
+// Instant Replay
+// ============================================================================
+
+/// Game time the instant replay on game over looks back over
+const INSTANT_REPLAY_MS: u64 = 5000;
+
+/// How many times slower than live the instant replay plays
+const INSTANT_REPLAY_SLOWDOWN: u64 = 2;
+
+/// Steps the game in whole replay frames and records the player's inputs
+/// on them, so the end of a run can be played back. Runs a seed and rule
+/// set can't rebuild (a fixed sequence, filtered pieces, a loaded board, a
+/// resumed save or a level picked mid-run) play the same but aren't kept.
+struct Recorder {
+    replay: Option<Replay>,
+    /// Whether restarts deal from a seed, so the new run can be recorded
+    replayable: bool,
+    /// Seed every restart deals from, or `None` for a fresh random one
+    fixed_seed: Option<u64>,
+    frame: u64,
+    /// Elapsed time not yet making up a whole frame
+    carry_ms: u64,
+}
+
+impl Recorder {
+    fn apply(&mut self, game: &mut Game, input: Input) {
+        if let Some(replay) = &mut self.replay {
+            replay.record(self.frame, input);
+        }
+        game.apply_input(input);
+    }
+
+    /// Ticks the game through the whole frames `delta_ms` completes. Time
+    /// paused or after the game ends is dropped, as replays never pause.
+    fn advance(&mut self, game: &mut Game, delta_ms: u64) {
+        if !game.is_playing() {
+            self.carry_ms = 0;
+            return;
+        }
+        self.carry_ms += delta_ms;
+        while self.carry_ms >= FRAME_MS && game.is_playing() {
+            game.tick_with_delta(FRAME_MS);
+            self.carry_ms -= FRAME_MS;
+            self.frame += 1;
+        }
+    }
+
+    fn restart(&mut self, game: &mut Game) {
+        self.replay = None;
+        self.frame = 0;
+        self.carry_ms = 0;
+        if self.replayable {
+            let seed = self.fixed_seed.unwrap_or_else(rand::random);
+            game.set_piece_provider(Box::new(SeededPieceProvider::new(seed)));
+            self.replay = Some(Replay::new(seed, game.config.clone()));
+        }
+        game.restart();
+    }
+
+    /// Stops recording a run that no longer follows from its seed
+    fn stop(&mut self) {
+        self.replay = None;
+    }
+
+    /// The last moments of the run just ended. The frame the game ended
+    /// on is played through too, as its input may be what ended it.
+    fn instant_replay(&self) -> Option<Playback> {
+        let replay = self.replay.clone()?;
+        Some(Playback::tail(
+            replay,
+            self.frame + 1,
+            INSTANT_REPLAY_MS,
+            INSTANT_REPLAY_SLOWDOWN,
+        ))
+    }
+}
+
+// ============================================================================
+// Session Stats
+// ============================================================================
+
+/// One file per finished run, named by its end time
+fn stats_file_name() -> String {
+    let secs = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map_or(0, |d| d.as_secs());
+    format!("stats-{secs}.json")
+}
+
+// ============================================================================
+// Freeze Frame
+// ============================================================================
+
+/// Writes the board as drawn, current piece included, followed by the
+/// snapshot JSON to a timestamped file for attaching to bug reports.
+/// Returns the file's name.
+fn write_freeze_frame(game: &Game) -> io::Result<String> {
+    let millis = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map_or(0, |d| d.as_millis());
+    let path = format!("freeze-{millis}.txt");
+    let snapshot = serde_json::to_string_pretty(&game.to_snapshot())?;
+    let contents = format!(
+        "Board:\n{}\nSnapshot:\n{}\n",
+        board_to_string(&game.render_grid()),
+        snapshot
+    );
+    std::fs::write(&path, contents)?;
+    Ok(path)
+}
+
+// ============================================================================
+// Command Line
+// ============================================================================
+
+/// Value following `flag`, `None` if the flag is absent, or an error
+/// message if it is the last argument
+fn flag_value<'a>(args: &'a [String], flag: &str, usage: &str) -> Result<Option<&'a str>, String> {
+    let Some(position) = args.iter().position(|arg| arg == flag) else {
+        return Ok(None);
+    };
+    match args.get(position + 1) {
+        Some(value) => Ok(Some(value)),
+        None => Err(format!("{} needs {}", flag, usage)),
+    }
+}
+
+/// Piece types spelled by `letters`, or an error message naming `flag` for
+/// an empty list or an unknown letter
+fn piece_letters(flag: &str, letters: &str, usage: &str) -> Result<Vec<TetrominoType>, String> {
+    let pieces = letters
+        .chars()
+        .map(|letter| {
+            TetrominoType::from_letter(letter)
+                .ok_or(format!("unknown piece '{}' in {}", letter, flag))
+        })
+        .collect::<Result<Vec<_>, _>>()?;
+    if pieces.is_empty() {
+        return Err(format!("{} needs {}", flag, usage));
+    }
+    Ok(pieces)
+}
+
+/// Piece types named by the letters after `--only` (e.g. `--only SZ`), or
+/// an error message for a missing or unknown letter
+fn only_pieces_arg(args: &[String]) -> Result<Option<Vec<TetrominoType>>, String> {
+    const USAGE: &str = "piece letters, e.g. --only SZ";
+    flag_value(args, "--only", USAGE)?
+        .map(|letters| piece_letters("--only", letters, USAGE))
+        .transpose()
+}
+
+/// Pieces to deal in order, repeating, from `--sequence` (e.g.
+/// `--sequence IOTJ`)
+fn sequence_arg(args: &[String]) -> Result<Option<Vec<TetrominoType>>, String> {
+    const USAGE: &str = "piece letters, e.g. --sequence IOTJ";
+    flag_value(args, "--sequence", USAGE)?
+        .map(|letters| piece_letters("--sequence", letters, USAGE))
+        .transpose()
+}
+
+/// Seed for the piece dealer from `--seed` (e.g. `--seed 42`)
+fn seed_arg(args: &[String]) -> Result<Option<u64>, String> {
+    flag_value(args, "--seed", "a number, e.g. --seed 42")?
+        .map(|seed| seed.parse().map_err(|_| format!("--seed needs a number, got '{}'", seed)))
+        .transpose()
+}
+
+/// Starting stack loaded from the file after `--board`, in the
+/// `board_to_string` format
+fn board_arg(args: &[String]) -> Result<Option<Vec<Vec<CellState>>>, String> {
+    flag_value(args, "--board", "a board file, e.g. --board stack.txt")?
+        .map(|path| board_from_file(path).map_err(|err| format!("{}: {}", path, err)))
+        .transpose()
+}
+
+/// Unwraps a parsed argument, or prints its error and exits
+fn or_exit<T>(parsed: Result<T, String>) -> T {
+    parsed.unwrap_or_else(|message| {
+        eprintln!("{}", message);
+        std::process::exit(2);
+    })
+}
+
 // ============================================================================
 // Main Loop
 // ============================================================================
 
 fn main() -> io::Result<()> {
+    let args: Vec<String> = std::env::args().collect();
+    let only_pieces = or_exit(only_pieces_arg(&args));
+    let sequence = or_exit(sequence_arg(&args));
+    let seed = or_exit(seed_arg(&args));
+    let board = or_exit(board_arg(&args));
+
+    let mut settings = Settings::load(SETTINGS_FILE);
+
+    // The centered layout would clip on a smaller terminal, so refuse to
+    // start rather than draw a broken screen
+    let (min_width, min_height) = required_terminal_size(settings.cell_shape);
+    let (width, height) = terminal::size()?;
+    if width < min_width || height < min_height {
+        eprintln!(
+            "Terminal is {}x{}, but the game needs at least {}x{}. Enlarge it and try again.",
+            width, height, min_width, min_height
+        );
+        std::process::exit(1);
+    }
+
+    let resume = GameSnapshot::load(AUTOSAVE_FILE).ok().filter(|_| confirm_resume());
+
     // Setup terminal
     enable_raw_mode()?;
     stdout().execute(EnterAlternateScreen)?;
+    stdout().execute(EnableFocusChange)?;
     let backend = CrosstermBackend::new(stdout());
     let mut terminal = Terminal::new(backend)?;
 
-    // Create game
-    let mut game = Game::new();
-    let mut last_tick = Instant::now();
+    // Create game: a fixed sequence or seed when asked for reproducibility,
+    // otherwise today's shared sequence for the daily challenge, drilling
+    // only the requested pieces if asked. Any other run deals from a random
+    // seed so it can be replayed.
+    let replayable = sequence.is_none() && only_pieces.is_none() && board.is_none();
+    let daily = args.iter().any(|arg| arg == "--daily");
+    let fixed_seed = seed.or_else(|| daily.then(daily_seed));
+    let run_seed = fixed_seed.unwrap_or_else(rand::random);
+    let dealer: Box<dyn PieceProvider> = match sequence {
+        Some(pieces) => Box::new(SequencePieceProvider::new(pieces)),
+        None => Box::new(SeededPieceProvider::new(run_seed)),
+    };
+    let provider: Box<dyn PieceProvider> = match only_pieces {
+        Some(pieces) => Box::new(FilteredPieceProvider::new(dealer, pieces)),
+        None => dealer,
+    };
+    let config = GameConfig {
+        chaos_colors: args.iter().any(|arg| arg == "--chaos"),
+        ..GameConfig::default()
+    };
+    let mut recorder = Recorder {
+        replay: (replayable && resume.is_none()).then(|| Replay::new(run_seed, config.clone())),
+        replayable,
+        fixed_seed,
+        frame: 0,
+        carry_ms: 0,
+    };
+    let mut game = Game::with_config(config, provider);
+    if let Some(grid) = board {
+        game.grid = grid;
+    }
+    if let Some(snapshot) = &resume {
+        game.restore_snapshot(snapshot);
+    }
+    let keys = settings.key_bindings();
+    let mut ui = UiState {
+        controls_hint: keys.hint(),
+        theme_index: theme_index(&settings.theme).unwrap_or(0),
+        ghost_style: settings.ghost_style,
+        held_ghost: settings.held_ghost,
+        invisible: settings
+            .invisible_blocks
+            .then(|| InvisibleStack::new(settings.reveal_on_clear)),
+        cell_shape: settings.cell_shape,
+        hide_preview: settings.hide_preview,
+        preview_shown: settings.preview_shown,
+        ..UiState::default()
+    };
+    let mut sound: Box<dyn SoundSink> = if settings.bell {
+        Box::new(TerminalBell)
+    } else {
+        Box::new(NoSound)
+    };
+    // Title screen first, unless picking a saved run back up. Its own
+    // timer runs the rain, and the game's timer only starts once it is
+    // dismissed, so no gravity builds up behind it.
+    if resume.is_none() && settings.intro && !run_intro(&mut terminal, ui.theme())? {
+        return restore_terminal();
+    }
+    let mut frame_timer = FrameTimer::new(SystemClock::new());
+    let mut was_game_over = false;
 
     // Main loop
     loop {
         // Render
-        terminal.draw(|frame| render(frame, &game))?;
+        terminal.draw(|frame| render(frame, &game, &ui))?;
 
-        // Calculate time until next tick
-        let tick_duration = Duration::from_millis(game.tick_duration_ms());
-        let timeout = tick_duration
-            .checked_sub(last_tick.elapsed())
-            .unwrap_or(Duration::ZERO);
+        // Wait for input until the next gravity tick or effect frame is due
+        let wait_ms = match ui.redraw_in_ms() {
+            Some(effect_ms) => effect_ms.min(game.time_until_tick_ms()),
+            None => game.time_until_tick_ms(),
+        };
+        let timeout = Duration::from_millis(wait_ms);
 
         // Handle input
         if event::poll(timeout)? {
-            if let Event::Key(key) = event::read()? {
-                if key.kind == KeyEventKind::Press {
+            let event = event::read()?;
+            // Pause while the terminal is in the background. A game the
+            // player paused themselves stays paused when focus returns.
+            match event {
+                Event::FocusLost if settings.auto_pause && game.is_playing() => {
+                    game.toggle_pause();
+                    ui.pause_menu = PauseMenu::default();
+                    ui.auto_paused = true;
+                }
+                Event::FocusGained if ui.auto_paused => {
+                    if game.is_paused() {
+                        game.toggle_pause();
+                    }
+                    ui.auto_paused = false;
+                }
+                _ => {}
+            }
+            if let Event::Key(key) = event {
+                // Terminals that report key repeats send one Repeat per
+                // auto-repeat instead of another Press, so each repeat is
+                // applied exactly once. Only movement and soft drop repeat.
+                if key.kind == KeyEventKind::Repeat {
+                    let input = keys.input_for_key(key.code).filter(|input| input.repeats_when_held());
+                    if let Some(input) = input.filter(|_| game.is_playing()) {
+                        recorder.apply(&mut game, input);
+                        ui.actions += 1;
+                    }
+                } else if key.kind == KeyEventKind::Press {
                     match key.code {
                         // Always allow quit
                         KeyCode::Esc | KeyCode::Char('q') | KeyCode::Char('Q') => break,
+                        // Any other key skips to the game-over screen
+                        _ if ui.instant_replay.is_some() => {
+                            ui.instant_replay = None;
+                        }
                         // Restart only available when game is over
-                        KeyCode::Char('r') | KeyCode::Char('R') if game.state == GameState::GameOver => {
-                            game.restart();
+                        KeyCode::Char('r') | KeyCode::Char('R') if game.is_game_over() => {
+                            recorder.restart(&mut game);
+                        }
+                        // Developer overlay with live piece coordinates
+                        KeyCode::F(3) => {
+                            ui.show_debug = !ui.show_debug;
+                        }
+                        // Faint trail behind moving pieces
+                        KeyCode::F(4) => {
+                            ui.show_trail = !ui.show_trail;
+                        }
+                        // Bottom-up playfield, for embedders that count rows
+                        // from the bottom
+                        KeyCode::F(5) => {
+                            ui.flip_vertical = !ui.flip_vertical;
+                        }
+                        // Dump the board and snapshot for a bug report
+                        KeyCode::F(6) => {
+                            ui.status = Some(match write_freeze_frame(&game) {
+                                Ok(path) => format!("Freeze frame saved to {path}"),
+                                Err(err) => format!("Freeze frame failed: {err}"),
+                            });
+                        }
+                        // Ghost-only practice: hide the falling piece
+                        KeyCode::F(7) => {
+                            ui.ghost_only = !ui.ghost_only;
+                        }
+                        // Blind play: hide the next queue
+                        KeyCode::Char('n') | KeyCode::Char('N') => {
+                            ui.hide_preview = !ui.hide_preview;
+                        }
+                        // Next built-in color theme
+                        KeyCode::Char('t') | KeyCode::Char('T') => {
+                            ui.theme_index = next_theme_index(ui.theme_index);
                         }
                         // Always allow pause/unpause toggle
                         KeyCode::Char('p') | KeyCode::Char('P') => {
                             game.toggle_pause();
+                            ui.pause_menu = PauseMenu::default();
+                            ui.auto_paused = false;
                         }
-                        // Only process game controls when playing
-                        _ if game.state == GameState::Playing => {
-                            match key.code {
-                                KeyCode::Char('a') | KeyCode::Char('A') => {
-                                    game.move_piece(-1, 0);
-                                }
-                                KeyCode::Char('d') | KeyCode::Char('D') => {
-                                    game.move_piece(1, 0);
-                                }
-                                KeyCode::Char('s') | KeyCode::Char('S')
-                                | KeyCode::Char('j') | KeyCode::Char('J') => {
-                                    game.soft_drop();
-                                }
-                                KeyCode::Char('w') | KeyCode::Char('W')
-                                | KeyCode::Char('k') | KeyCode::Char('K') => {
-                                    game.hard_drop();
-                                }
-                                KeyCode::Left | KeyCode::Char('h') | KeyCode::Char('H') => {
-                                    game.rotate_piece(false); // Counter-clockwise
-                                }
-                                KeyCode::Right | KeyCode::Char('l') | KeyCode::Char('L') => {
-                                    game.rotate_piece(true); // Clockwise
+                        // Pause menu navigation
+                        KeyCode::Up if game.is_paused() => {
+                            ui.pause_menu.select_previous();
+                        }
+                        KeyCode::Down if game.is_paused() => {
+                            ui.pause_menu.select_next();
+                        }
+                        KeyCode::Left
+                            if game.is_paused()
+                                && ui.pause_menu.selected() == PauseOption::ChangeLevel =>
+                        {
+                            game.set_level(game.level.saturating_sub(1));
+                            recorder.stop();
+                        }
+                        KeyCode::Right
+                            if game.is_paused()
+                                && ui.pause_menu.selected() == PauseOption::ChangeLevel =>
+                        {
+                            game.set_level(game.level + 1);
+                            recorder.stop();
+                        }
+                        KeyCode::Enter if game.is_paused() => {
+                            match ui.pause_menu.selected() {
+                                PauseOption::Resume => game.toggle_pause(),
+                                PauseOption::Restart => recorder.restart(&mut game),
+                                PauseOption::ChangeLevel => {
+                                    game.set_level(game.level + 1);
+                                    recorder.stop();
                                 }
-                                _ => {}
+                                PauseOption::Quit => break,
+                            }
+                        }
+                        // Only process game controls when playing
+                        _ if game.is_playing() => {
+                            if let Some(input) = keys.input_for_key(key.code) {
+                                recorder.apply(&mut game, input);
+                                ui.actions += 1;
                             }
                         }
                         _ => {}
@@ -378,15 +1461,55 @@ fn main() -> io::Result<()> {
         }
 
         // Update game state
-        if last_tick.elapsed() >= tick_duration {
-            game.tick();
-            last_tick = Instant::now();
+        let delta_ms = frame_timer.delta_ms();
+        recorder.advance(&mut game, delta_ms);
+        ui.advance(delta_ms);
+        let events = game.take_events();
+        ui.handle_events(&events);
+        let lock_flash_ms = ui.theme().lock_flash_ms;
+        ui.lock_flash.handle_events(&events, &game, lock_flash_ms);
+        ui.settle.handle_events(&events, settings.settle_animation);
+        if let Some(invisible) = &mut ui.invisible {
+            invisible.handle_events(&events, &game);
         }
+        play_events(sound.as_mut(), &events);
+        for event in &events {
+            match event {
+                GameEvent::Paused => {
+                    let _ = game.to_snapshot().save(AUTOSAVE_FILE);
+                }
+                GameEvent::GameOver => {
+                    let _ = std::fs::remove_file(AUTOSAVE_FILE);
+                }
+                _ => {}
+            }
+        }
+        ui.track_piece(&game.current_piece, &events);
+
+        // Export stats and start the instant replay once, on the frame the
+        // game ends
+        let is_game_over = game.is_game_over();
+        if is_game_over && !was_game_over {
+            let _ = game.export_stats(stats_file_name());
+            ui.instant_replay = recorder.instant_replay();
+        }
+        was_game_over = is_game_over;
     }
 
-    // Restore terminal
+    // Keep a beaten high score even when quitting mid-game
+    game.on_quit();
+    let _ = std::fs::remove_file(AUTOSAVE_FILE);
+    settings.theme = ui.theme().name.to_string();
+    settings.hide_preview = ui.hide_preview;
+    let _ = settings.save(SETTINGS_FILE);
+
+    restore_terminal()
+}
+
+/// Undoes the terminal setup at the start of `main`
+fn restore_terminal() -> io::Result<()> {
     disable_raw_mode()?;
+    stdout().execute(DisableFocusChange)?;
     stdout().execute(LeaveAlternateScreen)?;
-
     Ok(())
 }
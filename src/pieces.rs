@@ -0,0 +1,104 @@
+use std::{fs, io, path::Path, sync::RwLock};
+
+use serde::{Deserialize, Serialize};
+
+use crate::game::TetrominoType;
+
+// ============================================================================
+// Piece Definitions
+// ============================================================================
+
+/// A piece described by its rotation states and display color. Cells are
+/// `(x, y)` offsets with y pointing down, like `TetrominoType::shapes`.
+#[derive(Clone, PartialEq, Eq, Debug, Serialize, Deserialize)]
+pub struct PieceDefinition {
+    pub name: String,
+    /// One cell list per rotation state, in clockwise order
+    pub rotations: Vec<Vec<(i16, i16)>>,
+    /// RGB color used by the renderer
+    pub color: (u8, u8, u8),
+}
+
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub enum PieceError {
+    NoRotations,
+    EmptyRotation(usize),
+    RegistryFull,
+}
+
+impl std::fmt::Display for PieceError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            PieceError::NoRotations => write!(f, "piece has no rotation states"),
+            PieceError::EmptyRotation(rotation) => {
+                write!(f, "rotation state {} has no cells", rotation)
+            }
+            PieceError::RegistryFull => {
+                write!(f, "at most {} custom pieces can be registered", u8::MAX as usize + 1)
+            }
+        }
+    }
+}
+
+impl std::error::Error for PieceError {}
+
+// ============================================================================
+// Registry
+// ============================================================================
+//
+// The seven standard pieces are built into `TetrominoType`. Extra pieces are
+// appended to a process-wide registry and referred to as
+// `TetrominoType::Custom(id)`, so they stay `Copy` and fit everywhere a
+// standard piece does (grid cells, providers, events).
+
+static CUSTOM_PIECES: RwLock<Vec<PieceDefinition>> = RwLock::new(Vec::new());
+
+/// Adds a piece and returns the type to spawn it with. Each rotation state
+/// is shifted so its cells start at (0, 0), matching the built-in shapes.
+pub fn register_piece(mut definition: PieceDefinition) -> Result<TetrominoType, PieceError> {
+    if definition.rotations.is_empty() {
+        return Err(PieceError::NoRotations);
+    }
+    for (rotation, cells) in definition.rotations.iter_mut().enumerate() {
+        let min_x = cells.iter().map(|(x, _)| *x).min().ok_or(PieceError::EmptyRotation(rotation))?;
+        let min_y = cells.iter().map(|(_, y)| *y).min().ok_or(PieceError::EmptyRotation(rotation))?;
+        for (x, y) in cells.iter_mut() {
+            *x -= min_x;
+            *y -= min_y;
+        }
+    }
+
+    let mut pieces = CUSTOM_PIECES.write().unwrap_or_else(|e| e.into_inner());
+    let id = u8::try_from(pieces.len()).map_err(|_| PieceError::RegistryFull)?;
+    pieces.push(definition);
+    Ok(TetrominoType::Custom(id))
+}
+
+/// Definition registered under `id`, if any
+pub fn custom_piece(id: u8) -> Option<PieceDefinition> {
+    let pieces = CUSTOM_PIECES.read().unwrap_or_else(|e| e.into_inner());
+    pieces.get(id as usize).cloned()
+}
+
+/// Whether `piece` can be played in this process: a standard piece, or a
+/// custom one that has been registered. Custom ids only mean something to
+/// the process that registered them, so anything loaded from disk is
+/// checked with this first.
+pub fn is_known(piece: TetrominoType) -> bool {
+    match piece {
+        TetrominoType::Custom(id) => custom_piece(id).is_some(),
+        _ => true,
+    }
+}
+
+/// Registers every piece in a JSON array of definitions
+pub fn load_pieces(path: impl AsRef<Path>) -> io::Result<Vec<TetrominoType>> {
+    let json = fs::read_to_string(path)?;
+    let definitions: Vec<PieceDefinition> = serde_json::from_str(&json)?;
+    definitions
+        .into_iter()
+        .map(|definition| {
+            register_piece(definition).map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))
+        })
+        .collect()
+}
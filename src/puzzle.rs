@@ -0,0 +1,67 @@
+use std::{fmt, fs, io, path::Path};
+
+use serde::{Deserialize, Serialize};
+
+use crate::game::{BoardParseError, TetrominoType};
+
+// ============================================================================
+// Puzzles
+// ============================================================================
+
+/// Hand-authored challenge: a starting board, the pieces to play and the
+/// goal to reach with them. Loaded from JSON such as
+/// `{"board": "...", "sequence": ["I", "O"], "goal": {"lines": 1, "pieces": 2}}`.
+#[derive(Clone, PartialEq, Eq, Debug, Serialize, Deserialize)]
+pub struct Puzzle {
+    /// Starting board in `board_from_string` format
+    pub board: String,
+    /// Pieces dealt in order, the first one starting in play
+    pub sequence: Vec<TetrominoType>,
+    pub goal: PuzzleGoal,
+}
+
+/// Clear `lines` lines within `pieces` placed pieces
+#[derive(Clone, Copy, PartialEq, Eq, Debug, Serialize, Deserialize)]
+pub struct PuzzleGoal {
+    pub lines: u32,
+    pub pieces: u32,
+}
+
+impl Puzzle {
+    pub fn from_json(json: &str) -> serde_json::Result<Self> {
+        serde_json::from_str(json)
+    }
+
+    pub fn load(path: impl AsRef<Path>) -> io::Result<Self> {
+        let json = fs::read_to_string(path)?;
+        Ok(Self::from_json(&json)?)
+    }
+}
+
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub enum PuzzleError {
+    Board(BoardParseError),
+    EmptySequence,
+    /// A custom piece this process has not registered
+    UnknownPiece(TetrominoType),
+}
+
+impl fmt::Display for PuzzleError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            PuzzleError::Board(err) => write!(f, "invalid puzzle board: {}", err),
+            PuzzleError::EmptySequence => write!(f, "puzzle has no pieces to play"),
+            PuzzleError::UnknownPiece(piece) => {
+                write!(f, "puzzle deals {:?}, which is not registered", piece)
+            }
+        }
+    }
+}
+
+impl std::error::Error for PuzzleError {}
+
+impl From<BoardParseError> for PuzzleError {
+    fn from(err: BoardParseError) -> Self {
+        PuzzleError::Board(err)
+    }
+}
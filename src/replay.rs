@@ -0,0 +1,243 @@
+use std::fmt;
+use std::io::{Read, Write};
+
+use base64::engine::general_purpose::URL_SAFE_NO_PAD;
+use base64::Engine;
+use flate2::read::DeflateDecoder;
+use flate2::write::DeflateEncoder;
+use flate2::Compression;
+use serde::{Deserialize, Serialize};
+
+use crate::game::{board_to_string, Game, GameConfig, Input, SeededPieceProvider};
+
+// ============================================================================
+// Replay
+// ============================================================================
+
+/// Game time covered by one replay frame (about 60 frames per second)
+pub const FRAME_MS: u64 = 16;
+
+/// First byte of every share code, bumped whenever the encoding changes so
+/// old codes are rejected instead of misread
+pub const SHARE_CODE_VERSION: u8 = 1;
+
+/// Everything needed to play a game back exactly: the piece seed, the rule
+/// set, and the inputs pressed on each frame. A frame applies its inputs in
+/// order and then advances the game by `FRAME_MS`.
+#[derive(Clone, PartialEq, Eq, Debug, Default, Serialize, Deserialize)]
+pub struct Replay {
+    pub seed: u64,
+    pub config: GameConfig,
+    /// `(frame, input)` pairs, ordered by frame
+    inputs: Vec<(u64, Input)>,
+    /// `state_checksum` of the game after the last frame, stored by `seal`.
+    /// Missing from replays recorded before checksums were added.
+    #[serde(default)]
+    checksum: Option<u64>,
+}
+
+impl Replay {
+    pub fn new(seed: u64, config: GameConfig) -> Self {
+        Self {
+            seed,
+            config,
+            inputs: Vec::new(),
+            checksum: None,
+        }
+    }
+
+    /// Adds an input pressed on `frame`. Frames must not go backwards.
+    pub fn record(&mut self, frame: u64, input: Input) {
+        debug_assert!(self.inputs.last().is_none_or(|&(last, _)| last <= frame));
+        self.inputs.push((frame, input));
+    }
+
+    pub fn inputs(&self) -> &[(u64, Input)] {
+        &self.inputs
+    }
+
+    /// Number of frames up to and including the last recorded input
+    pub fn frame_count(&self) -> u64 {
+        self.inputs.last().map_or(0, |&(frame, _)| frame + 1)
+    }
+
+    /// The game as it was before frame 0. Its high score stays in memory,
+    /// so playing back someone else's run never overwrites the local one.
+    pub fn new_game(&self) -> Game {
+        Game::with_config(self.config.clone(), Box::new(SeededPieceProvider::new(self.seed)))
+            .without_high_score_file()
+    }
+
+    /// Plays frame `frame` on `game`: its inputs, then one frame of time
+    pub fn step(&self, game: &mut Game, frame: u64) {
+        let start = self.inputs.partition_point(|&(f, _)| f < frame);
+        for &(_, input) in self.inputs[start..].iter().take_while(|&&(f, _)| f == frame) {
+            game.apply_input(input);
+        }
+        game.tick_with_delta(FRAME_MS);
+    }
+
+    /// Rebuilds the game as it was at the start of `frame`, by stepping a
+    /// fresh game through every earlier frame
+    pub fn seek(&self, frame: u64) -> Game {
+        let mut game = self.new_game();
+        for f in 0..frame {
+            self.step(&mut game, f);
+        }
+        game
+    }
+
+    /// Stores the checksum of the state the replay ends in. Call once
+    /// recording is done; inputs recorded afterwards won't match it.
+    pub fn seal(&mut self) {
+        self.checksum = Some(state_checksum(&self.seek(self.frame_count())));
+    }
+
+    pub fn checksum(&self) -> Option<u64> {
+        self.checksum
+    }
+
+    /// Plays the replay through and checks it ends in the sealed state. A
+    /// mismatch means the timeline was altered or this build scores or
+    /// deals pieces differently from the one that recorded it. Unsealed
+    /// replays can't be checked and never verify.
+    pub fn verify(&self) -> bool {
+        self.checksum
+            .is_some_and(|checksum| checksum == state_checksum(&self.seek(self.frame_count())))
+    }
+
+    /// Packs the replay into a short URL-safe string for sharing: the
+    /// version byte followed by the deflated JSON, in unpadded base64
+    pub fn to_share_code(&self) -> String {
+        let json = serde_json::to_vec(self).expect("replays always serialize");
+        let mut encoder = DeflateEncoder::new(vec![SHARE_CODE_VERSION], Compression::best());
+        encoder.write_all(&json).expect("writing to a Vec cannot fail");
+        let bytes = encoder.finish().expect("writing to a Vec cannot fail");
+        URL_SAFE_NO_PAD.encode(bytes)
+    }
+
+    pub fn from_share_code(code: &str) -> Result<Self, ShareCodeError> {
+        let bytes = URL_SAFE_NO_PAD
+            .decode(code.trim())
+            .map_err(|_| ShareCodeError::NotBase64)?;
+        let (&version, payload) = bytes.split_first().ok_or(ShareCodeError::Corrupt)?;
+        if version != SHARE_CODE_VERSION {
+            return Err(ShareCodeError::UnsupportedVersion(version));
+        }
+
+        let mut json = Vec::new();
+        DeflateDecoder::new(payload)
+            .read_to_end(&mut json)
+            .map_err(|_| ShareCodeError::Corrupt)?;
+        let replay: Self = serde_json::from_slice(&json).map_err(|_| ShareCodeError::Corrupt)?;
+        if !replay.inputs.windows(2).all(|pair| pair[0].0 <= pair[1].0) {
+            return Err(ShareCodeError::Corrupt);
+        }
+        Ok(replay)
+    }
+}
+
+/// FNV-1a hash of the locked board, the falling piece, score, lines and
+/// level. Unlike `DefaultHasher` it is the same on every build, so
+/// checksums survive being shared.
+pub fn state_checksum(game: &Game) -> u64 {
+    let piece = &game.current_piece;
+    let state = format!(
+        "{}|{:?}|{},{}|{}|{}|{}|{}",
+        board_to_string(&game.grid),
+        piece.tetromino_type,
+        piece.position.x,
+        piece.position.y,
+        piece.rotation,
+        game.score,
+        game.lines_cleared,
+        game.level
+    );
+    state.bytes().fold(0xcbf2_9ce4_8422_2325, |hash, byte| {
+        (hash ^ byte as u64).wrapping_mul(0x0000_0100_0000_01b3)
+    })
+}
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum ShareCodeError {
+    /// Not a URL-safe base64 string
+    NotBase64,
+    /// Made by a build with a different share code format
+    UnsupportedVersion(u8),
+    /// Decoded, but the replay inside is truncated or invalid
+    Corrupt,
+}
+
+impl fmt::Display for ShareCodeError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ShareCodeError::NotBase64 => write!(f, "share code is not valid base64"),
+            ShareCodeError::UnsupportedVersion(version) => {
+                write!(f, "share code version {} is not supported", version)
+            }
+            ShareCodeError::Corrupt => write!(f, "share code is damaged"),
+        }
+    }
+}
+
+impl std::error::Error for ShareCodeError {}
+
+// ============================================================================
+// Playback
+// ============================================================================
+
+/// A stretch of a replay played back against the wall clock, slowed down
+/// by a whole factor, such as the instant replay of a game's last moments
+pub struct Playback {
+    replay: Replay,
+    game: Game,
+    frame: u64,
+    end: u64,
+    /// Real time each frame stays on screen
+    frame_ms: u64,
+    carry_ms: u64,
+}
+
+impl Playback {
+    /// Plays the `window_ms` of game time before frame `end`, each frame
+    /// shown for `slowdown` times as long as it took live
+    pub fn tail(replay: Replay, end: u64, window_ms: u64, slowdown: u64) -> Self {
+        let start = end.saturating_sub(window_ms / FRAME_MS);
+        Self {
+            game: replay.seek(start),
+            replay,
+            frame: start,
+            end,
+            frame_ms: FRAME_MS * slowdown.max(1),
+            carry_ms: 0,
+        }
+    }
+
+    /// The game as of the frame on screen
+    pub fn game(&self) -> &Game {
+        &self.game
+    }
+
+    pub fn frame(&self) -> u64 {
+        self.frame
+    }
+
+    /// Steps through as many frames as `elapsed_ms` of real time covers
+    pub fn advance(&mut self, elapsed_ms: u64) {
+        self.carry_ms += elapsed_ms;
+        while self.carry_ms >= self.frame_ms && !self.is_finished() {
+            self.replay.step(&mut self.game, self.frame);
+            self.frame += 1;
+            self.carry_ms -= self.frame_ms;
+        }
+    }
+
+    pub fn is_finished(&self) -> bool {
+        self.frame >= self.end
+    }
+
+    /// Real time until the next frame is due
+    pub fn time_until_frame_ms(&self) -> u64 {
+        self.frame_ms.saturating_sub(self.carry_ms)
+    }
+}
@@ -0,0 +1,104 @@
+use serde::{Deserialize, Serialize};
+
+use crate::game::TetrominoType;
+
+// ============================================================================
+// Rotation Systems
+// ============================================================================
+
+/// Rule set used to resolve rotations that collide in place
+#[derive(Clone, Copy, PartialEq, Eq, Debug, Default, Serialize, Deserialize)]
+pub enum RotationSystem {
+    /// No wall kicks: a rotation that collides in place fails (retro rules)
+    None,
+    /// Try a short fixed list of nudges (the original behavior)
+    #[default]
+    Classic,
+    /// Super Rotation System: true center rotation with guideline kick tables
+    Srs,
+}
+
+/// Guideline names of the four rotation states, clockwise from spawn
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum RotationState {
+    Spawn,
+    Right,
+    Two,
+    Left,
+}
+
+impl RotationState {
+    /// The state a rotation index (any number of turns) ends up in
+    pub fn from_index(rotation: usize) -> Self {
+        match rotation % 4 {
+            0 => RotationState::Spawn,
+            1 => RotationState::Right,
+            2 => RotationState::Two,
+            _ => RotationState::Left,
+        }
+    }
+}
+
+/// Offsets tried by the classic system, the in-place rotation first
+pub const CLASSIC_KICKS: [(i16, i16); 6] = [(0, 0), (1, 0), (-1, 0), (0, -1), (2, 0), (-2, 0)];
+
+// ============================================================================
+// SRS Tables
+// ============================================================================
+//
+// Shapes in `TetrominoType::shapes` are normalized to their top-left corner,
+// while SRS rotates pieces inside a fixed bounding box. `srs_origin` is where
+// each normalized shape sits inside that box, so the shift between two
+// states is `origin(to) - origin(from)`. Kick offsets use this crate's
+// y-down coordinates (the guideline tables are y-up).
+
+/// Top-left corner of each rotation state's cells within the SRS box
+pub fn srs_origin(tetromino_type: TetrominoType, rotation: usize) -> (i16, i16) {
+    const JLSTZ: [(i16, i16); 4] = [(0, 0), (1, 0), (0, 1), (0, 0)];
+    const I: [(i16, i16); 4] = [(0, 1), (2, 0), (0, 2), (1, 0)];
+
+    match tetromino_type {
+        TetrominoType::O => (0, 0),
+        TetrominoType::I => I[rotation % 4],
+        _ => JLSTZ[rotation % 4],
+    }
+}
+
+/// Kick offsets to try, in order, when rotating from `from` to `to`
+pub fn srs_kicks(tetromino_type: TetrominoType, from: usize, to: usize) -> &'static [(i16, i16)] {
+    const JLSTZ_CW: [[(i16, i16); 5]; 4] = [
+        [(0, 0), (-1, 0), (-1, -1), (0, 2), (-1, 2)], // 0 -> R
+        [(0, 0), (1, 0), (1, 1), (0, -2), (1, -2)],   // R -> 2
+        [(0, 0), (1, 0), (1, -1), (0, 2), (1, 2)],    // 2 -> L
+        [(0, 0), (-1, 0), (-1, 1), (0, -2), (-1, -2)], // L -> 0
+    ];
+    const JLSTZ_CCW: [[(i16, i16); 5]; 4] = [
+        [(0, 0), (1, 0), (1, -1), (0, 2), (1, 2)],    // 0 -> L
+        [(0, 0), (1, 0), (1, 1), (0, -2), (1, -2)],   // R -> 0
+        [(0, 0), (-1, 0), (-1, -1), (0, 2), (-1, 2)], // 2 -> R
+        [(0, 0), (-1, 0), (-1, 1), (0, -2), (-1, -2)], // L -> 2
+    ];
+    const I_CW: [[(i16, i16); 5]; 4] = [
+        [(0, 0), (-2, 0), (1, 0), (-2, 1), (1, -2)], // 0 -> R
+        [(0, 0), (-1, 0), (2, 0), (-1, -2), (2, 1)], // R -> 2
+        [(0, 0), (2, 0), (-1, 0), (2, -1), (-1, 2)], // 2 -> L
+        [(0, 0), (1, 0), (-2, 0), (1, 2), (-2, -1)], // L -> 0
+    ];
+    const I_CCW: [[(i16, i16); 5]; 4] = [
+        [(0, 0), (-1, 0), (2, 0), (-1, -2), (2, 1)], // 0 -> L
+        [(0, 0), (2, 0), (-1, 0), (2, -1), (-1, 2)], // R -> 0
+        [(0, 0), (1, 0), (-2, 0), (1, 2), (-2, -1)], // 2 -> R
+        [(0, 0), (-2, 0), (1, 0), (-2, 1), (1, -2)], // L -> 2
+    ];
+    const NONE: [(i16, i16); 1] = [(0, 0)];
+
+    let from = from % 4;
+    let clockwise = to % 4 == (from + 1) % 4;
+    match (tetromino_type, clockwise) {
+        (TetrominoType::O, _) => &NONE,
+        (TetrominoType::I, true) => &I_CW[from],
+        (TetrominoType::I, false) => &I_CCW[from],
+        (_, true) => &JLSTZ_CW[from],
+        (_, false) => &JLSTZ_CCW[from],
+    }
+}
@@ -0,0 +1,95 @@
+use std::{fs, io, path::Path};
+
+use serde::{Deserialize, Serialize};
+
+use crate::game::PREVIEW_COUNT;
+use crate::keys::{KeyBindings, KeyPreset};
+use crate::theme::{CellShape, GhostStyle, THEMES};
+
+// ============================================================================
+// Settings
+// ============================================================================
+
+/// Player preferences kept between runs. Fields missing from the file
+/// fall back to their defaults.
+#[derive(Clone, PartialEq, Eq, Debug, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Settings {
+    /// Name of the active color theme
+    pub theme: String,
+    /// How the landing preview of the falling piece is drawn
+    pub ghost_style: GhostStyle,
+    /// Characters each playfield cell is drawn with
+    pub cell_shape: CellShape,
+    /// Ring the terminal bell on line clears
+    pub bell: bool,
+    /// Blind play: draw the next queue as placeholders
+    pub hide_preview: bool,
+    /// How many upcoming pieces the Next panel shows. The game always
+    /// buffers `PREVIEW_COUNT`, so larger values show just those.
+    pub preview_shown: usize,
+    /// Layout of the game controls
+    pub controls: KeyPreset,
+    /// Swap left/right movement and rotation directions of `controls`
+    pub mirror_controls: bool,
+    /// Pause when the terminal loses focus and resume when it comes back,
+    /// on terminals that report focus changes
+    pub auto_pause: bool,
+    /// Squash a hard-dropped piece for a frame as it lands
+    pub settle_animation: bool,
+    /// Also mark where the held piece would land if swapped in now
+    pub held_ghost: bool,
+    /// Open on the title screen with falling pieces
+    pub intro: bool,
+    /// Challenge mode: locked pieces vanish from view shortly after
+    /// locking, though they are still on the board
+    pub invisible_blocks: bool,
+    /// In that mode, briefly show the whole stack on every line clear
+    pub reveal_on_clear: bool,
+}
+
+impl Default for Settings {
+    fn default() -> Self {
+        Self {
+            theme: THEMES[0].name.to_string(),
+            ghost_style: GhostStyle::default(),
+            cell_shape: CellShape::default(),
+            bell: false,
+            hide_preview: false,
+            preview_shown: PREVIEW_COUNT,
+            controls: KeyPreset::default(),
+            mirror_controls: false,
+            auto_pause: true,
+            settle_animation: true,
+            held_ghost: false,
+            intro: true,
+            invisible_blocks: false,
+            reveal_on_clear: true,
+        }
+    }
+}
+
+impl Settings {
+    /// Reads settings from `path`, or the defaults if it is missing or unreadable
+    pub fn load(path: impl AsRef<Path>) -> Self {
+        fs::read_to_string(path)
+            .ok()
+            .and_then(|json| serde_json::from_str(&json).ok())
+            .unwrap_or_default()
+    }
+
+    /// The game controls these settings ask for
+    pub fn key_bindings(&self) -> KeyBindings {
+        let bindings = self.controls.bindings();
+        if self.mirror_controls {
+            bindings.mirrored()
+        } else {
+            bindings
+        }
+    }
+
+    pub fn save(&self, path: impl AsRef<Path>) -> io::Result<()> {
+        let json = serde_json::to_string_pretty(self)?;
+        fs::write(path, json)
+    }
+}
@@ -0,0 +1,83 @@
+use std::collections::BTreeMap;
+use std::{fs, io, path::Path};
+
+use serde::{Deserialize, Serialize};
+
+use crate::game::{CellState, Tetromino, TetrominoType, GRID_HEIGHT, GRID_WIDTH, PREVIEW_COUNT};
+use crate::pieces;
+
+// ============================================================================
+// Game Snapshots
+// ============================================================================
+
+/// A run in progress, from `Game::to_snapshot`, saved as JSON so it can be
+/// resumed with `Game::restore_snapshot`
+#[derive(Clone, PartialEq, Eq, Debug, Serialize, Deserialize)]
+pub struct GameSnapshot {
+    pub grid: Vec<Vec<CellState>>,
+    pub current_piece: Tetromino,
+    pub preview_queue: Vec<TetrominoType>,
+    /// Oldest first, like `Game::held_pieces`
+    pub held_pieces: Vec<TetrominoType>,
+    pub can_hold: bool,
+    pub score: u32,
+    pub lines_cleared: u32,
+    pub level: u32,
+    pub elapsed_ms: u64,
+    pub pieces_placed: BTreeMap<TetrominoType, u32>,
+    pub combo: u32,
+    pub max_combo: u32,
+    pub tetrises: u32,
+    /// Missing from snapshots saved before Tetris chains were tracked
+    #[serde(default)]
+    pub consecutive_tetris: u32,
+    /// Also missing from older snapshots
+    #[serde(default)]
+    pub pieces_since_clear: u32,
+    #[serde(default)]
+    pub back_to_back: bool,
+    pub tspins: u32,
+}
+
+impl GameSnapshot {
+    /// Reads a snapshot, rejecting one whose board or preview queue doesn't
+    /// fit this build or that uses a custom piece not registered here
+    pub fn load(path: impl AsRef<Path>) -> io::Result<Self> {
+        let json = fs::read_to_string(path)?;
+        let snapshot: Self = serde_json::from_str(&json)?;
+
+        let board_fits = snapshot.grid.len() == GRID_HEIGHT
+            && snapshot.grid.iter().all(|row| row.len() == GRID_WIDTH);
+        if !board_fits || snapshot.preview_queue.len() != PREVIEW_COUNT {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidData,
+                "snapshot has a different board or preview size",
+            ));
+        }
+        if let Some(piece) = snapshot.pieces().find(|&piece| !pieces::is_known(piece)) {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidData,
+                format!("snapshot uses {:?}, which is not registered", piece),
+            ));
+        }
+        Ok(snapshot)
+    }
+
+    /// Every piece type the snapshot refers to, repeats included
+    fn pieces(&self) -> impl Iterator<Item = TetrominoType> + '_ {
+        let cells = self.grid.iter().flatten().filter_map(|cell| match cell {
+            CellState::Filled(piece) | CellState::Tinted(piece, _) => Some(*piece),
+            CellState::Empty | CellState::Garbage => None,
+        });
+        cells
+            .chain([self.current_piece.tetromino_type])
+            .chain(self.preview_queue.iter().copied())
+            .chain(self.held_pieces.iter().copied())
+            .chain(self.pieces_placed.keys().copied())
+    }
+
+    pub fn save(&self, path: impl AsRef<Path>) -> io::Result<()> {
+        let json = serde_json::to_string(self)?;
+        fs::write(path, json)
+    }
+}
@@ -0,0 +1,80 @@
+use crate::bitboard::BitBoard;
+use crate::game::{Tetromino, TetrominoType, GRID_HEIGHT, GRID_WIDTH};
+
+// ============================================================================
+// Placements
+// ============================================================================
+
+/// Every resting spot reachable by rotating at the top of the board and
+/// dropping straight down, one per distinct shape and column
+pub fn drop_placements(board: &BitBoard, tetromino_type: TetrominoType) -> Vec<Tetromino> {
+    let shapes = tetromino_type.shapes();
+    let mut placements = Vec::new();
+
+    for rotation in 0..shapes.len() {
+        // Skip rotations that repeat an earlier shape (O, I, S, Z)
+        if shapes[..rotation].contains(&shapes[rotation]) {
+            continue;
+        }
+
+        for x in 0..GRID_WIDTH as i16 {
+            let mut piece = Tetromino::new_at(tetromino_type, x, 0);
+            piece.rotation = rotation;
+            if !board.is_valid_position(&piece) {
+                continue;
+            }
+            loop {
+                let mut lower = piece.clone();
+                lower.position.y += 1;
+                if !board.is_valid_position(&lower) {
+                    break;
+                }
+                piece = lower;
+            }
+            placements.push(piece);
+        }
+    }
+
+    placements
+}
+
+// ============================================================================
+// Perfect Clear Search
+// ============================================================================
+
+/// Whether placing `pieces` in order (each dropped from the top) can leave
+/// the board completely empty after one of the placements
+pub fn can_perfect_clear(board: &BitBoard, pieces: &[TetrominoType]) -> bool {
+    let Some((&piece_type, rest)) = pieces.split_first() else {
+        return false;
+    };
+
+    for placement in drop_placements(board, piece_type) {
+        let mut next = *board;
+        next.lock(&placement);
+        next.clear_lines();
+
+        if next.total_filled_cells() == 0 {
+            return true;
+        }
+        if could_still_clear(&next, rest) && can_perfect_clear(&next, rest) {
+            return true;
+        }
+    }
+
+    false
+}
+
+/// Every row up to the highest filled cell has to be cleared for a perfect
+/// clear, and the remaining pieces can only complete so many rows
+fn could_still_clear(board: &BitBoard, pieces: &[TetrominoType]) -> bool {
+    let piece_cells: usize = pieces.iter().map(|t| t.shapes()[0].len()).sum();
+    let cells = board.total_filled_cells() + piece_cells;
+    let clearable_rows = cells / GRID_WIDTH;
+    let stack_height = board
+        .rows()
+        .iter()
+        .position(|row| *row != 0)
+        .map_or(0, |top| GRID_HEIGHT - top);
+    !pieces.is_empty() && stack_height <= clearable_rows
+}
@@ -0,0 +1,66 @@
+use std::io::{self, Write};
+
+use crate::game::GameEvent;
+
+// ============================================================================
+// Sound Sinks
+// ============================================================================
+
+/// Receives the game events worth a sound effect. Every method defaults to
+/// doing nothing, so an implementation only overrides the sounds it plays.
+pub trait SoundSink {
+    fn piece_moved(&mut self) {}
+    fn piece_rotated(&mut self) {}
+    fn piece_locked(&mut self) {}
+    fn hard_drop(&mut self) {}
+    fn piece_held(&mut self) {}
+    fn lines_cleared(&mut self, _lines: u32) {}
+    fn tspin(&mut self, _lines: u32, _mini: bool) {}
+    fn level_up(&mut self, _level: u32) {}
+    fn game_over(&mut self) {}
+}
+
+/// Plays nothing; the default sink
+#[derive(Clone, Copy, Debug, Default)]
+pub struct NoSound;
+
+impl SoundSink for NoSound {}
+
+/// Rings the terminal bell when lines are cleared
+#[derive(Clone, Copy, Debug, Default)]
+pub struct TerminalBell;
+
+impl SoundSink for TerminalBell {
+    fn lines_cleared(&mut self, _lines: u32) {
+        let mut stdout = io::stdout();
+        let _ = stdout.write_all(b"\x07");
+        let _ = stdout.flush();
+    }
+}
+
+/// Forwards a frame's events to the matching sink methods. Events without a
+/// sound (spawns, cleared line indices, new high scores, finesse and pace
+/// reports, pausing, restarts) are skipped.
+pub fn play_events(sink: &mut dyn SoundSink, events: &[GameEvent]) {
+    for event in events {
+        match *event {
+            GameEvent::PieceMoved => sink.piece_moved(),
+            GameEvent::PieceRotated => sink.piece_rotated(),
+            GameEvent::PieceLocked => sink.piece_locked(),
+            GameEvent::HardDrop(_) => sink.hard_drop(),
+            GameEvent::PieceHeld(_) => sink.piece_held(),
+            GameEvent::LinesCleared(lines) => sink.lines_cleared(lines),
+            GameEvent::TSpin { lines, mini } => sink.tspin(lines, mini),
+            GameEvent::LevelUp(level) => sink.level_up(level),
+            GameEvent::GameOver => sink.game_over(),
+            GameEvent::Finesse { .. }
+            | GameEvent::PieceSpawned(_)
+            | GameEvent::LinesClearedAt(_)
+            | GameEvent::NewHighScore
+            | GameEvent::PaceUpdate(_)
+            | GameEvent::Paused
+            | GameEvent::Unpaused
+            | GameEvent::GameRestarted => {}
+        }
+    }
+}
@@ -0,0 +1,31 @@
+use std::{collections::BTreeMap, fs, io, path::Path};
+
+use serde::{Deserialize, Serialize};
+
+use crate::game::TetrominoType;
+
+// ============================================================================
+// Session Stats
+// ============================================================================
+
+/// Per-run summary written as JSON when a game ends
+#[derive(Clone, PartialEq, Eq, Debug, Serialize, Deserialize)]
+pub struct SessionStats {
+    pub score: u32,
+    pub lines: u32,
+    pub level: u32,
+    /// Time spent playing, excluding pauses
+    pub duration_ms: u64,
+    pub pieces_placed: BTreeMap<TetrominoType, u32>,
+    /// Longest run of consecutive pieces that each cleared lines
+    pub max_combo: u32,
+    pub tetrises: u32,
+    pub tspins: u32,
+}
+
+impl SessionStats {
+    pub fn load(path: impl AsRef<Path>) -> io::Result<Self> {
+        let json = fs::read_to_string(path)?;
+        Ok(serde_json::from_str(&json)?)
+    }
+}
@@ -0,0 +1,251 @@
+use ratatui::style::Color;
+use serde::{Deserialize, Serialize};
+
+use crate::game::TetrominoType;
+use crate::pieces::custom_piece;
+
+// ============================================================================
+// Themes
+// ============================================================================
+
+/// Named set of piece and panel colors for the terminal renderer
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub struct Theme {
+    pub name: &'static str,
+    /// Indexed like `TetrominoType::ALL`
+    pub pieces: [Color; 7],
+    /// Lighter variants for highlights such as the hard drop flash
+    pub highlights: [Color; 7],
+    /// Fill behind empty cells and panel contents; `Reset` keeps the
+    /// terminal's own background
+    pub background: Color,
+    pub border: Color,
+    pub title: Color,
+    /// Color the cells of a just-locked piece flash
+    pub lock_flash: Color,
+    /// How long that flash lasts (0 turns it off)
+    pub lock_flash_ms: u64,
+}
+
+impl Theme {
+    /// Bright pieces on a near-black board with muted borders
+    pub const fn dark() -> Self {
+        Theme {
+            name: "dark",
+            pieces: [
+                Color::Rgb(0, 220, 230),
+                Color::Rgb(240, 220, 0),
+                Color::Rgb(190, 80, 230),
+                Color::Rgb(80, 220, 80),
+                Color::Rgb(240, 70, 70),
+                Color::Rgb(70, 120, 250),
+                Color::Rgb(250, 150, 30),
+            ],
+            highlights: [
+                Color::Rgb(160, 245, 250),
+                Color::Rgb(250, 240, 150),
+                Color::Rgb(225, 175, 245),
+                Color::Rgb(175, 240, 175),
+                Color::Rgb(250, 170, 170),
+                Color::Rgb(170, 195, 252),
+                Color::Rgb(252, 205, 150),
+            ],
+            background: Color::Rgb(18, 18, 26),
+            border: Color::Rgb(85, 85, 105),
+            title: Color::Rgb(200, 200, 220),
+            lock_flash: Color::Rgb(255, 255, 255),
+            lock_flash_ms: 100,
+        }
+    }
+
+    /// Deep piece colors that stay readable on a pale board
+    pub const fn light() -> Self {
+        Theme {
+            name: "light",
+            pieces: [
+                Color::Rgb(0, 140, 160),
+                Color::Rgb(190, 150, 0),
+                Color::Rgb(130, 40, 150),
+                Color::Rgb(30, 130, 30),
+                Color::Rgb(180, 30, 30),
+                Color::Rgb(30, 60, 170),
+                Color::Rgb(210, 110, 0),
+            ],
+            highlights: [
+                Color::Rgb(0, 90, 105),
+                Color::Rgb(130, 100, 0),
+                Color::Rgb(85, 20, 100),
+                Color::Rgb(15, 85, 15),
+                Color::Rgb(120, 15, 15),
+                Color::Rgb(15, 35, 115),
+                Color::Rgb(140, 70, 0),
+            ],
+            background: Color::Rgb(238, 236, 228),
+            border: Color::Rgb(120, 120, 120),
+            title: Color::Rgb(40, 40, 40),
+            lock_flash: Color::Rgb(20, 20, 20),
+            lock_flash_ms: 100,
+        }
+    }
+
+    pub fn piece_color(&self, tetromino_type: TetrominoType) -> Color {
+        match tetromino_type.standard_index() {
+            Some(index) => self.pieces[index],
+            None => {
+                let (r, g, b) = custom_color(tetromino_type);
+                Color::Rgb(r, g, b)
+            }
+        }
+    }
+
+    pub fn highlight_color(&self, tetromino_type: TetrominoType) -> Color {
+        match tetromino_type.standard_index() {
+            Some(index) => self.highlights[index],
+            None => lighten(custom_color(tetromino_type)),
+        }
+    }
+
+    /// `piece_color`, unless the piece brought a color of its own
+    pub fn cell_color(&self, tetromino_type: TetrominoType, color: Option<(u8, u8, u8)>) -> Color {
+        match color {
+            Some((r, g, b)) => Color::Rgb(r, g, b),
+            None => self.piece_color(tetromino_type),
+        }
+    }
+
+    /// `highlight_color`, unless the piece brought a color of its own
+    pub fn cell_highlight_color(
+        &self,
+        tetromino_type: TetrominoType,
+        color: Option<(u8, u8, u8)>,
+    ) -> Color {
+        match color {
+            Some(color) => lighten(color),
+            None => self.highlight_color(tetromino_type),
+        }
+    }
+}
+
+/// Halfway to white
+fn lighten((r, g, b): (u8, u8, u8)) -> Color {
+    Color::Rgb(r / 2 + 128, g / 2 + 128, b / 2 + 128)
+}
+
+/// Custom pieces keep the color they were registered with in every theme
+fn custom_color(tetromino_type: TetrominoType) -> (u8, u8, u8) {
+    match tetromino_type {
+        TetrominoType::Custom(id) => custom_piece(id).map_or((255, 255, 255), |piece| piece.color),
+        _ => (255, 255, 255),
+    }
+}
+
+/// Appearance of the ghost piece
+#[derive(Clone, Copy, PartialEq, Eq, Debug, Default, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum GhostStyle {
+    /// Solid blocks in the piece color, dimmed
+    #[default]
+    Dimmed,
+    /// Bracket borders in the piece color with an empty interior
+    Outline,
+    /// No ghost at all
+    Hidden,
+}
+
+/// How one game cell maps onto terminal characters
+#[derive(Clone, Copy, PartialEq, Eq, Debug, Default, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum CellShape {
+    /// Two characters by one row per cell, with distinct glyphs for the
+    /// ghost, trail and garbage
+    #[default]
+    Wide,
+    /// One character per cell with two grid rows packed into each terminal
+    /// row: `▀` takes the upper cell's color as foreground and the lower
+    /// cell's as background, `▄` draws a lone lower cell. The playfield
+    /// comes out half as wide and half as tall, and everything is told
+    /// apart by color alone.
+    HalfBlock,
+}
+
+// ============================================================================
+// Built-in Themes
+// ============================================================================
+
+/// Themes that can be cycled through at runtime, the default first
+pub const THEMES: [Theme; 5] = [
+    Theme {
+        name: "classic",
+        pieces: [
+            Color::Cyan,
+            Color::Yellow,
+            Color::Magenta,
+            Color::Green,
+            Color::Red,
+            Color::Blue,
+            Color::Rgb(255, 165, 0),
+        ],
+        highlights: [
+            Color::LightCyan,
+            Color::LightYellow,
+            Color::LightMagenta,
+            Color::LightGreen,
+            Color::LightRed,
+            Color::LightBlue,
+            Color::Rgb(255, 205, 120),
+        ],
+        background: Color::Reset,
+        border: Color::Reset,
+        title: Color::Reset,
+        lock_flash: Color::White,
+        lock_flash_ms: 80,
+    },
+    Theme {
+        name: "pastel",
+        pieces: [
+            Color::Rgb(150, 220, 230),
+            Color::Rgb(250, 235, 150),
+            Color::Rgb(205, 170, 230),
+            Color::Rgb(170, 225, 170),
+            Color::Rgb(240, 160, 160),
+            Color::Rgb(160, 180, 240),
+            Color::Rgb(245, 195, 145),
+        ],
+        highlights: [
+            Color::Rgb(205, 240, 245),
+            Color::Rgb(255, 248, 205),
+            Color::Rgb(232, 215, 245),
+            Color::Rgb(215, 242, 215),
+            Color::Rgb(250, 210, 210),
+            Color::Rgb(210, 220, 250),
+            Color::Rgb(250, 225, 200),
+        ],
+        background: Color::Reset,
+        border: Color::Reset,
+        title: Color::Reset,
+        lock_flash: Color::White,
+        lock_flash_ms: 80,
+    },
+    Theme {
+        name: "monochrome",
+        pieces: [Color::Gray; 7],
+        highlights: [Color::White; 7],
+        background: Color::Reset,
+        border: Color::Reset,
+        title: Color::Reset,
+        lock_flash: Color::White,
+        lock_flash_ms: 80,
+    },
+    Theme::dark(),
+    Theme::light(),
+];
+
+/// Position of the built-in theme called `name`
+pub fn theme_index(name: &str) -> Option<usize> {
+    THEMES.iter().position(|theme| theme.name == name)
+}
+
+/// The theme after `index`, wrapping back to the first
+pub fn next_theme_index(index: usize) -> usize {
+    (index + 1) % THEMES.len()
+}
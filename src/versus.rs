@@ -0,0 +1,72 @@
+use rand::{rngs::StdRng, Rng, SeedableRng};
+
+use crate::game::{Game, GameEvent, GameState, GRID_WIDTH};
+
+// ============================================================================
+// Versus Match
+// ============================================================================
+
+/// Garbage rows sent for clearing `lines` lines at once. Singles send
+/// nothing; combos and T-spins earn no extra garbage.
+pub fn garbage_for_lines(lines: u32) -> u32 {
+    match lines {
+        0 | 1 => 0,
+        2 => 1,
+        3 => 2,
+        _ => 4,
+    }
+}
+
+/// Two boards played against each other: lines cleared on one board push
+/// garbage rows onto the other. Input goes straight to `players`; rendering
+/// is left to the caller.
+pub struct VersusMatch {
+    pub players: [Game; 2],
+    rng: StdRng,
+}
+
+impl VersusMatch {
+    /// Hole columns of sent garbage are drawn from `seed`, so a match with
+    /// the same inputs plays out the same way
+    pub fn new(first: Game, second: Game, seed: u64) -> Self {
+        Self {
+            players: [first, second],
+            rng: StdRng::seed_from_u64(seed),
+        }
+    }
+
+    /// Advances both boards by `delta_ms`, then sends garbage for every
+    /// clear since the last update. Returns each board's drained events.
+    pub fn update(&mut self, delta_ms: u64) -> [Vec<GameEvent>; 2] {
+        for game in &mut self.players {
+            game.tick_with_delta(delta_ms);
+        }
+
+        let events = [self.players[0].take_events(), self.players[1].take_events()];
+        for (sender, sent_events) in events.iter().enumerate() {
+            let rows: u32 = sent_events
+                .iter()
+                .map(|event| match event {
+                    GameEvent::LinesCleared(lines) => garbage_for_lines(*lines),
+                    _ => 0,
+                })
+                .sum();
+            if rows > 0 {
+                let holes: Vec<usize> =
+                    (0..rows).map(|_| self.rng.gen_range(0..GRID_WIDTH)).collect();
+                self.players[1 - sender].add_garbage_lines(&holes);
+            }
+        }
+        events
+    }
+
+    /// Index of the player still standing once the other has topped out
+    pub fn winner(&self) -> Option<usize> {
+        match (self.players[0].state, self.players[1].state) {
+            (GameState::GameOver, GameState::GameOver) => None,
+            (GameState::GameOver, _) => Some(1),
+            (_, GameState::GameOver) => Some(0),
+            _ => None,
+        }
+    }
+}
@@ -7,13 +7,76 @@
 //! - Scoring and leveling
 //! - Game over detection
 //! - State consistency (render_grid matches actual state)
+//! - Garbage injection and scheduled attacks
+//! - Bitboard parity with the grid implementation
+//! - Frame timing with a mock clock
+//! - SRS rotation and T-spin detection
+//! - Perfect clear search
+//! - Hold slot
+//! - Session stats export
+//! - Entry delay (ARE) after line clears and the spawn delay after every lock
+//! - Sprint and Ultra mode goals
+//! - Custom piece registry
+//! - Finesse checking
+//! - Board shape metrics
+//! - Replay stepping, seeking, playback and share codes
+//! - Internal invariants under random play
+//! - Color themes and saved settings
+//! - Sideways (leftward) gravity
+//! - Puzzle loading and win/lose detection
+//! - Sound sink event dispatch
+//! - Versus garbage exchange
+//! - Autosave snapshots
+//! - Lock flash, settle and invisible block timing
+//! - Key binding presets
+
+// Board setups read more naturally as explicit (x, y) indexing.
+#![allow(clippy::needless_range_loop)]
 
 use tetris::game::{
-    test_helpers::*, CellState, Game, GameEvent, GameState, PieceProvider, Position,
-    SequencePieceProvider, Tetromino, TetrominoType, GRID_HEIGHT, GRID_WIDTH, LINES_PER_LEVEL,
-    PREVIEW_COUNT, SCORE_DOUBLE, SCORE_SINGLE, SCORE_TETRIS, SCORE_TRIPLE,
+    board_from_file, board_from_string, board_to_string, daily_seed, seed_for_date, test_helpers::*,
+    utc_date, BoardFileError, BoardParseError, CellState, FilteredPieceProvider, Game, GameConfig,
+    GameEvent, GameMode, GameState, GravityDirection, HoldOrder, Input, LockOut, ModeProgress,
+    Movability, PieceProvider, Position, RenderCell, SeededPieceProvider, SequencePieceProvider,
+    Tetromino, TetrominoType, WeightedRandomProvider, GRID_HEIGHT, GRID_WIDTH, LINES_PER_LEVEL,
+    PREVIEW_COUNT, SCORE_DOUBLE, SCORE_SINGLE, SCORE_SOFT_DROP, SCORE_TETRIS, SCORE_TRIPLE,
+    SCORE_TSPIN_TRIPLE,
 };
 use serial_test::serial;
+use tetris::bitboard::BitBoard;
+use tetris::clock::{Clock, FrameTimer, MockClock};
+use tetris::effects::{
+    InvisibleStack, LockFlash, Settle, INVISIBLE_AFTER_MS, REVEAL_MS, SETTLE_MS,
+};
+use tetris::finesse::{optimal_inputs, Placement};
+use tetris::keys::{KeyBindings, KeyPreset};
+use tetris::garbage::{
+    cheese_holes, CheeseConfig, GarbageAttackConfig, GarbageScheduler, RisingFloorConfig,
+};
+use tetris::pieces::{is_known, register_piece, PieceDefinition, PieceError};
+use tetris::puzzle::{Puzzle, PuzzleError, PuzzleGoal};
+use tetris::replay::{state_checksum, Playback, Replay, ShareCodeError, FRAME_MS, SHARE_CODE_VERSION};
+use tetris::rotation::{RotationState, RotationSystem};
+use tetris::settings::Settings;
+use tetris::snapshot::GameSnapshot;
+use tetris::sound::{play_events, NoSound, SoundSink};
+use tetris::stats::SessionStats;
+use tetris::theme::{next_theme_index, theme_index, CellShape, GhostStyle, Theme, THEMES};
+use tetris::versus::{garbage_for_lines, VersusMatch};
+use base64::engine::general_purpose::URL_SAFE_NO_PAD;
+use base64::Engine;
+use crossterm::event::KeyCode;
+use ratatui::style::Color;
+use std::path::{Path, PathBuf};
+
+/// A high score file of the test's own under the temp dir, so tests never
+/// touch the player's `highscore.txt`. Starts out missing.
+fn temp_high_score_file(name: &str) -> PathBuf {
+    let path = std::env::temp_dir()
+        .join(format!("tetris-highscore-{}-{}.txt", name, std::process::id()));
+    let _ = std::fs::remove_file(&path);
+    path
+}
 
 // ============================================================================
 // Piece Movement Tests
@@ -106,6 +169,38 @@ mod piece_movement {
         let events = game.take_events();
         assert!(events.contains(&GameEvent::PieceMoved));
     }
+
+    #[test]
+    fn only_movement_and_soft_drop_repeat_when_held() {
+        let repeating = [Input::MoveLeft, Input::MoveRight, Input::SoftDrop];
+        let single = [Input::HardDrop, Input::RotateCw, Input::RotateCcw, Input::Hold, Input::TogglePause];
+
+        assert!(repeating.iter().all(|input| input.repeats_when_held()));
+        assert!(single.iter().all(|input| !input.repeats_when_held()));
+    }
+
+    #[test]
+    fn apply_inputs_runs_a_program_in_order() {
+        let provider = SequencePieceProvider::new(vec![TetrominoType::O]);
+        let mut game = Game::with_provider(Box::new(provider));
+        game.take_events();
+        game.toggle_pause();
+        game.toggle_pause();
+
+        use Input::*;
+        let events = game.apply_inputs(&[
+            MoveLeft, MoveLeft, MoveLeft, MoveLeft, HardDrop,
+            MoveRight, MoveRight, MoveRight, MoveRight, HardDrop,
+        ]);
+
+        let board = board_to_string(&game.grid);
+        let bottom: Vec<&str> = board.lines().rev().take(3).collect();
+        assert_eq!(bottom, ["OO......OO", "OO......OO", ".........."]);
+        let locks = events.iter().filter(|event| **event == GameEvent::PieceLocked).count();
+        assert_eq!(locks, 2);
+        // Earlier events are left for take_events
+        assert_eq!(game.take_events(), [GameEvent::Paused, GameEvent::Unpaused]);
+    }
 }
 
 // ============================================================================
@@ -157,6 +252,39 @@ mod rotation {
         assert!(game.rotate_piece(true));
     }
 
+    #[test]
+    fn plain_rotation_records_no_kick() {
+        let piece = Tetromino::new_at(TetrominoType::T, 4, 5);
+        let mut game = Game::with_grid(empty_grid(), piece);
+
+        assert!(game.rotate_piece(true));
+        assert_eq!(game.last_kick(), None);
+    }
+
+    #[test]
+    fn rotation_against_wall_records_kick() {
+        // Vertical T against the right wall widens to 3 cells when rotated
+        let mut piece = Tetromino::new_at(TetrominoType::T, GRID_WIDTH as i16 - 2, 5);
+        piece.rotation = 1;
+        let mut game = Game::with_grid(empty_grid(), piece);
+
+        assert!(game.rotate_piece(true));
+        assert_eq!(game.last_kick(), Some((-1, 0)));
+        assert_eq!(game.current_piece.position.x, GRID_WIDTH as i16 - 3);
+    }
+
+    #[test]
+    fn spawn_resets_last_kick() {
+        let mut piece = Tetromino::new_at(TetrominoType::T, GRID_WIDTH as i16 - 2, 5);
+        piece.rotation = 1;
+        let mut game = Game::with_grid(empty_grid(), piece);
+        game.rotate_piece(true);
+
+        game.hard_drop();
+
+        assert_eq!(game.last_kick(), None);
+    }
+
     #[test]
     fn rotation_emits_event() {
         let piece = Tetromino::new_at(TetrominoType::T, 4, 5);
@@ -168,6 +296,248 @@ mod rotation {
         let events = game.take_events();
         assert!(events.contains(&GameEvent::PieceRotated));
     }
+
+    #[test]
+    fn rotation_state_names_each_quarter_turn() {
+        let mut piece = Tetromino::new_at(TetrominoType::T, 4, 5);
+        let states = [
+            RotationState::Spawn,
+            RotationState::Right,
+            RotationState::Two,
+            RotationState::Left,
+            RotationState::Spawn,
+        ];
+
+        for (rotation, state) in states.into_iter().enumerate() {
+            piece.rotation = rotation;
+            assert_eq!(piece.rotation_state(), state, "rotation {}", rotation);
+        }
+    }
+
+    #[test]
+    fn piece_cells_at_matches_tetromino_blocks() {
+        for piece_type in TetrominoType::ALL {
+            for rotation in 0..8 {
+                let mut piece = Tetromino::new_at(piece_type, 3, -1);
+                piece.rotation = rotation;
+
+                assert_eq!(
+                    Game::piece_cells_at(piece_type, 3, -1, rotation),
+                    piece.blocks(),
+                    "{:?} rotation {}",
+                    piece_type,
+                    rotation
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn bounding_box_covers_each_t_rotation() {
+        let mut piece = Tetromino::new_at(TetrominoType::T, 3, 5);
+        let expected = [(3, 5, 5, 6), (3, 5, 4, 7), (3, 5, 5, 6), (3, 5, 4, 7)];
+
+        for (rotation, bounds) in expected.into_iter().enumerate() {
+            piece.rotation = rotation;
+            assert_eq!(piece.bounding_box(), bounds, "rotation {}", rotation);
+        }
+    }
+
+    #[test]
+    fn movable_reports_a_piece_pinned_in_the_corner() {
+        // Flat I on the floor against the left wall: too low to stand up
+        let piece = Tetromino::new_at(TetrominoType::I, 0, GRID_HEIGHT as i16 - 1);
+        let mut game = Game::with_grid(empty_grid(), piece.clone());
+        game.take_events();
+
+        let expected = Movability {
+            left: false,
+            right: true,
+            down: false,
+            rotate_cw: false,
+            rotate_ccw: false,
+        };
+        assert_eq!(game.movable(), expected);
+        assert_eq!(game.current_piece, piece);
+        assert!(game.take_events().is_empty());
+    }
+
+    #[test]
+    fn movable_in_open_space_allows_everything() {
+        let game = Game::with_grid(empty_grid(), Tetromino::new_at(TetrominoType::T, 4, 5));
+        let all = Movability {
+            left: true,
+            right: true,
+            down: true,
+            rotate_cw: true,
+            rotate_ccw: true,
+        };
+        assert_eq!(game.movable(), all);
+    }
+
+    /// Vertical T against the left wall, with a block where its flat
+    /// rotation would land in place
+    fn blocked_rotation(rotation_system: RotationSystem) -> Game {
+        let mut grid = empty_grid();
+        grid[5][2] = CellState::Filled(TetrominoType::O);
+        let mut piece = Tetromino::new_at(TetrominoType::T, 0, 5);
+        piece.rotation = 1;
+        let config = GameConfig {
+            rotation_system,
+            ..GameConfig::default()
+        };
+        let provider = SequencePieceProvider::new(vec![TetrominoType::T]);
+        let mut game = Game::with_config(config, Box::new(provider));
+        game.grid = grid;
+        game.current_piece = piece;
+        game
+    }
+
+    #[test]
+    fn blocked_rotation_fails_without_kicks() {
+        let mut game = blocked_rotation(RotationSystem::None);
+        let before = game.current_piece.clone();
+
+        assert!(!game.rotate_piece(true));
+        assert_eq!(game.current_piece, before);
+    }
+
+    #[test]
+    fn blocked_rotation_kicks_under_srs_and_classic() {
+        for rotation_system in [RotationSystem::Srs, RotationSystem::Classic] {
+            let mut game = blocked_rotation(rotation_system);
+
+            assert!(game.rotate_piece(true), "{:?}", rotation_system);
+            assert_eq!(game.current_piece.rotation, 2);
+            assert!(game.last_kick().is_some());
+        }
+    }
+
+    #[test]
+    fn free_rotation_works_without_kicks() {
+        let config = GameConfig {
+            rotation_system: RotationSystem::None,
+            ..GameConfig::default()
+        };
+        let provider = SequencePieceProvider::new(vec![TetrominoType::T]);
+        let mut game = Game::with_config(config, Box::new(provider));
+        game.current_piece = Tetromino::new_at(TetrominoType::T, 4, 5);
+
+        assert!(game.rotate_piece(true));
+        assert_eq!(game.current_piece.position, Position { x: 4, y: 5 });
+    }
+
+    /// A freshly spawned SRS I piece, lying flat along the top row
+    fn spawned_srs_i(buffer_rows: usize) -> Game {
+        let config = GameConfig {
+            rotation_system: RotationSystem::Srs,
+            buffer_rows,
+            ..GameConfig::default()
+        };
+        let provider = SequencePieceProvider::new(vec![TetrominoType::I]);
+        Game::with_config(config, Box::new(provider))
+    }
+
+    #[test]
+    fn spawned_piece_rotates_into_the_buffer() {
+        let mut game = spawned_srs_i(2);
+        assert_eq!(game.current_piece.bounding_box().1, 0);
+
+        // Standing up around the SRS center pokes one block above the board
+        assert!(game.rotate_piece(true));
+        assert_eq!(game.last_kick(), None);
+        assert_eq!(game.current_piece.bounding_box().1, -1);
+        assert!(game.is_valid_position(&game.current_piece));
+    }
+
+    #[test]
+    fn rotation_without_a_buffer_stays_on_the_board() {
+        let mut game = spawned_srs_i(0);
+
+        assert!(game.rotate_piece(true));
+        assert!(game.last_kick().is_some());
+        assert!(game.current_piece.blocks().iter().all(|block| block.y >= 0));
+    }
+
+    #[test]
+    fn the_buffer_is_off_by_default() {
+        let game = Game::new();
+
+        assert_eq!(game.config.buffer_rows, 0);
+        assert!(!game.is_valid_position(&Tetromino::new_at(TetrominoType::O, 0, -1)));
+    }
+
+    #[test]
+    fn pieces_cannot_rise_past_the_buffer() {
+        let game = spawned_srs_i(2);
+        let mut piece = Tetromino::new_at(TetrominoType::I, 0, -2);
+        piece.rotation = 1;
+        assert!(game.is_valid_position(&piece));
+
+        piece.position.y = -3;
+        assert!(!game.is_valid_position(&piece));
+    }
+
+    #[test]
+    fn buffer_rows_count_as_empty_but_stay_in_the_walls() {
+        let mut game = spawned_srs_i(2);
+        game.grid[0][0] = CellState::Filled(TetrominoType::O);
+
+        assert!(game.is_valid_position(&Tetromino::new_at(TetrominoType::O, 0, -2)));
+        assert!(!game.is_valid_position(&Tetromino::new_at(TetrominoType::O, -1, -2)));
+        assert!(!game.is_valid_position(&Tetromino::new_at(TetrominoType::O, 0, -1)));
+    }
+
+    #[test]
+    fn locking_in_the_buffer_tops_out() {
+        let mut game = spawned_srs_i(2);
+        assert!(game.rotate_piece(true));
+        // Stack the column the standing I is in right up to its foot
+        let x = game.current_piece.position.x as usize;
+        for y in 3..GRID_HEIGHT {
+            game.grid[y][x] = CellState::Filled(TetrominoType::O);
+        }
+
+        game.hard_drop();
+
+        assert_eq!(game.state, GameState::GameOver);
+    }
+
+    #[test]
+    fn full_lock_out_keeps_playing_after_a_partly_hidden_lock() {
+        let mut game = spawned_srs_i(2);
+        game.config.lock_out = LockOut::Full;
+        assert!(game.rotate_piece(true));
+        // Clear of the next I spawning across the top row
+        while game.move_piece(-1, 0) {}
+        let x = game.current_piece.position.x as usize;
+        for y in 3..GRID_HEIGHT {
+            game.grid[y][x] = CellState::Filled(TetrominoType::O);
+        }
+
+        game.hard_drop();
+
+        // The block up in the buffer is lost, the visible three stay
+        assert_eq!(game.state, GameState::Playing);
+        assert!((0..3).all(|y| game.grid[y][x] == CellState::Filled(TetrominoType::I)));
+    }
+
+    #[test]
+    fn lock_out_strictness_decides_a_lock_entirely_above_the_board() {
+        for lock_out in [LockOut::Partial, LockOut::Full] {
+            let mut game = spawned_srs_i(2);
+            game.config.lock_out = lock_out;
+            for row in game.grid.iter_mut() {
+                row[4] = CellState::Garbage;
+                row[5] = CellState::Garbage;
+            }
+            game.current_piece = Tetromino::new_at(TetrominoType::O, 4, -2);
+
+            game.hard_drop();
+
+            assert_eq!(game.state, GameState::GameOver, "{:?}", lock_out);
+        }
+    }
 }
 
 // ============================================================================
@@ -298,6 +668,57 @@ mod line_clearing {
 
         assert_eq!(cleared, 2);
     }
+
+    #[test]
+    fn cleared_row_indices_are_reported_top_to_bottom() {
+        let mut grid = empty_grid();
+        for y in [GRID_HEIGHT - 1, GRID_HEIGHT - 2, GRID_HEIGHT - 4, GRID_HEIGHT - 5] {
+            fill_row(&mut grid, y);
+        }
+        grid[GRID_HEIGHT - 3][0] = CellState::Filled(TetrominoType::T);
+
+        let piece = Tetromino::new_at(TetrominoType::I, 0, 0);
+        let mut game = Game::with_grid(grid, piece);
+        game.take_events();
+
+        assert_eq!(game.clear_lines(), 4);
+
+        let events = game.take_events();
+        let expected = vec![GRID_HEIGHT - 5, GRID_HEIGHT - 4, GRID_HEIGHT - 2, GRID_HEIGHT - 1];
+        assert_eq!(
+            events,
+            vec![GameEvent::LinesCleared(4), GameEvent::LinesClearedAt(expected)]
+        );
+        // The lone partial row between them settles on the floor
+        assert_eq!(game.grid[GRID_HEIGHT - 1][0], CellState::Filled(TetrominoType::T));
+        assert_eq!(game.filled_count_in_row(GRID_HEIGHT - 1), 1);
+    }
+
+    #[test]
+    fn ghost_reports_only_the_rows_it_would_complete() {
+        let mut grid = empty_grid();
+        fill_row(&mut grid, GRID_HEIGHT - 1);
+        fill_row(&mut grid, GRID_HEIGHT - 2);
+        // Bottom row waits on the O's two columns; the one above lacks a third
+        grid[GRID_HEIGHT - 1][4] = CellState::Empty;
+        grid[GRID_HEIGHT - 1][5] = CellState::Empty;
+        grid[GRID_HEIGHT - 2][4] = CellState::Empty;
+        grid[GRID_HEIGHT - 2][5] = CellState::Empty;
+        grid[GRID_HEIGHT - 2][6] = CellState::Empty;
+
+        let piece = Tetromino::new_at(TetrominoType::O, 4, 0);
+        let game = Game::with_grid(grid.clone(), piece);
+
+        assert_eq!(game.rows_cleared_by_ghost(), vec![GRID_HEIGHT - 1]);
+        // Read-only: nothing was placed
+        assert_eq!(game.grid, grid);
+
+        let mut shifted = Game::with_grid(grid, Tetromino::new_at(TetrominoType::O, 5, 0));
+        assert!(shifted.rows_cleared_by_ghost().is_empty());
+        shifted.current_piece = Tetromino::new_at(TetrominoType::O, 4, 0);
+        shifted.hard_drop();
+        assert_eq!(shifted.lines_cleared, 1);
+    }
 }
 
 // ============================================================================
@@ -307,6 +728,45 @@ mod line_clearing {
 mod scoring {
     use super::*;
 
+    #[test]
+    fn only_player_soft_drops_score() {
+        let piece = Tetromino::new(TetrominoType::O);
+        let mut game = Game::with_grid(empty_grid(), piece);
+        game.config.soft_drop_scoring = true;
+        let start_y = game.current_piece.position.y;
+
+        // Gravity pulls the piece down three rows for free
+        game.tick_with_delta(game.tick_duration_ms() * 3);
+        assert_eq!(game.current_piece.position.y, start_y + 3);
+        assert_eq!(game.score, 0);
+
+        for _ in 0..4 {
+            game.soft_drop();
+        }
+        assert_eq!(game.current_piece.position.y, start_y + 7);
+        assert_eq!(game.score, 4 * SCORE_SOFT_DROP);
+
+        // Landing on the floor doesn't score a row it didn't fall
+        game.current_piece = game.ghost_piece();
+        game.config.soft_drop_locks_instantly = false;
+        game.soft_drop();
+        assert_eq!(game.score, 4 * SCORE_SOFT_DROP);
+    }
+
+    #[test]
+    fn soft_drops_do_not_score_by_default() {
+        let piece = Tetromino::new(TetrominoType::O);
+        let mut game = Game::with_grid(empty_grid(), piece);
+        let start_y = game.current_piece.position.y;
+
+        for _ in 0..4 {
+            game.soft_drop();
+        }
+
+        assert_eq!(game.current_piece.position.y, start_y + 4);
+        assert_eq!(game.score, 0);
+    }
+
     #[test]
     fn single_line_scores_correctly() {
         let piece = Tetromino::new(TetrominoType::O);
@@ -359,6 +819,24 @@ mod scoring {
         assert_eq!(game.score, SCORE_SINGLE * 3);
     }
 
+    #[test]
+    fn score_for_lines_matches_add_score_delta() {
+        let piece = Tetromino::new(TetrominoType::O);
+        let mut game = Game::with_grid(empty_grid(), piece);
+        game.level = 4;
+
+        for lines in 0..=4 {
+            let before = game.score;
+            let predicted = game.score_for_lines(lines);
+            assert_eq!(game.score, before); // Prediction doesn't mutate
+
+            game.add_score(lines);
+
+            assert_eq!(game.score - before, predicted);
+        }
+        assert_eq!(game.score_for_lines(4), SCORE_TETRIS * 4);
+    }
+
     #[test]
     fn level_increases_after_lines_threshold() {
         let piece = Tetromino::new(TetrominoType::O);
@@ -383,90 +861,368 @@ mod scoring {
         let events = game.take_events();
         assert!(events.contains(&GameEvent::LevelUp(2)));
     }
-}
 
-// ============================================================================
-// Hard Drop Tests
-// ============================================================================
+    fn chain_bonus_game(bonus: u32) -> Game {
+        let config = GameConfig {
+            tetris_chain_bonus: bonus,
+            ..GameConfig::default()
+        };
+        Game::with_config(config, Box::new(SequencePieceProvider::new(vec![TetrominoType::O])))
+    }
 
-mod hard_drop {
-    use super::*;
+    /// Score `add_score(lines)` adds on top of the plain line clear value
+    fn bonus_for(game: &mut Game, lines: u32) -> u32 {
+        let before = game.score;
+        let plain = match lines {
+            3 => SCORE_TRIPLE,
+            4 => SCORE_TETRIS,
+            _ => unreachable!("only Triples and Tetrises are scored here"),
+        };
+        let base = plain * game.effective_level();
+        game.add_score(lines);
+        game.score - before - base
+    }
 
     #[test]
-    fn hard_drop_moves_piece_to_bottom() {
-        let piece = Tetromino::new_at(TetrominoType::O, 4, 0);
-        let mut game = Game::with_grid(empty_grid(), piece);
+    fn consecutive_tetrises_earn_an_escalating_bonus() {
+        let mut game = chain_bonus_game(100);
 
-        game.hard_drop();
-
-        // O piece should be locked at bottom (y = GRID_HEIGHT - 2)
-        // Check that cells are filled
-        assert_ne!(
-            game.grid[GRID_HEIGHT - 1][4],
-            CellState::Empty
-        );
-        assert_ne!(
-            game.grid[GRID_HEIGHT - 1][5],
-            CellState::Empty
-        );
+        assert_eq!(bonus_for(&mut game, 4), 0);
+        assert_eq!(bonus_for(&mut game, 4), 100);
+        assert_eq!(bonus_for(&mut game, 4), 200);
+        assert_eq!(game.consecutive_tetris(), 3);
     }
 
     #[test]
-    fn hard_drop_locks_piece_immediately() {
-        let piece = Tetromino::new_at(TetrominoType::O, 4, 0);
-        let mut game = Game::with_grid(empty_grid(), piece);
-        game.take_events();
-
-        game.hard_drop();
+    fn any_other_clear_breaks_the_tetris_chain() {
+        let mut game = chain_bonus_game(100);
+        game.add_score(4);
+        game.add_score(4);
 
-        let events = game.take_events();
-        assert!(events.contains(&GameEvent::PieceLocked));
+        assert_eq!(bonus_for(&mut game, 3), 0);
+        assert_eq!(game.consecutive_tetris(), 0);
+        assert_eq!(bonus_for(&mut game, 4), 0);
+        assert_eq!(bonus_for(&mut game, 4), 100);
     }
 
     #[test]
-    fn hard_drop_spawns_new_piece() {
-        let pieces = vec![TetrominoType::O, TetrominoType::T, TetrominoType::I];
-        let provider = Box::new(SequencePieceProvider::new(pieces));
-        let mut game = Game::with_provider(provider);
+    fn score_for_lines_includes_the_tetris_chain_bonus() {
+        let mut game = chain_bonus_game(100);
 
-        let first_piece_type = game.current_piece.tetromino_type;
-        game.hard_drop();
+        for lines in [4, 4, 4, 2, 4, 4] {
+            let before = game.score;
+            let predicted = game.score_for_lines(lines);
 
-        assert_ne!(game.current_piece.tetromino_type, first_piece_type);
+            game.add_score(lines);
+
+            assert_eq!(game.score - before, predicted);
+        }
+        assert_eq!(game.score_for_lines(4), SCORE_TETRIS * game.effective_level() + 200);
     }
 
     #[test]
-    fn hard_drop_clears_lines() {
-        let mut grid = empty_grid();
-        // Fill bottom row except for columns 4 and 5 (where O piece will land)
-        for x in 0..GRID_WIDTH {
-            if x != 4 && x != 5 {
-                grid[GRID_HEIGHT - 1][x] = CellState::Filled(TetrominoType::T);
-                grid[GRID_HEIGHT - 2][x] = CellState::Filled(TetrominoType::T);
-            }
-        }
+    fn tetris_chain_bonus_is_off_by_default() {
+        let mut game = chain_bonus_game(GameConfig::default().tetris_chain_bonus);
+        game.add_score(4);
 
-        let piece = Tetromino::new_at(TetrominoType::O, 4, 0);
-        let mut game = Game::with_grid(grid, piece);
-        game.take_events();
+        assert_eq!(bonus_for(&mut game, 4), 0);
+        assert_eq!(game.consecutive_tetris(), 2);
+    }
 
-        game.hard_drop();
+    #[test]
+    fn score_to_high_score_counts_down_until_the_best_is_beaten() {
+        let mut game = Game::with_grid(empty_grid(), Tetromino::new(TetrominoType::O));
+        game.high_score = 1000;
 
-        let events = game.take_events();
-        assert!(events.iter().any(|e| matches!(e, GameEvent::LinesCleared(2))));
-    }
-}
+        game.score = 400;
+        assert_eq!(game.score_to_high_score(), Some(600));
 
-// ============================================================================
-// Soft Drop Tests
-// ============================================================================
+        game.score = 1000;
+        assert_eq!(game.score_to_high_score(), Some(0));
 
-mod soft_drop {
-    use super::*;
+        game.score = 1001;
+        assert_eq!(game.score_to_high_score(), None);
+    }
 
     #[test]
-    fn soft_drop_moves_piece_down_one() {
-        let piece = Tetromino::new_at(TetrominoType::O, 4, 0);
+    fn set_level_jumps_ahead_of_lines() {
+        let piece = Tetromino::new(TetrominoType::O);
+        let mut game = Game::with_grid(empty_grid(), piece);
+
+        game.set_level(5);
+        assert_eq!(game.level, 5);
+
+        // Clears below the chosen level's threshold leave it alone
+        game.add_score(LINES_PER_LEVEL);
+        assert_eq!(game.level, 5);
+    }
+
+    #[test]
+    fn set_level_never_drops_below_earned_level() {
+        let piece = Tetromino::new(TetrominoType::O);
+        let mut game = Game::with_grid(empty_grid(), piece);
+        game.add_score(LINES_PER_LEVEL * 2);
+
+        game.set_level(1);
+
+        assert_eq!(game.level, 3);
+    }
+
+    fn capped_game(cap: u32) -> Game {
+        let config = GameConfig {
+            level_cap: Some(cap),
+            ..GameConfig::default()
+        };
+        Game::with_config(config, Box::new(SequencePieceProvider::new(vec![TetrominoType::O])))
+    }
+
+    #[test]
+    fn level_cap_limits_score_multiplier() {
+        let mut game = capped_game(10);
+        game.level = 15;
+
+        game.add_score(1);
+
+        assert_eq!(game.score, SCORE_SINGLE * 10);
+        assert_eq!(game.score_for_tspin(3, false), SCORE_TSPIN_TRIPLE * 10);
+        assert_eq!(game.effective_level(), 10);
+    }
+
+    #[test]
+    fn level_keeps_counting_past_cap() {
+        let mut game = capped_game(2);
+        game.level = 2;
+        game.lines_cleared = 2 * LINES_PER_LEVEL;
+
+        game.add_score(1);
+
+        assert_eq!(game.level, 3);
+        assert_eq!(game.effective_level(), 2);
+    }
+
+    #[test]
+    fn level_cap_limits_gravity() {
+        let mut capped = capped_game(3);
+        let mut uncapped = Game::with_grid(empty_grid(), Tetromino::new(TetrominoType::O));
+        capped.level = 8;
+        uncapped.level = 3;
+
+        assert_eq!(capped.tick_duration_ms(), uncapped.tick_duration_ms());
+    }
+
+    #[test]
+    fn uncapped_by_default() {
+        let mut game = Game::with_grid(empty_grid(), Tetromino::new(TetrominoType::O));
+        game.level = 40;
+
+        assert_eq!(game.effective_level(), 40);
+        assert_eq!(game.score_for_lines(1), SCORE_SINGLE * 40);
+    }
+
+    fn new_high_score_events(game: &mut Game) -> usize {
+        let events = game.take_events();
+        events.iter().filter(|event| **event == GameEvent::NewHighScore).count()
+    }
+
+    #[test]
+    fn passing_the_high_score_is_announced_once() {
+        let mut game = Game::with_grid(empty_grid(), Tetromino::new(TetrominoType::O));
+        game.high_score = 500;
+        game.score = 400;
+        game.take_events();
+
+        game.add_score(1);
+        assert_eq!(game.score, 500);
+        assert_eq!(new_high_score_events(&mut game), 0, "tying is not beating");
+
+        game.add_score(1);
+        assert_eq!(new_high_score_events(&mut game), 1);
+
+        game.add_score(4);
+        game.add_score(2);
+        assert_eq!(new_high_score_events(&mut game), 0);
+    }
+
+    #[test]
+    fn first_ever_run_has_no_high_score_to_beat() {
+        let mut game = Game::with_grid(empty_grid(), Tetromino::new(TetrominoType::O));
+        game.high_score = 0;
+        game.take_events();
+
+        game.add_score(1);
+
+        assert_eq!(new_high_score_events(&mut game), 0);
+    }
+
+    #[test]
+    fn score_saturates_instead_of_wrapping() {
+        let mut game = Game::with_grid(empty_grid(), Tetromino::new(TetrominoType::O));
+        game.score = u32::MAX - 10;
+
+        game.add_score(4);
+        assert_eq!(game.score, u32::MAX);
+
+        game.level = u32::MAX;
+        assert_eq!(game.score_for_lines(4), u32::MAX);
+        assert_eq!(game.score_for_tspin(3, false), u32::MAX);
+        game.add_score(1);
+        assert_eq!(game.score, u32::MAX);
+    }
+}
+
+// ============================================================================
+// Hard Drop Tests
+// ============================================================================
+
+mod hard_drop {
+    use super::*;
+
+    #[test]
+    fn hard_drop_moves_piece_to_bottom() {
+        let piece = Tetromino::new_at(TetrominoType::O, 4, 0);
+        let mut game = Game::with_grid(empty_grid(), piece);
+
+        game.hard_drop();
+
+        // O piece should be locked at bottom (y = GRID_HEIGHT - 2)
+        // Check that cells are filled
+        assert_ne!(
+            game.grid[GRID_HEIGHT - 1][4],
+            CellState::Empty
+        );
+        assert_ne!(
+            game.grid[GRID_HEIGHT - 1][5],
+            CellState::Empty
+        );
+    }
+
+    #[test]
+    fn hard_drop_emits_landed_piece_before_lock() {
+        let piece = Tetromino::new_at(TetrominoType::O, 4, 0);
+        let mut game = Game::with_grid(empty_grid(), piece);
+        game.take_events();
+
+        game.hard_drop();
+
+        let events = game.take_events();
+        let landed = Tetromino::new_at(TetrominoType::O, 4, GRID_HEIGHT as i16 - 2);
+        assert_eq!(events[0], GameEvent::HardDrop(landed));
+        assert_eq!(events[1], GameEvent::PieceLocked);
+    }
+
+    #[test]
+    fn soft_drop_lock_emits_no_hard_drop() {
+        let piece = Tetromino::new_at(TetrominoType::O, 4, GRID_HEIGHT as i16 - 2);
+        let mut game = Game::with_grid(empty_grid(), piece);
+
+        game.soft_drop();
+
+        let events = game.take_events();
+        assert!(events.contains(&GameEvent::PieceLocked));
+        assert!(!events.iter().any(|e| matches!(e, GameEvent::HardDrop(_))));
+    }
+
+    #[test]
+    fn ghost_piece_marks_hard_drop_landing() {
+        let mut grid = empty_grid();
+        grid[GRID_HEIGHT - 1][4] = CellState::Filled(TetrominoType::T);
+
+        let piece = Tetromino::new_at(TetrominoType::O, 4, 0);
+        let game = Game::with_grid(grid, piece);
+
+        let ghost = game.ghost_piece();
+
+        assert_eq!(ghost.position.x, 4);
+        assert_eq!(ghost.position.y, GRID_HEIGHT as i16 - 3);
+        assert_eq!(game.current_piece.position.y, 0);
+    }
+
+    #[test]
+    fn hard_drop_locks_piece_immediately() {
+        let piece = Tetromino::new_at(TetrominoType::O, 4, 0);
+        let mut game = Game::with_grid(empty_grid(), piece);
+        game.take_events();
+
+        game.hard_drop();
+
+        let events = game.take_events();
+        assert!(events.contains(&GameEvent::PieceLocked));
+    }
+
+    #[test]
+    fn hard_drop_spawns_new_piece() {
+        let pieces = vec![TetrominoType::O, TetrominoType::T, TetrominoType::I];
+        let provider = Box::new(SequencePieceProvider::new(pieces));
+        let mut game = Game::with_provider(provider);
+
+        let first_piece_type = game.current_piece.tetromino_type;
+        game.hard_drop();
+
+        assert_ne!(game.current_piece.tetromino_type, first_piece_type);
+    }
+
+    #[test]
+    fn hard_drops_emit_spawn_events_in_sequence() {
+        let pieces = vec![
+            TetrominoType::T,
+            TetrominoType::S,
+            TetrominoType::Z,
+            TetrominoType::L,
+            TetrominoType::J,
+        ];
+        let provider = Box::new(SequencePieceProvider::new(pieces));
+        let mut game = Game::with_provider(provider);
+        game.take_events();
+
+        game.hard_drop();
+        game.hard_drop();
+
+        let spawned: Vec<_> = game
+            .take_events()
+            .into_iter()
+            .filter(|e| matches!(e, GameEvent::PieceSpawned(_)))
+            .collect();
+        assert_eq!(
+            spawned,
+            vec![
+                GameEvent::PieceSpawned(TetrominoType::T),
+                GameEvent::PieceSpawned(TetrominoType::S),
+            ]
+        );
+    }
+
+    #[test]
+    fn hard_drop_clears_lines() {
+        let mut grid = empty_grid();
+        // Fill bottom row except for columns 4 and 5 (where O piece will land)
+        for x in 0..GRID_WIDTH {
+            if x != 4 && x != 5 {
+                grid[GRID_HEIGHT - 1][x] = CellState::Filled(TetrominoType::T);
+                grid[GRID_HEIGHT - 2][x] = CellState::Filled(TetrominoType::T);
+            }
+        }
+
+        let piece = Tetromino::new_at(TetrominoType::O, 4, 0);
+        let mut game = Game::with_grid(grid, piece);
+        game.take_events();
+
+        game.hard_drop();
+
+        let events = game.take_events();
+        assert!(events.iter().any(|e| matches!(e, GameEvent::LinesCleared(2))));
+    }
+}
+
+// ============================================================================
+// Soft Drop Tests
+// ============================================================================
+
+mod soft_drop {
+    use super::*;
+
+    #[test]
+    fn soft_drop_moves_piece_down_one() {
+        let piece = Tetromino::new_at(TetrominoType::O, 4, 0);
         let mut game = Game::with_grid(empty_grid(), piece);
 
         game.soft_drop();
@@ -486,6 +1242,46 @@ mod soft_drop {
         assert!(events.contains(&GameEvent::PieceLocked));
     }
 
+    fn grounded_game(soft_drop_locks_instantly: bool) -> Game {
+        let config = GameConfig {
+            soft_drop_locks_instantly,
+            ..GameConfig::default()
+        };
+        let provider = SequencePieceProvider::new(vec![TetrominoType::O]);
+        let mut game = Game::with_config(config, Box::new(provider));
+        game.current_piece = game.ghost_piece();
+        game.take_events();
+        game
+    }
+
+    #[test]
+    fn soft_drop_locks_instantly_by_default() {
+        assert!(GameConfig::default().soft_drop_locks_instantly);
+
+        let mut game = grounded_game(true);
+        game.soft_drop();
+
+        assert!(game.take_events().contains(&GameEvent::PieceLocked));
+    }
+
+    #[test]
+    fn grounded_soft_drop_waits_for_gravity_when_not_instant() {
+        let mut game = grounded_game(false);
+        let grounded = game.current_piece.clone();
+
+        game.soft_drop();
+
+        // Still in play, so it can slide along the floor
+        assert!(!game.take_events().contains(&GameEvent::PieceLocked));
+        assert_eq!(game.current_piece, grounded);
+        game.apply_input(Input::MoveLeft);
+        assert_ne!(game.current_piece, grounded);
+
+        game.tick_with_delta(game.time_until_tick_ms());
+
+        assert!(game.take_events().contains(&GameEvent::PieceLocked));
+    }
+
     #[test]
     fn soft_drop_locks_when_blocked() {
         let mut grid = empty_grid();
@@ -500,6 +1296,34 @@ mod soft_drop {
         let events = game.take_events();
         assert!(events.contains(&GameEvent::PieceLocked));
     }
+
+    #[test]
+    fn sonic_drop_lands_at_ghost_without_locking() {
+        let mut grid = empty_grid();
+        grid[GRID_HEIGHT - 1][4] = CellState::Filled(TetrominoType::T);
+        let mut game = Game::with_grid(grid, Tetromino::new_at(TetrominoType::O, 4, 0));
+        game.take_events();
+        let ghost = game.ghost_piece();
+
+        assert!(game.sonic_drop());
+
+        assert_eq!(game.current_piece, ghost);
+        assert_eq!(game.current_piece.position.y, GRID_HEIGHT as i16 - 3);
+        assert_eq!(game.take_events(), vec![GameEvent::PieceMoved]);
+        // Still free to slide along the stack
+        assert!(game.move_piece(1, 0));
+    }
+
+    #[test]
+    fn sonic_drop_on_the_floor_does_nothing() {
+        let piece = Tetromino::new_at(TetrominoType::O, 4, GRID_HEIGHT as i16 - 2);
+        let mut game = Game::with_grid(empty_grid(), piece.clone());
+        game.take_events();
+
+        assert!(!game.sonic_drop());
+        assert_eq!(game.current_piece, piece);
+        assert!(game.take_events().is_empty());
+    }
 }
 
 // ============================================================================
@@ -526,6 +1350,23 @@ mod game_over {
         assert!(game.is_game_over());
     }
 
+    #[test]
+    fn blocked_spawn_emits_no_spawn_event() {
+        let mut grid = empty_grid();
+        for x in 0..GRID_WIDTH {
+            grid[0][x] = CellState::Filled(TetrominoType::T);
+        }
+
+        let piece = Tetromino::new_at(TetrominoType::O, 0, 10);
+        let mut game = Game::with_grid(grid, piece);
+        game.take_events();
+
+        game.spawn_next_piece();
+
+        let events = game.take_events();
+        assert!(!events.iter().any(|e| matches!(e, GameEvent::PieceSpawned(_))));
+    }
+
     #[test]
     fn game_over_emits_event() {
         let mut grid = empty_grid();
@@ -610,25 +1451,226 @@ mod render_consistency {
     }
 
     #[test]
-    fn render_grid_current_piece_overlays_correctly() {
-        // Edge case: what if current piece position overlaps with grid cell visually?
-        // render_grid should show the current piece
+    fn render_grid_detailed_shows_ghost_below_active_piece() {
         let mut grid = empty_grid();
-        grid[5][4] = CellState::Filled(TetrominoType::T); // Place a T block
+        grid[GRID_HEIGHT - 1][0] = CellState::Filled(TetrominoType::T);
 
-        let piece = Tetromino::new_at(TetrominoType::O, 4, 5); // O piece overlaps at (4,5)
+        let piece = Tetromino::new_at(TetrominoType::O, 4, 5);
         let game = Game::with_grid(grid, piece);
 
-        let visual = game.render_grid();
+        let visual = game.render_grid_detailed();
 
-        // Current piece should be shown (O), not the underlying T
-        assert_eq!(visual[5][4], CellState::Filled(TetrominoType::O));
+        assert_eq!(visual[5][4], RenderCell::Active(TetrominoType::O, None));
+        assert_eq!(visual[6][5], RenderCell::Active(TetrominoType::O, None));
+        // Ghost rests on the floor, directly below the piece
+        assert_eq!(visual[GRID_HEIGHT - 2][4], RenderCell::Ghost(TetrominoType::O, None));
+        assert_eq!(visual[GRID_HEIGHT - 1][5], RenderCell::Ghost(TetrominoType::O, None));
+        assert_eq!(visual[GRID_HEIGHT - 1][0], RenderCell::Locked(TetrominoType::T, None));
+        assert_eq!(visual[GRID_HEIGHT - 1][9], RenderCell::Empty);
     }
-}
+
+    #[test]
+    fn render_grid_detailed_active_piece_covers_ghost_when_resting() {
+        let piece = Tetromino::new_at(TetrominoType::O, 4, GRID_HEIGHT as i16 - 2);
+        let game = Game::with_grid(empty_grid(), piece);
+
+        let visual = game.render_grid_detailed();
+
+        let ghosts = visual
+            .iter()
+            .flatten()
+            .filter(|cell| matches!(cell, RenderCell::Ghost(..)))
+            .count();
+        assert_eq!(ghosts, 0);
+        assert_eq!(visual[GRID_HEIGHT - 1][4], RenderCell::Active(TetrominoType::O, None));
+    }
+
+    fn ghost_cells(game: &Game) -> Vec<(usize, usize)> {
+        let visual = game.render_grid_detailed();
+        let mut cells = Vec::new();
+        for y in 0..GRID_HEIGHT {
+            for x in 0..GRID_WIDTH {
+                if visual[y][x] == RenderCell::Ghost(game.current_piece.tetromino_type, None) {
+                    cells.push((x, y));
+                }
+            }
+        }
+        cells
+    }
+
+    #[test]
+    fn ghost_is_drawn_on_the_spawn_frame() {
+        let provider = SequencePieceProvider::new(vec![TetrominoType::T]);
+        let mut game = Game::with_config(GameConfig::default(), Box::new(provider));
+        game.current_piece = Tetromino::new_at(TetrominoType::O, 0, 0);
+
+        // Lock the O; the T spawns with no tick in between
+        game.hard_drop();
+        assert!(game.take_events().contains(&GameEvent::PieceSpawned(TetrominoType::T)));
+
+        let mut expected: Vec<(usize, usize)> = game
+            .ghost_piece()
+            .blocks()
+            .iter()
+            .map(|b| (b.x as usize, b.y as usize))
+            .collect();
+        expected.sort_by_key(|&(x, y)| (y, x));
+        assert_eq!(ghost_cells(&game), expected);
+        assert_eq!(expected.iter().map(|&(_, y)| y).max(), Some(GRID_HEIGHT - 1));
+    }
+
+    #[test]
+    fn spawn_on_a_tall_stack_draws_only_the_piece() {
+        let mut grid = empty_grid();
+        // Stack reaching up to row 2, leaving rows 0-1 free for an O
+        for y in 2..GRID_HEIGHT {
+            for x in 0..GRID_WIDTH {
+                if x != y % GRID_WIDTH {
+                    grid[y][x] = CellState::Filled(TetrominoType::T);
+                }
+            }
+        }
+        let game = Game::with_grid(grid, Tetromino::new(TetrominoType::O));
+
+        assert_eq!(game.ghost_piece(), game.current_piece);
+        assert!(ghost_cells(&game).is_empty());
+        let visual = game.render_grid_detailed();
+        for block in game.current_piece.blocks() {
+            let cell = visual[block.y as usize][block.x as usize];
+            assert_eq!(cell, RenderCell::Active(TetrominoType::O, None));
+        }
+    }
+
+    #[test]
+    fn occupied_cells_yields_only_filled_cells() {
+        let mut grid = empty_grid();
+        grid[GRID_HEIGHT - 1][0] = CellState::Filled(TetrominoType::T);
+        grid[GRID_HEIGHT - 1][9] = CellState::Filled(TetrominoType::S);
+        grid[12][4] = CellState::Filled(TetrominoType::I);
+
+        let piece = Tetromino::new_at(TetrominoType::O, 4, 0);
+        let game = Game::with_grid(grid, piece);
+
+        let cells: Vec<_> = game.occupied_cells(false).collect();
+        assert_eq!(
+            cells,
+            vec![
+                (4, 12, CellState::Filled(TetrominoType::I)),
+                (0, GRID_HEIGHT - 1, CellState::Filled(TetrominoType::T)),
+                (9, GRID_HEIGHT - 1, CellState::Filled(TetrominoType::S)),
+            ]
+        );
+    }
+
+    #[test]
+    fn occupied_cells_can_include_current_piece() {
+        let mut grid = empty_grid();
+        grid[GRID_HEIGHT - 1][0] = CellState::Filled(TetrominoType::T);
+
+        let piece = Tetromino::new_at(TetrominoType::O, 4, 5);
+        let game = Game::with_grid(grid, piece);
+
+        let cells: Vec<_> = game.occupied_cells(true).collect();
+        assert_eq!(cells.len(), 5);
+        assert!(cells.contains(&(4, 5, CellState::Filled(TetrominoType::O))));
+        assert!(cells.contains(&(5, 6, CellState::Filled(TetrominoType::O))));
+        assert_eq!(cells.len(), game.total_filled_cells() + 4);
+    }
+
+    #[test]
+    fn cell_matrix_is_row_major_from_the_top() {
+        let mut grid = empty_grid();
+        grid[GRID_HEIGHT - 1][0] = CellState::Filled(TetrominoType::T);
+        grid[GRID_HEIGHT - 1][9] = CellState::Garbage;
+
+        let piece = Tetromino::new_at(TetrominoType::O, 4, 5);
+        let game = Game::with_grid(grid, piece);
+
+        let matrix = game.to_cell_matrix(false);
+        assert_eq!(matrix.len(), GRID_HEIGHT);
+        assert!(matrix.iter().all(|row| row.len() == GRID_WIDTH));
+        assert_eq!(matrix[GRID_HEIGHT - 1][0], Some(TetrominoType::T));
+        assert_eq!(matrix[GRID_HEIGHT - 1][9], None);
+        assert_eq!(matrix[5][4], None);
+
+        let with_piece = game.to_cell_matrix(true);
+        assert_eq!(with_piece[5][4], Some(TetrominoType::O));
+        assert_eq!(with_piece[6][5], Some(TetrominoType::O));
+        assert_eq!(with_piece.iter().flatten().flatten().count(), 5);
+    }
+
+    #[test]
+    fn render_grid_current_piece_overlays_correctly() {
+        // Edge case: what if current piece position overlaps with grid cell visually?
+        // render_grid should show the current piece
+        let mut grid = empty_grid();
+        grid[5][4] = CellState::Filled(TetrominoType::T); // Place a T block
+
+        let piece = Tetromino::new_at(TetrominoType::O, 4, 5); // O piece overlaps at (4,5)
+        let game = Game::with_grid(grid, piece);
+
+        let visual = game.render_grid();
+
+        // Current piece should be shown (O), not the underlying T
+        assert_eq!(visual[5][4], CellState::Filled(TetrominoType::O));
+    }
+
+    fn chaos_game() -> Game {
+        let config = GameConfig {
+            chaos_colors: true,
+            ..GameConfig::default()
+        };
+        Game::with_config(config, Box::new(SequencePieceProvider::new(vec![TetrominoType::O])))
+    }
+
+    #[test]
+    fn locked_cells_keep_their_randomized_color() {
+        let mut game = chaos_game();
+        let color = game.current_piece.color.expect("chaos pieces spawn with a color");
+        let landing = game.ghost_piece().blocks();
+
+        game.hard_drop();
+
+        let visual = game.render_grid_detailed();
+        for block in landing {
+            let (x, y) = (block.x as usize, block.y as usize);
+            assert_eq!(game.grid[y][x], CellState::Tinted(TetrominoType::O, color));
+            assert_eq!(visual[y][x], RenderCell::Locked(TetrominoType::O, Some(color)));
+            assert_eq!(game.to_cell_matrix(false)[y][x], Some(TetrominoType::O));
+        }
+        assert!(game.current_piece.color.is_some());
+    }
+
+    #[test]
+    fn tinted_cells_clear_like_filled_ones() {
+        let mut game = chaos_game();
+        fill_row(&mut game.grid, GRID_HEIGHT - 1);
+        game.grid[GRID_HEIGHT - 1][3] = CellState::Tinted(TetrominoType::S, (200, 100, 50));
+
+        assert_eq!(game.clear_lines(), 1);
+        assert_eq!(game.filled_count_in_row(GRID_HEIGHT - 1), 0);
+    }
+
+    #[test]
+    fn pieces_have_no_color_of_their_own_by_default() {
+        let provider = SequencePieceProvider::new(vec![TetrominoType::O]);
+        let mut game = Game::with_config(GameConfig::default(), Box::new(provider));
+        assert_eq!(game.current_piece.color, None);
+        let landing = game.ghost_piece().blocks();
+
+        game.hard_drop();
+
+        let block = landing[0];
+        assert_eq!(
+            game.grid[block.y as usize][block.x as usize],
+            CellState::Filled(TetrominoType::O)
+        );
+    }
+}
 
 // ============================================================================
-// Deterministic Piece Provider Tests
-// ============================================================================
+// Deterministic Piece Provider Tests
+// ============================================================================
 
 mod piece_provider {
     use super::*;
@@ -642,6 +1684,141 @@ mod piece_provider {
         assert_eq!(provider.next_piece(), TetrominoType::I); // Cycles
     }
 
+    #[test]
+    fn set_preview_queue_controls_spawn_order() {
+        let provider = Box::new(SequencePieceProvider::new(vec![TetrominoType::O]));
+        let mut game = Game::with_provider(provider);
+        game.set_preview_queue(vec![
+            TetrominoType::I,
+            TetrominoType::T,
+            TetrominoType::S,
+            TetrominoType::Z,
+        ]);
+
+        let mut spawned = Vec::new();
+        for _ in 0..5 {
+            game.hard_drop();
+            spawned.push(game.current_piece.tetromino_type);
+        }
+
+        // The set queue comes out in order, then the provider takes over
+        assert_eq!(
+            spawned,
+            vec![
+                TetrominoType::I,
+                TetrominoType::T,
+                TetrominoType::S,
+                TetrominoType::Z,
+                TetrominoType::O,
+            ]
+        );
+    }
+
+    #[test]
+    fn single_type_filter_deals_only_that_type() {
+        let all = SequencePieceProvider::new(TetrominoType::ALL.to_vec());
+        let provider = FilteredPieceProvider::new(Box::new(all), vec![TetrominoType::S]);
+        let mut game = Game::with_provider(Box::new(provider));
+
+        assert_eq!(game.current_piece.tetromino_type, TetrominoType::S);
+        assert!(game.preview_queue.iter().all(|&t| t == TetrominoType::S));
+        game.hard_drop();
+        assert_eq!(game.current_piece.tetromino_type, TetrominoType::S);
+    }
+
+    #[test]
+    fn subset_filter_keeps_the_inner_order() {
+        let all = SequencePieceProvider::new(TetrominoType::ALL.to_vec());
+        let allowed = vec![TetrominoType::S, TetrominoType::Z];
+        let mut provider = FilteredPieceProvider::new(Box::new(all), allowed);
+
+        let dealt: Vec<TetrominoType> = (0..4).map(|_| provider.next_piece()).collect();
+        assert_eq!(
+            dealt,
+            vec![TetrominoType::S, TetrominoType::Z, TetrominoType::S, TetrominoType::Z]
+        );
+    }
+
+    #[test]
+    fn piece_letters_parse_in_either_case() {
+        assert_eq!(TetrominoType::from_letter('s'), Some(TetrominoType::S));
+        assert_eq!(TetrominoType::from_letter('L'), Some(TetrominoType::L));
+        assert_eq!(TetrominoType::from_letter('x'), None);
+    }
+
+    #[test]
+    fn preview_shows_a_prefix_of_the_queue() {
+        let pieces = vec![TetrominoType::I, TetrominoType::T, TetrominoType::S, TetrominoType::Z];
+        let game = Game::with_provider(Box::new(SequencePieceProvider::new(pieces)));
+        let queue: Vec<TetrominoType> = game.preview_queue.iter().copied().collect();
+
+        assert_eq!(game.preview(0), Vec::new());
+        assert_eq!(game.preview(2), queue[..2].to_vec());
+        assert_eq!(game.preview(PREVIEW_COUNT), queue);
+        // More than the queue holds is clamped to the queue
+        assert_eq!(game.preview(PREVIEW_COUNT + 3), queue);
+    }
+
+    #[test]
+    fn peek_spawn_matches_the_next_spawn_without_consuming_it() {
+        let pieces = vec![TetrominoType::I, TetrominoType::T, TetrominoType::S, TetrominoType::Z];
+        let mut game = Game::with_provider(Box::new(SequencePieceProvider::new(pieces)));
+        let queue_before = game.preview_queue.clone();
+
+        let next = game.peek_spawn();
+        assert_eq!(next, game.preview_queue[0]);
+        assert_eq!(game.peek_spawn(), next);
+        assert_eq!(game.preview_queue, queue_before);
+
+        game.spawn_next_piece();
+        assert_eq!(game.current_piece.tetromino_type, next);
+        assert_eq!(game.peek_spawn(), queue_before[1]);
+    }
+
+    #[test]
+    fn set_preview_queue_pads_and_truncates() {
+        let provider = Box::new(SequencePieceProvider::new(vec![TetrominoType::O]));
+        let mut game = Game::with_provider(provider);
+
+        game.set_preview_queue(vec![TetrominoType::I]);
+        assert_eq!(game.preview_queue.len(), PREVIEW_COUNT);
+        assert_eq!(game.preview_queue[0], TetrominoType::I);
+        assert!(game.preview_queue.iter().skip(1).all(|&t| t == TetrominoType::O));
+
+        game.set_preview_queue(vec![TetrominoType::T; PREVIEW_COUNT + 3]);
+        assert_eq!(game.preview_queue.len(), PREVIEW_COUNT);
+        assert!(game.preview_queue.iter().all(|&t| t == TetrominoType::T));
+    }
+
+    #[test]
+    fn pieces_spawn_in_rotation_zero_by_default() {
+        let provider = Box::new(SequencePieceProvider::new(vec![TetrominoType::T]));
+        let game = Game::with_provider(provider);
+
+        assert_eq!(game.config.spawn_rotation(TetrominoType::T), 0);
+        assert_eq!(game.current_piece.rotation, 0);
+    }
+
+    #[test]
+    fn configured_spawn_rotation_is_applied() {
+        let mut config = GameConfig::default();
+        config.set_spawn_rotation(TetrominoType::T, 2);
+        let provider = Box::new(SequencePieceProvider::new(vec![TetrominoType::T, TetrominoType::O]));
+        let mut game = Game::with_config(config, provider);
+
+        // Sequence T, O, T, O, T: the first current piece is T
+        assert_eq!(game.current_piece.tetromino_type, TetrominoType::T);
+        assert_eq!(game.current_piece.rotation, 2);
+
+        game.hard_drop();
+        assert_eq!(game.current_piece.tetromino_type, TetrominoType::T);
+        assert_eq!(game.current_piece.rotation, 2);
+
+        game.hard_drop();
+        assert_eq!(game.current_piece.tetromino_type, TetrominoType::O);
+        assert_eq!(game.current_piece.rotation, 0);
+    }
+
     #[test]
     fn game_uses_provider_for_pieces() {
         let pieces = vec![
@@ -665,6 +1842,84 @@ mod piece_provider {
         assert_eq!(preview[2], TetrominoType::Z);
         assert_eq!(preview[3], TetrominoType::L);
     }
+
+    #[test]
+    fn same_date_gives_the_same_seed() {
+        assert_eq!(seed_for_date(2024, 3, 15), seed_for_date(2024, 3, 15));
+
+        let mut a = SeededPieceProvider::new(seed_for_date(2024, 3, 15));
+        let mut b = SeededPieceProvider::new(seed_for_date(2024, 3, 15));
+        for _ in 0..20 {
+            assert_eq!(a.next_piece(), b.next_piece());
+        }
+    }
+
+    #[test]
+    fn different_dates_give_different_seeds() {
+        let dates = [(2024, 3, 15), (2024, 3, 16), (2024, 4, 15), (2025, 3, 15), (2023, 12, 31)];
+        let mut seeds: Vec<u64> = dates.iter().map(|&(y, m, d)| seed_for_date(y, m, d)).collect();
+        seeds.sort_unstable();
+        seeds.dedup();
+
+        assert_eq!(seeds.len(), dates.len());
+    }
+
+    #[test]
+    fn utc_date_converts_unix_timestamps() {
+        assert_eq!(utc_date(0), (1970, 1, 1));
+        assert_eq!(utc_date(951_782_400), (2000, 2, 29));
+        assert_eq!(utc_date(1_704_067_199), (2023, 12, 31));
+        assert_eq!(utc_date(1_704_067_200), (2024, 1, 1));
+    }
+
+    #[test]
+    fn daily_seed_is_todays_date_seed() {
+        let secs = std::time::SystemTime::now()
+            .duration_since(std::time::UNIX_EPOCH)
+            .unwrap()
+            .as_secs();
+        let (year, month, day) = utc_date(secs);
+
+        // Could straddle midnight, so allow the neighboring day
+        let (next_year, next_month, next_day) = utc_date(secs + 60);
+        let seed = daily_seed();
+        assert!(
+            seed == seed_for_date(year, month, day)
+                || seed == seed_for_date(next_year, next_month, next_day)
+        );
+    }
+
+    /// Most draws any one type went without being dealt
+    fn longest_drought(provider: &mut dyn PieceProvider, draws: u32) -> u32 {
+        let mut last_seen = [0u32; 7];
+        let mut longest = 0;
+        for draw in 1..=draws {
+            let index = provider.next_piece().standard_index().unwrap();
+            longest = longest.max(draw - last_seen[index] - 1);
+            last_seen[index] = draw;
+        }
+        longest
+    }
+
+    #[test]
+    fn strong_weighting_shortens_droughts() {
+        let uniform = longest_drought(&mut WeightedRandomProvider::new(0.0, 7), 10_000);
+        let weighted = longest_drought(&mut WeightedRandomProvider::new(1.0, 7), 10_000);
+
+        // A 7-bag never goes past 12; uniform draws routinely reach 40+
+        assert!(weighted < 20, "weighted drought {}", weighted);
+        assert!(weighted < uniform, "weighted {} vs uniform {}", weighted, uniform);
+    }
+
+    #[test]
+    fn weighted_provider_is_reproducible_from_its_seed() {
+        let mut first = WeightedRandomProvider::new(0.5, 42);
+        let mut second = WeightedRandomProvider::new(0.5, 42);
+
+        for _ in 0..100 {
+            assert_eq!(first.next_piece(), second.next_piece());
+        }
+    }
 }
 
 // ============================================================================
@@ -697,620 +1952,3754 @@ mod tick {
     }
 
     #[test]
-    fn tick_does_nothing_when_game_over() {
-        let piece = Tetromino::new_at(TetrominoType::O, 4, 5);
-        let mut game = Game::with_grid(empty_grid(), piece);
-        game.state = GameState::GameOver;
-        let initial_y = game.current_piece.position.y;
-
-        game.tick();
+    fn gravity_cells_per_second_is_the_reciprocal_of_the_tick() {
+        let mut game = Game::with_grid(empty_grid(), Tetromino::new(TetrominoType::O));
+        assert_eq!(game.tick_duration_ms(), 800);
+        assert_eq!(game.gravity_cells_per_second(), 1.25);
+
+        game.level = 7;
+        assert_eq!(game.tick_duration_ms(), 500);
+        assert_eq!(game.gravity_cells_per_second(), 2.0);
+
+        // Past the fastest tick the speed stops rising
+        game.level = 40;
+        assert_eq!(game.gravity_cells_per_second(), 10.0);
+    }
 
-        assert_eq!(game.current_piece.position.y, initial_y);
+    fn ramped_game(level_cap: Option<u32>) -> Game {
+        let config = GameConfig {
+            gravity_ramp_ms: Some(30_000),
+            level_cap,
+            ..GameConfig::default()
+        };
+        let provider = SequencePieceProvider::new(vec![TetrominoType::I]);
+        Game::with_config(config, Box::new(provider))
     }
-}
 
-// ============================================================================
-// Integration Tests - Full Game Scenarios
-// ============================================================================
+    #[test]
+    fn time_ramp_speeds_up_gravity_at_the_same_level() {
+        let mut game = ramped_game(None);
+        assert_eq!(game.tick_duration_ms(), 800);
 
-mod integration {
-    use super::*;
+        // Stalling for two minutes: pieces pile up, nothing clears
+        for _ in 0..120 {
+            game.tick_with_delta(1000);
+        }
+
+        assert_eq!(game.state, GameState::Playing);
+        assert_eq!(game.level, 1);
+        assert_eq!(game.gravity_level(), 5);
+        assert_eq!(game.tick_duration_ms(), 600);
+        assert!(game.gravity_cells_per_second() > 1.25);
+    }
 
     #[test]
-    fn complete_game_scenario_line_clear() {
-        // Setup: Almost complete bottom row, drop I piece to complete it
-        let mut grid = empty_grid();
-        for x in 0..6 {
-            grid[GRID_HEIGHT - 1][x] = CellState::Filled(TetrominoType::T);
+    fn time_ramp_never_slows_a_higher_level() {
+        let mut game = ramped_game(None);
+        game.level = 8;
+        assert_eq!(game.gravity_level(), 8);
+
+        let mut capped = ramped_game(Some(2));
+        for _ in 0..120 {
+            capped.tick_with_delta(1000);
         }
+        assert_eq!(capped.gravity_level(), 2);
+    }
 
-        // I piece horizontal at position that will fill columns 6-9
-        let pieces = vec![TetrominoType::I, TetrominoType::O];
-        let provider = Box::new(SequencePieceProvider::new(pieces));
-        let mut game = Game::with_provider(provider);
-        game.grid = grid;
-        game.current_piece = Tetromino::new_at(TetrominoType::I, 6, 0);
-        game.take_events();
+    #[test]
+    fn tick_with_delta_applies_gravity_per_tick_duration() {
+        let piece = Tetromino::new_at(TetrominoType::O, 4, 0);
+        let mut game = Game::with_grid(empty_grid(), piece);
+        let tick = game.tick_duration_ms();
 
-        // Hard drop the I piece
-        game.hard_drop();
+        game.tick_with_delta(tick - 1);
+        assert_eq!(game.current_piece.position.y, 0);
+        assert_eq!(game.time_until_tick_ms(), 1);
 
-        // Should have cleared the line
-        let events = game.take_events();
-        assert!(events.iter().any(|e| matches!(e, GameEvent::LinesCleared(1))));
-        assert_eq!(game.lines_cleared, 1);
-        assert_eq!(game.score, SCORE_SINGLE);
+        game.tick_with_delta(1);
+        assert_eq!(game.current_piece.position.y, 1);
+
+        game.tick_with_delta(tick * 3);
+        assert_eq!(game.current_piece.position.y, 4);
     }
 
     #[test]
-    fn complete_game_scenario_tetris() {
-        // Setup: 4 almost complete rows
-        let mut grid = empty_grid();
-        for y in (GRID_HEIGHT - 4)..GRID_HEIGHT {
-            for x in 0..9 {
-                grid[y][x] = CellState::Filled(TetrominoType::T);
-            }
-        }
+    fn tick_with_delta_ignores_time_while_paused() {
+        let piece = Tetromino::new_at(TetrominoType::O, 4, 0);
+        let mut game = Game::with_grid(empty_grid(), piece);
+        let tick = game.tick_duration_ms();
 
-        let pieces = vec![TetrominoType::I, TetrominoType::O];
-        let provider = Box::new(SequencePieceProvider::new(pieces));
-        let mut game = Game::with_provider(provider);
-        game.grid = grid;
-        // I piece vertical at column 9
-        game.current_piece = Tetromino::new_at(TetrominoType::I, 9, 0);
-        game.current_piece.rotation = 1; // Vertical
-        game.take_events();
+        game.toggle_pause();
+        game.tick_with_delta(tick * 5);
+        game.toggle_pause();
 
-        game.hard_drop();
+        assert_eq!(game.current_piece.position.y, 0);
+        assert_eq!(game.time_until_tick_ms(), tick);
+    }
 
-        let events = game.take_events();
+    #[test]
+    fn elapsed_time_excludes_pauses() {
+        let mut game = Game::with_grid(empty_grid(), Tetromino::new(TetrominoType::O));
+
+        game.tick_with_delta(300);
+        game.toggle_pause();
+        game.tick_with_delta(10_000);
+        game.toggle_pause();
+        game.tick_with_delta(200);
+
+        assert_eq!(game.elapsed_ms(), 500);
+    }
+
+    #[test]
+    fn elapsed_time_stops_at_game_over_and_resets_on_restart() {
+        let mut game = Game::with_grid(empty_grid(), Tetromino::new(TetrominoType::O));
+        game.tick_with_delta(400);
+        game.state = GameState::GameOver;
+
+        game.tick_with_delta(1000);
+        assert_eq!(game.elapsed_ms(), 400);
+
+        game.restart();
+        assert_eq!(game.elapsed_ms(), 0);
+    }
+
+    #[test]
+    fn lines_per_minute_is_zero_before_the_first_line() {
+        let mut game = Game::with_grid(empty_grid(), Tetromino::new(TetrominoType::O));
+        assert_eq!(game.lines_per_minute(), 0.0);
+
+        game.tick_with_delta(600);
+
+        assert_eq!(game.lines_per_minute(), 0.0);
+    }
+
+    #[test]
+    fn lines_per_minute_scales_lines_by_play_time() {
+        let mut game = Game::with_grid(empty_grid(), Tetromino::new(TetrominoType::O));
+        game.lines_cleared = 2;
+
+        game.tick_with_delta(600);
+
+        assert_eq!(game.lines_per_minute(), 200.0);
+    }
+
+    #[test]
+    fn pieces_per_second_is_zero_before_any_play_time() {
+        let mut game = Game::with_grid(empty_grid(), Tetromino::new(TetrominoType::O));
+        game.hard_drop();
+
+        assert_eq!(game.pieces_locked(), 1);
+        assert_eq!(game.pieces_per_second(), 0.0);
+    }
+
+    #[test]
+    fn pieces_per_second_divides_locked_pieces_by_play_time() {
+        let provider = SequencePieceProvider::new(vec![TetrominoType::O]);
+        let mut game = Game::with_config(GameConfig::default(), Box::new(provider));
+        for _ in 0..4 {
+            game.hard_drop();
+        }
+
+        game.tick_with_delta(2000);
+
+        assert_eq!(game.pieces_locked(), 4);
+        assert_eq!(game.pieces_per_second(), 2.0);
+    }
+
+    #[test]
+    fn pace_update_is_sent_each_second_of_play() {
+        let mut game = Game::with_grid(empty_grid(), Tetromino::new(TetrominoType::O));
+        game.lines_cleared = 11;
+        game.take_events();
+
+        game.tick_with_delta(600);
+        assert!(!game
+            .take_events()
+            .iter()
+            .any(|event| matches!(event, GameEvent::PaceUpdate(_))));
+
+        game.tick_with_delta(500);
+        let paces: Vec<GameEvent> = game
+            .take_events()
+            .into_iter()
+            .filter(|event| matches!(event, GameEvent::PaceUpdate(_)))
+            .collect();
+        assert_eq!(paces, vec![GameEvent::PaceUpdate(60_000)]);
+    }
+
+    #[test]
+    fn tick_does_nothing_when_game_over() {
+        let piece = Tetromino::new_at(TetrominoType::O, 4, 5);
+        let mut game = Game::with_grid(empty_grid(), piece);
+        game.state = GameState::GameOver;
+        let initial_y = game.current_piece.position.y;
+
+        game.tick();
+
+        assert_eq!(game.current_piece.position.y, initial_y);
+    }
+}
+
+// ============================================================================
+// Integration Tests - Full Game Scenarios
+// ============================================================================
+
+mod integration {
+    use super::*;
+
+    #[test]
+    fn complete_game_scenario_line_clear() {
+        // Setup: Almost complete bottom row, drop I piece to complete it
+        let mut grid = empty_grid();
+        for x in 0..6 {
+            grid[GRID_HEIGHT - 1][x] = CellState::Filled(TetrominoType::T);
+        }
+
+        // I piece horizontal at position that will fill columns 6-9
+        let pieces = vec![TetrominoType::I, TetrominoType::O];
+        let provider = Box::new(SequencePieceProvider::new(pieces));
+        let mut game = Game::with_provider(provider);
+        game.grid = grid;
+        game.current_piece = Tetromino::new_at(TetrominoType::I, 6, 0);
+        game.take_events();
+
+        // Hard drop the I piece
+        game.hard_drop();
+
+        // Should have cleared the line
+        let events = game.take_events();
+        assert!(events.iter().any(|e| matches!(e, GameEvent::LinesCleared(1))));
+        assert_eq!(game.lines_cleared, 1);
+        assert_eq!(game.score, SCORE_SINGLE);
+    }
+
+    #[test]
+    fn complete_game_scenario_tetris() {
+        // Setup: 4 almost complete rows
+        let mut grid = empty_grid();
+        for y in (GRID_HEIGHT - 4)..GRID_HEIGHT {
+            for x in 0..9 {
+                grid[y][x] = CellState::Filled(TetrominoType::T);
+            }
+        }
+
+        let pieces = vec![TetrominoType::I, TetrominoType::O];
+        let provider = Box::new(SequencePieceProvider::new(pieces));
+        let mut game = Game::with_provider(provider);
+        game.grid = grid;
+        // I piece vertical at column 9
+        game.current_piece = Tetromino::new_at(TetrominoType::I, 9, 0);
+        game.current_piece.rotation = 1; // Vertical
+        game.take_events();
+
+        game.hard_drop();
+
+        let events = game.take_events();
         assert!(events.iter().any(|e| matches!(e, GameEvent::LinesCleared(4))));
         assert_eq!(game.score, SCORE_TETRIS);
     }
 
     #[test]
-    fn rapid_soft_drops_work_correctly() {
-        let piece = Tetromino::new_at(TetrominoType::O, 4, 0);
-        let mut game = Game::with_grid(empty_grid(), piece);
+    fn rapid_soft_drops_work_correctly() {
+        let piece = Tetromino::new_at(TetrominoType::O, 4, 0);
+        let mut game = Game::with_grid(empty_grid(), piece);
+
+        // Soft drop all the way down
+        for _ in 0..30 {
+            game.soft_drop();
+            if game.current_piece.position.y == 0 {
+                // New piece spawned
+                break;
+            }
+        }
+
+        // Should have locked and spawned new piece
+        assert!(game.total_filled_cells() > 0);
+    }
+
+    #[test]
+    fn game_state_consistent_after_many_operations() {
+        let pieces: Vec<TetrominoType> = vec![
+            TetrominoType::T,
+            TetrominoType::S,
+            TetrominoType::Z,
+            TetrominoType::L,
+            TetrominoType::J,
+            TetrominoType::I,
+            TetrominoType::O,
+        ];
+        let provider = Box::new(SequencePieceProvider::new(pieces));
+        let mut game = Game::with_provider(provider);
+
+        // Simulate some gameplay
+        for _ in 0..10 {
+            game.move_piece(-1, 0);
+            game.move_piece(1, 0);
+            game.rotate_piece(true);
+            game.hard_drop();
+
+            if game.is_game_over() {
+                break;
+            }
+        }
+
+        // Verify render_grid is valid
+        let visual = game.render_grid();
+        assert_eq!(visual.len(), GRID_HEIGHT);
+        assert_eq!(visual[0].len(), GRID_WIDTH);
+
+        // Every cell should be a valid CellState
+        for row in &visual {
+            for cell in row {
+                match cell {
+                    CellState::Empty => {}
+                    CellState::Filled(_) => {}
+                    CellState::Garbage => {}
+                    CellState::Tinted(..) => {}
+                }
+            }
+        }
+    }
+}
+
+// ============================================================================
+// Edge Cases
+// ============================================================================
+
+mod edge_cases {
+    use super::*;
+
+    #[test]
+    fn i_piece_at_top_row() {
+        // I piece horizontal at y=0 should be valid
+        let piece = Tetromino::new_at(TetrominoType::I, 0, 0);
+        let game = Game::with_grid(empty_grid(), piece);
+
+        assert!(game.is_valid_position(&game.current_piece));
+    }
+
+    #[test]
+    fn piece_at_exact_boundaries() {
+        // Test pieces at exact grid boundaries
+        let test_cases = vec![
+            (TetrominoType::O, 0, 0),                                         // Top-left
+            (TetrominoType::O, GRID_WIDTH as i16 - 2, 0),                     // Top-right
+            (TetrominoType::O, 0, GRID_HEIGHT as i16 - 2),                    // Bottom-left
+            (TetrominoType::O, GRID_WIDTH as i16 - 2, GRID_HEIGHT as i16 - 2), // Bottom-right
+        ];
+
+        for (piece_type, x, y) in test_cases {
+            let piece = Tetromino::new_at(piece_type, x, y);
+            let game = Game::with_grid(empty_grid(), piece);
+            assert!(
+                game.is_valid_position(&game.current_piece),
+                "Piece at ({}, {}) should be valid",
+                x,
+                y
+            );
+        }
+    }
+
+    #[test]
+    fn clear_top_row() {
+        let mut grid = empty_grid();
+        fill_row(&mut grid, 0); // Fill top row
+
+        let piece = Tetromino::new_at(TetrominoType::O, 4, 10);
+        let mut game = Game::with_grid(grid, piece);
+
+        let cleared = game.clear_lines();
+
+        assert_eq!(cleared, 1);
+        assert_eq!(game.filled_count_in_row(0), 0);
+    }
+
+    #[test]
+    fn all_rows_filled_and_cleared() {
+        let mut grid = empty_grid();
+        for y in 0..GRID_HEIGHT {
+            fill_row(&mut grid, y);
+        }
+
+        let piece = Tetromino::new_at(TetrominoType::O, 4, 0);
+        let mut game = Game::with_grid(grid, piece);
+
+        let cleared = game.clear_lines();
+
+        assert_eq!(cleared, GRID_HEIGHT as u32);
+        // After clearing all rows, grid should be empty
+        for y in 0..GRID_HEIGHT {
+            assert!(!game.is_row_complete(y));
+        }
+    }
+}
+
+// ============================================================================
+// Pause Tests
+// ============================================================================
+
+mod pause {
+    use super::*;
+
+    #[test]
+    fn state_helpers_follow_pause_and_unpause() {
+        let mut game = Game::new();
+        assert!(game.is_playing());
+        assert!(!game.is_paused());
+        assert!(!game.is_game_over());
+
+        game.toggle_pause();
+        assert!(!game.is_playing());
+        assert!(game.is_paused());
+        assert!(!game.is_game_over());
+
+        game.toggle_pause();
+        assert!(game.is_playing());
+        assert!(!game.is_paused());
+    }
+
+    #[test]
+    fn state_helpers_after_game_over() {
+        let mut game = Game::new();
+        game.state = GameState::GameOver;
+
+        assert!(!game.is_playing());
+        assert!(!game.is_paused());
+        assert!(game.is_game_over());
+
+        // Pausing is refused once the game is over
+        game.toggle_pause();
+        assert!(!game.is_paused());
+    }
+
+    #[test]
+    fn pause_from_playing_state() {
+        let mut game = Game::new();
+        assert_eq!(game.state, GameState::Playing);
+        game.take_events();
+
+        game.toggle_pause();
+
+        assert_eq!(game.state, GameState::Paused);
+        let events = game.take_events();
+        assert!(events.contains(&GameEvent::Paused));
+    }
+
+    #[test]
+    fn unpause_from_paused_state() {
+        let mut game = Game::new();
+        game.toggle_pause(); // Pause first
+        game.take_events();
+
+        game.toggle_pause(); // Unpause
+
+        assert_eq!(game.state, GameState::Playing);
+        let events = game.take_events();
+        assert!(events.contains(&GameEvent::Unpaused));
+    }
+
+    #[test]
+    fn cannot_pause_when_game_over() {
+        let mut game = Game::new();
+        game.state = GameState::GameOver;
+
+        game.toggle_pause();
+
+        assert_eq!(game.state, GameState::GameOver);
+    }
+
+    #[test]
+    fn tick_does_nothing_when_paused() {
+        let piece = Tetromino::new_at(TetrominoType::O, 4, 5);
+        let mut game = Game::with_grid(empty_grid(), piece);
+        let initial_y = game.current_piece.position.y;
+
+        game.toggle_pause();
+        game.tick();
+
+        assert_eq!(game.current_piece.position.y, initial_y);
+    }
+
+    #[test]
+    fn cannot_move_piece_when_paused() {
+        let piece = Tetromino::new_at(TetrominoType::O, 4, 5);
+        let mut game = Game::with_grid(empty_grid(), piece);
+        let initial_x = game.current_piece.position.x;
+
+        game.toggle_pause();
+        let moved = game.move_piece(1, 0);
+
+        assert!(!moved);
+        assert_eq!(game.current_piece.position.x, initial_x);
+    }
+
+    #[test]
+    fn cannot_rotate_piece_when_paused() {
+        let piece = Tetromino::new_at(TetrominoType::T, 4, 5);
+        let mut game = Game::with_grid(empty_grid(), piece);
+        let initial_rotation = game.current_piece.rotation;
+
+        game.toggle_pause();
+        let rotated = game.rotate_piece(true);
+
+        assert!(!rotated);
+        assert_eq!(game.current_piece.rotation, initial_rotation);
+    }
+
+    #[test]
+    fn pause_and_unpause_multiple_times() {
+        let mut game = Game::new();
+
+        game.toggle_pause();
+        assert_eq!(game.state, GameState::Paused);
+
+        game.toggle_pause();
+        assert_eq!(game.state, GameState::Playing);
+
+        game.toggle_pause();
+        assert_eq!(game.state, GameState::Paused);
+    }
+
+    #[test]
+    fn no_move_events_when_paused() {
+        let piece = Tetromino::new_at(TetrominoType::O, 4, 5);
+        let mut game = Game::with_grid(empty_grid(), piece);
+        game.toggle_pause();
+        game.take_events(); // Clear pause event
+
+        game.move_piece(-1, 0);
+        game.move_piece(1, 0);
+        game.move_piece(0, 1);
+
+        let events = game.take_events();
+        assert!(events.is_empty());
+        assert!(!events.contains(&GameEvent::PieceMoved));
+    }
+
+    #[test]
+    fn no_rotation_events_when_paused() {
+        let piece = Tetromino::new_at(TetrominoType::T, 4, 5);
+        let mut game = Game::with_grid(empty_grid(), piece);
+        game.toggle_pause();
+        game.take_events(); // Clear pause event
+
+        game.rotate_piece(true);
+        game.rotate_piece(false);
+
+        let events = game.take_events();
+        assert!(events.is_empty());
+        assert!(!events.contains(&GameEvent::PieceRotated));
+    }
+
+    #[test]
+    fn no_drop_events_when_paused() {
+        let piece = Tetromino::new_at(TetrominoType::O, 4, 0);
+        let mut game = Game::with_grid(empty_grid(), piece);
+        game.toggle_pause();
+        game.take_events(); // Clear pause event
+
+        game.soft_drop();
+        game.hard_drop();
+
+        let events = game.take_events();
+        assert!(events.is_empty());
+        assert!(!events.contains(&GameEvent::PieceMoved));
+        assert!(!events.contains(&GameEvent::PieceLocked));
+    }
+}
+
+// ============================================================================
+// Restart Tests
+// ============================================================================
+
+mod restart {
+    use super::*;
+
+    #[test]
+    fn restart_resets_score() {
+        let path = temp_high_score_file("restart-resets");
+        let mut game = Game::new().with_high_score_file(&path);
+        game.score = 5000;
+        game.state = GameState::GameOver;
+
+        game.restart();
+
+        assert_eq!(game.score, 0);
+        std::fs::remove_file(&path).unwrap();
+    }
+
+    #[test]
+    fn restart_resets_lines_and_level() {
+        let mut game = Game::new();
+        game.lines_cleared = 50;
+        game.level = 6;
+        game.state = GameState::GameOver;
+
+        game.restart();
+
+        assert_eq!(game.lines_cleared, 0);
+        assert_eq!(game.level, 1);
+    }
+
+    #[test]
+    fn restart_clears_grid() {
+        let mut grid = empty_grid();
+        // Fill some cells
+        grid[GRID_HEIGHT - 1][0] = CellState::Filled(TetrominoType::T);
+        grid[GRID_HEIGHT - 1][1] = CellState::Filled(TetrominoType::T);
+
+        let piece = Tetromino::new_at(TetrominoType::O, 4, 0);
+        let mut game = Game::with_grid(grid, piece);
+        game.state = GameState::GameOver;
+
+        game.restart();
+
+        // Grid should be empty
+        for row in &game.grid {
+            for cell in row {
+                assert_eq!(*cell, CellState::Empty);
+            }
+        }
+    }
+
+    #[test]
+    fn restart_sets_state_to_playing() {
+        let mut game = Game::new();
+        game.state = GameState::GameOver;
+
+        game.restart();
+
+        assert_eq!(game.state, GameState::Playing);
+    }
+
+    #[test]
+    fn restart_emits_event() {
+        let mut game = Game::new();
+        game.state = GameState::GameOver;
+        game.take_events();
+
+        game.restart();
+
+        let events = game.take_events();
+        assert!(events.contains(&GameEvent::GameRestarted));
+    }
+
+    #[test]
+    fn restart_after_game_over_emits_only_restart_event() {
+        let mut grid = empty_grid();
+        for x in 0..GRID_WIDTH {
+            grid[0][x] = CellState::Filled(TetrominoType::T);
+        }
+        let piece = Tetromino::new_at(TetrominoType::O, 0, 10);
+        let mut game = Game::with_grid(grid, piece);
+        game.spawn_next_piece();
+        assert!(game.is_game_over());
+
+        // Game over events are left unconsumed on purpose
+        game.restart();
+
+        assert_eq!(game.take_events(), vec![GameEvent::GameRestarted]);
+    }
+
+    #[test]
+    fn restart_spawns_new_piece() {
+        let mut game = Game::new();
+        game.state = GameState::GameOver;
+
+        game.restart();
+
+        // New piece should be spawned at spawn position
+        assert_eq!(game.current_piece.position.x, (GRID_WIDTH as i16 / 2) - 1);
+        assert_eq!(game.current_piece.position.y, 0);
+    }
+
+    #[test]
+    fn restart_with_deterministic_provider() {
+        // Test with known piece sequence to verify provider is preserved
+        let pieces = vec![
+            TetrominoType::T,
+            TetrominoType::O,
+            TetrominoType::I,
+            TetrominoType::L,
+            TetrominoType::J,
+            TetrominoType::S,
+            TetrominoType::Z,
+        ];
+        let provider = Box::new(SequencePieceProvider::new(pieces));
+        let mut game = Game::with_provider(provider);
+
+        // Use up first 5 pieces (4 for preview + 1 current)
+        game.state = GameState::GameOver;
+
+        game.restart();
+
+        // After restart, should get new pieces from the same provider
+        assert_eq!(game.state, GameState::Playing);
+        assert_eq!(game.preview_queue.len(), PREVIEW_COUNT);
+    }
+
+    #[test]
+    fn restart_game_can_be_played() {
+        let mut game = Game::new();
+        game.state = GameState::GameOver;
+
+        game.restart();
+
+        // Should be able to move pieces after restart
+        game.move_piece(1, 0);
+        // May or may not move depending on piece type and boundaries
+
+        // Should be able to tick
+        game.tick();
+
+        // Game should still be playing (not immediately game over)
+        assert_eq!(game.state, GameState::Playing);
+    }
+}
+
+// ============================================================================
+// High Score Tests
+// ============================================================================
+
+mod high_score {
+    use super::*;
+
+    // Helper to clean up high score file after tests
+    fn cleanup_high_score_file(path: &Path) {
+        let _ = std::fs::remove_file(path);
+    }
+
+    #[test]
+    #[serial]
+    fn high_score_initializes_to_zero_when_no_file() {
+        let path = temp_high_score_file("none");
+        cleanup_high_score_file(&path);
+        let game = Game::new().with_high_score_file(&path);
+        assert_eq!(game.high_score, 0);
+        cleanup_high_score_file(&path);
+    }
+
+    #[test]
+    #[serial]
+    fn high_score_loads_from_file() {
+        let path = temp_high_score_file("loads");
+        cleanup_high_score_file(&path);
+        std::fs::write(&path, "5000").unwrap();
+
+        let game = Game::new().with_high_score_file(&path);
+
+        assert_eq!(game.high_score, 5000);
+        cleanup_high_score_file(&path);
+    }
+
+    #[test]
+    #[serial]
+    fn high_score_updates_on_game_over_when_beaten() {
+        let path = temp_high_score_file("over-beaten");
+        cleanup_high_score_file(&path);
+        std::fs::write(&path, "1000").unwrap();
+
+        let mut grid = empty_grid();
+        // Fill the spawn area to trigger game over
+        for x in 0..GRID_WIDTH {
+            grid[0][x] = CellState::Filled(TetrominoType::T);
+            grid[1][x] = CellState::Filled(TetrominoType::T);
+        }
+
+        let piece = Tetromino::new_at(TetrominoType::O, 4, 10);
+        let mut game = Game::with_grid(grid, piece).with_high_score_file(&path);
+        game.score = 2000; // Set score higher than high score
+
+        game.spawn_next_piece(); // This will trigger game over
+
+        assert_eq!(game.state, GameState::GameOver);
+        assert_eq!(game.high_score, 2000);
+
+        // Verify file was updated
+        let saved_score = std::fs::read_to_string(&path)
+            .unwrap()
+            .trim()
+            .parse::<u32>()
+            .unwrap();
+        assert_eq!(saved_score, 2000);
+
+        cleanup_high_score_file(&path);
+    }
+
+    #[test]
+    #[serial]
+    fn high_score_does_not_update_when_not_beaten() {
+        let path = temp_high_score_file("over-below");
+        cleanup_high_score_file(&path);
+        std::fs::write(&path, "5000").unwrap();
+
+        let mut grid = empty_grid();
+        // Fill the spawn area to trigger game over
+        for x in 0..GRID_WIDTH {
+            grid[0][x] = CellState::Filled(TetrominoType::T);
+            grid[1][x] = CellState::Filled(TetrominoType::T);
+        }
+
+        let piece = Tetromino::new_at(TetrominoType::O, 4, 10);
+        let mut game = Game::with_grid(grid, piece).with_high_score_file(&path);
+        game.score = 3000; // Set score lower than high score
+
+        game.spawn_next_piece(); // This will trigger game over
+
+        assert_eq!(game.state, GameState::GameOver);
+        assert_eq!(game.high_score, 5000); // Should remain 5000
+
+        // Verify file was not changed
+        let saved_score = std::fs::read_to_string(&path)
+            .unwrap()
+            .trim()
+            .parse::<u32>()
+            .unwrap();
+        assert_eq!(saved_score, 5000);
+
+        cleanup_high_score_file(&path);
+    }
+
+    #[test]
+    #[serial]
+    fn high_score_persists_across_game_restarts() {
+        let path = temp_high_score_file("persists");
+        cleanup_high_score_file(&path);
+
+        // First game session - set high score
+        {
+            let mut grid = empty_grid();
+            for x in 0..GRID_WIDTH {
+                grid[0][x] = CellState::Filled(TetrominoType::T);
+                grid[1][x] = CellState::Filled(TetrominoType::T);
+            }
+
+            let piece = Tetromino::new_at(TetrominoType::O, 4, 10);
+            let mut game = Game::with_grid(grid, piece).with_high_score_file(&path);
+            game.score = 8000;
+            game.spawn_next_piece(); // Game over, saves high score
+        }
+
+        // Second game session - verify high score loaded
+        {
+            let game = Game::new().with_high_score_file(&path);
+            assert_eq!(game.high_score, 8000);
+        }
+
+        cleanup_high_score_file(&path);
+    }
+
+    #[test]
+    #[serial]
+    fn high_score_handles_invalid_file_content() {
+        let path = temp_high_score_file("invalid");
+        cleanup_high_score_file(&path);
+        std::fs::write(&path, "not_a_number").unwrap();
+
+        let game = Game::new().with_high_score_file(&path);
+
+        // Should default to 0 if file contains invalid data
+        assert_eq!(game.high_score, 0);
+        cleanup_high_score_file(&path);
+    }
+
+    #[test]
+    #[serial]
+    fn quitting_mid_game_saves_beaten_high_score() {
+        let path = temp_high_score_file("quit-beaten");
+        cleanup_high_score_file(&path);
+        std::fs::write(&path, "1000").unwrap();
+
+        let mut game = Game::new().with_high_score_file(&path);
+        game.score = 2500; // Set score higher than high score
+        game.toggle_pause();
+
+        game.on_quit();
+
+        assert_eq!(game.high_score, 2500);
+
+        // Verify file was updated
+        let saved_score = std::fs::read_to_string(&path)
+            .unwrap()
+            .trim()
+            .parse::<u32>()
+            .unwrap();
+        assert_eq!(saved_score, 2500);
+
+        cleanup_high_score_file(&path);
+    }
+
+    #[test]
+    #[serial]
+    fn quitting_below_high_score_leaves_file_alone() {
+        let path = temp_high_score_file("quit-below");
+        cleanup_high_score_file(&path);
+        std::fs::write(&path, "1000").unwrap();
+
+        let mut game = Game::new().with_high_score_file(&path);
+        game.score = 400; // Set score lower than high score
+
+        game.on_quit();
+
+        assert_eq!(game.high_score, 1000); // Should remain 1000
+
+        // Verify file was not changed
+        let saved_score = std::fs::read_to_string(&path)
+            .unwrap()
+            .trim()
+            .parse::<u32>()
+            .unwrap();
+        assert_eq!(saved_score, 1000);
+
+        cleanup_high_score_file(&path);
+    }
+
+    #[test]
+    #[serial]
+    fn restart_saves_beaten_high_score_first() {
+        let path = temp_high_score_file("restart");
+        cleanup_high_score_file(&path);
+        std::fs::write(&path, "1000").unwrap();
+
+        let mut game = Game::new().with_high_score_file(&path);
+        game.score = 4000;
+
+        game.restart();
+
+        assert_eq!(game.score, 0);
+        assert_eq!(game.high_score, 4000);
+
+        // Verify file was updated before the score was reset
+        let saved_score = std::fs::read_to_string(&path)
+            .unwrap()
+            .trim()
+            .parse::<u32>()
+            .unwrap();
+        assert_eq!(saved_score, 4000);
+
+        cleanup_high_score_file(&path);
+    }
+}
+
+// ============================================================================
+// Garbage Tests
+// ============================================================================
+
+mod garbage {
+    use super::*;
+
+    fn cheese_game(rows: usize, seed: u64) -> Game {
+        let config = GameConfig {
+            cheese: Some(CheeseConfig { rows, seed }),
+            ..GameConfig::default()
+        };
+        Game::with_config(config, Box::new(SequencePieceProvider::new(vec![TetrominoType::O])))
+    }
+
+    #[test]
+    fn cheese_board_fills_bottom_rows_with_one_hole_each() {
+        let game = cheese_game(5, 7);
+
+        let mut holes = Vec::new();
+        for y in GRID_HEIGHT - 5..GRID_HEIGHT {
+            let row = &game.grid[y];
+            let empty: Vec<usize> =
+                (0..GRID_WIDTH).filter(|&x| row[x] == CellState::Empty).collect();
+            assert_eq!(empty.len(), 1, "row {}", y);
+            assert!(row.iter().all(|cell| matches!(cell, CellState::Empty | CellState::Garbage)));
+            holes.push(empty[0]);
+        }
+        // No two neighboring rows share a hole column
+        assert!(holes.windows(2).all(|pair| pair[0] != pair[1]));
+        assert_eq!(holes, cheese_holes(CheeseConfig { rows: 5, seed: 7 }));
+
+        // Everything above the cheese is empty
+        for y in 0..GRID_HEIGHT - 5 {
+            assert!(game.grid[y].iter().all(|cell| *cell == CellState::Empty));
+        }
+    }
+
+    #[test]
+    fn cheese_is_reproducible_from_its_seed() {
+        assert_eq!(cheese_game(8, 99).grid, cheese_game(8, 99).grid);
+        assert_ne!(cheese_game(8, 99).grid, cheese_game(8, 100).grid);
+    }
+
+    #[test]
+    fn restart_brings_back_the_same_cheese() {
+        let path = temp_high_score_file("cheese");
+        let mut game = cheese_game(5, 3).with_high_score_file(&path);
+        let start = game.grid.clone();
+        game.hard_drop();
+        assert_ne!(game.grid, start);
+
+        game.restart();
+
+        assert_eq!(game.grid, start);
+        let _ = std::fs::remove_file(&path);
+    }
+
+    fn rising_game(rise_interval_ms: u64, speedup_ms: u64) -> Game {
+        let config = GameConfig {
+            rising_floor: Some(RisingFloorConfig {
+                rise_interval_ms,
+                speedup_ms,
+                min_interval_ms: 300,
+                seed: 11,
+            }),
+            ..GameConfig::default()
+        };
+        Game::with_config(config, Box::new(SequencePieceProvider::new(vec![TetrominoType::O])))
+    }
+
+    fn risen_rows(game: &Game) -> usize {
+        game.grid.iter().filter(|row| row.contains(&CellState::Garbage)).count()
+    }
+
+    #[test]
+    fn floor_rises_once_per_interval_without_clears() {
+        let mut game = rising_game(500, 0);
+
+        game.tick_with_delta(499);
+        assert_eq!(risen_rows(&game), 0);
+        game.tick_with_delta(1);
+        assert_eq!(risen_rows(&game), 1);
+        game.tick_with_delta(500);
+
+        assert_eq!(risen_rows(&game), 2);
+        game.assert_invariants();
+    }
+
+    #[test]
+    fn floor_rises_sooner_each_time_down_to_the_minimum() {
+        let config = RisingFloorConfig {
+            rise_interval_ms: 1000,
+            speedup_ms: 300,
+            min_interval_ms: 300,
+            seed: 0,
+        };
+        let intervals: Vec<u64> = (0..4).map(|rises| config.interval_after(rises)).collect();
+        assert_eq!(intervals, vec![1000, 700, 400, 300]);
+
+        let mut game = rising_game(1000, 300);
+        game.tick_with_delta(1000);
+        game.tick_with_delta(700);
+        assert_eq!(risen_rows(&game), 2);
+    }
+
+    #[test]
+    fn clearing_lines_delays_the_next_rise() {
+        let mut game = rising_game(500, 0);
+        let mut bottom = vec![CellState::Filled(TetrominoType::J); GRID_WIDTH];
+        bottom[4] = CellState::Empty;
+        bottom[5] = CellState::Empty;
+        game.grid[GRID_HEIGHT - 1] = bottom.clone();
+        game.grid[GRID_HEIGHT - 2] = bottom;
+
+        game.tick_with_delta(400);
+        game.hard_drop();
+        assert_eq!(game.lines_cleared, 2);
+
+        // Would have risen at 500 without the clear
+        game.tick_with_delta(400);
+        assert_eq!(risen_rows(&game), 0);
+        game.tick_with_delta(100);
+        assert_eq!(risen_rows(&game), 1);
+    }
+
+    fn attack_config() -> GarbageAttackConfig {
+        GarbageAttackConfig {
+            interval_ms: 5000,
+            lines_per_attack: 2,
+            seed: 42,
+        }
+    }
+
+    fn garbage_game() -> Game {
+        let config = GameConfig {
+            garbage_attack: Some(attack_config()),
+            ..GameConfig::default()
+        };
+        let provider = Box::new(SequencePieceProvider::new(vec![TetrominoType::O]));
+        Game::with_config(config, provider)
+    }
+
+    #[test]
+    fn add_garbage_lines_pushes_rows_with_single_hole() {
+        let mut grid = empty_grid();
+        grid[GRID_HEIGHT - 1][0] = CellState::Filled(TetrominoType::J);
+
+        let piece = Tetromino::new_at(TetrominoType::O, 4, 0);
+        let mut game = Game::with_grid(grid, piece);
+
+        game.add_garbage_lines(&[3, 7]);
+
+        assert_eq!(game.grid.len(), GRID_HEIGHT);
+        assert_eq!(game.filled_count_in_row(GRID_HEIGHT - 1), GRID_WIDTH - 1);
+        assert_eq!(game.filled_count_in_row(GRID_HEIGHT - 2), GRID_WIDTH - 1);
+        assert_eq!(game.grid[GRID_HEIGHT - 2][3], CellState::Empty);
+        assert_eq!(game.grid[GRID_HEIGHT - 1][7], CellState::Empty);
+        // Existing stack is pushed up
+        assert_eq!(game.grid[GRID_HEIGHT - 3][0], CellState::Filled(TetrominoType::J));
+    }
+
+    #[test]
+    fn garbage_rows_are_marked_as_garbage() {
+        let piece = Tetromino::new_at(TetrominoType::O, 4, 0);
+        let mut game = Game::with_grid(empty_grid(), piece);
+
+        game.add_garbage_lines(&[2]);
+
+        let visual = game.render_grid();
+        assert_eq!(visual[GRID_HEIGHT - 1][0], CellState::Garbage);
+        assert_eq!(visual[GRID_HEIGHT - 1][2], CellState::Empty);
+        assert!(game
+            .occupied_cells(false)
+            .all(|(_, y, cell)| y == GRID_HEIGHT - 1 && cell == CellState::Garbage));
+    }
+
+    fn lowest_row(piece: &Tetromino) -> i16 {
+        piece.blocks().iter().map(|block| block.y).max().unwrap()
+    }
+
+    #[test]
+    fn ghost_follows_garbage_under_a_falling_piece() {
+        let piece = Tetromino::new_at(TetrominoType::T, 4, 6);
+        let mut game = Game::with_grid(empty_grid(), piece.clone());
+        assert_eq!(lowest_row(&game.ghost_piece()), GRID_HEIGHT as i16 - 1);
+
+        game.add_garbage_lines(&[0, 9]);
+
+        // The piece stays put in mid-air; its ghost lands on the new stack
+        assert_eq!(game.current_piece, piece);
+        assert_eq!(lowest_row(&game.ghost_piece()), GRID_HEIGHT as i16 - 3);
+        assert!(game.is_valid_position(&game.ghost_piece()));
+        game.assert_invariants();
+    }
+
+    #[test]
+    fn garbage_lifts_a_piece_resting_on_the_stack() {
+        let piece = Tetromino::new_at(TetrominoType::O, 4, 0);
+        let mut game = Game::with_grid(empty_grid(), piece);
+        game.current_piece = game.ghost_piece();
+        let resting = game.current_piece.clone();
+
+        game.add_garbage_lines(&[0, 9]);
+
+        assert_eq!(game.current_piece.position.x, resting.position.x);
+        assert_eq!(game.current_piece.position.y, resting.position.y - 2);
+        assert_eq!(game.ghost_piece(), game.current_piece);
+        assert_eq!(game.state, GameState::Playing);
+        game.assert_invariants();
+    }
+
+    #[test]
+    fn garbage_pushing_blocks_over_the_top_ends_the_game() {
+        let mut grid = empty_grid();
+        grid[0][0] = CellState::Filled(TetrominoType::J);
+        let piece = Tetromino::new_at(TetrominoType::O, 4, 8);
+        let mut game = Game::with_grid(grid, piece);
+
+        game.add_garbage_lines(&[3]);
+
+        assert!(game.is_game_over());
+        assert!(game.take_events().contains(&GameEvent::GameOver));
+    }
+
+    #[test]
+    fn garbage_row_clears_when_hole_is_filled() {
+        let mut piece = Tetromino::new_at(TetrominoType::I, 2, 0);
+        piece.rotation = 1;
+        let mut game = Game::with_grid(empty_grid(), piece);
+        game.add_garbage_lines(&[2]);
+        assert_eq!(game.render_grid()[GRID_HEIGHT - 1][5], CellState::Garbage);
+
+        game.hard_drop();
+
+        assert_eq!(game.lines_cleared, 1);
+        assert!(!game.grid.iter().flatten().any(|cell| *cell == CellState::Garbage));
+    }
+
+    #[test]
+    fn board_text_marks_garbage_with_g() {
+        let grid = board_from_string("GGGG.GGGGG").unwrap();
+
+        assert_eq!(grid[GRID_HEIGHT - 1][0], CellState::Garbage);
+        assert_eq!(grid[GRID_HEIGHT - 1][4], CellState::Empty);
+    }
+
+    #[test]
+    fn scheduler_disabled_by_default() {
+        assert!(GarbageScheduler::from_config(&GameConfig::default()).is_none());
+    }
+
+    #[test]
+    fn scheduler_sends_garbage_after_interval() {
+        let mut game = garbage_game();
+        let mut scheduler = GarbageScheduler::from_config(&game.config).unwrap();
+
+        assert_eq!(scheduler.update(&mut game, 4999), 0);
+        assert_eq!(game.total_filled_cells(), 0);
+
+        assert_eq!(scheduler.update(&mut game, 1), 2);
+        assert_eq!(game.total_filled_cells(), 2 * (GRID_WIDTH - 1));
+        assert_eq!(game.filled_count_in_row(GRID_HEIGHT - 1), GRID_WIDTH - 1);
+        assert_eq!(game.filled_count_in_row(GRID_HEIGHT - 2), GRID_WIDTH - 1);
+        assert_eq!(game.filled_count_in_row(GRID_HEIGHT - 3), 0);
+    }
+
+    #[test]
+    fn scheduler_catches_up_on_long_frames() {
+        let mut game = garbage_game();
+        let mut scheduler = GarbageScheduler::from_config(&game.config).unwrap();
+
+        assert_eq!(scheduler.update(&mut game, 15000), 6);
+        assert_eq!(game.total_filled_cells(), 6 * (GRID_WIDTH - 1));
+    }
+
+    #[test]
+    fn scheduler_is_reproducible_for_same_seed() {
+        let mut first = garbage_game();
+        let mut second = garbage_game();
+        let mut first_scheduler = GarbageScheduler::new(attack_config());
+        let mut second_scheduler = GarbageScheduler::new(attack_config());
+
+        first_scheduler.update(&mut first, 20000);
+        second_scheduler.update(&mut second, 20000);
+
+        assert_eq!(first.grid, second.grid);
+    }
+
+    #[test]
+    fn configured_attack_sends_garbage_as_the_game_plays() {
+        let mut game = garbage_game();
+
+        game.tick_with_delta(4999);
+        assert_eq!(risen_rows(&game), 0);
+        game.tick_with_delta(1);
+        assert_eq!(risen_rows(&game), 2);
+
+        // Restarting starts the schedule over
+        game.restart();
+        game.tick_with_delta(4999);
+        assert_eq!(risen_rows(&game), 0);
+    }
+
+    #[test]
+    fn replays_reproduce_configured_attacks() {
+        let mut replay = Replay::new(7, garbage_game().config);
+        replay.record(700, Input::HardDrop);
+
+        let game = replay.seek(replay.frame_count());
+
+        assert_eq!(risen_rows(&game), 4);
+        assert_eq!(game.grid, replay.seek(replay.frame_count()).grid);
+    }
+
+    #[test]
+    fn scheduler_pauses_with_game() {
+        let mut game = garbage_game();
+        let mut scheduler = GarbageScheduler::from_config(&game.config).unwrap();
+        game.toggle_pause();
+
+        assert_eq!(scheduler.update(&mut game, 10000), 0);
+        assert_eq!(game.total_filled_cells(), 0);
+    }
+}
+
+// ============================================================================
+// Bitboard Tests
+// ============================================================================
+
+mod bitboard {
+    use super::*;
+
+    const ALL_TYPES: [TetrominoType; 7] = [
+        TetrominoType::I,
+        TetrominoType::O,
+        TetrominoType::T,
+        TetrominoType::S,
+        TetrominoType::Z,
+        TetrominoType::J,
+        TetrominoType::L,
+    ];
+
+    fn jagged_grid() -> Vec<Vec<CellState>> {
+        let mut grid = empty_grid();
+        for y in 10..GRID_HEIGHT {
+            for x in 0..GRID_WIDTH {
+                if (x * 7 + y * 3) % 5 != 0 {
+                    grid[y][x] = CellState::Filled(TetrominoType::L);
+                }
+            }
+        }
+        fill_row(&mut grid, GRID_HEIGHT - 1);
+        fill_row(&mut grid, GRID_HEIGHT - 3);
+        grid
+    }
+
+    #[test]
+    fn collision_checks_match_grid() {
+        for grid in [empty_grid(), jagged_grid()] {
+            let game = Game::with_grid(grid, Tetromino::new(TetrominoType::O));
+            let board = game.bitboard();
+
+            for piece_type in ALL_TYPES {
+                for rotation in 0..4 {
+                    for y in -3..GRID_HEIGHT as i16 + 1 {
+                        for x in -3..GRID_WIDTH as i16 + 1 {
+                            let mut piece = Tetromino::new_at(piece_type, x, y);
+                            piece.rotation = rotation;
+                            assert_eq!(
+                                board.is_valid_position(&piece),
+                                game.is_valid_position(&piece),
+                                "{:?} rotation {} at ({}, {})",
+                                piece_type,
+                                rotation,
+                                x,
+                                y
+                            );
+                        }
+                    }
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn clear_lines_matches_grid() {
+        let piece = Tetromino::new(TetrominoType::O);
+        let mut game = Game::with_grid(jagged_grid(), piece);
+        let mut board = game.bitboard();
+
+        assert_eq!(board.clear_lines(), game.clear_lines());
+        assert_eq!(board, game.bitboard());
+    }
+
+    #[test]
+    fn lock_matches_grid() {
+        let piece = Tetromino::new_at(TetrominoType::T, 3, GRID_HEIGHT as i16 - 2);
+        let mut game = Game::with_grid(empty_grid(), piece.clone());
+        let mut board = BitBoard::new();
+
+        board.lock(&piece);
+        game.hard_drop();
+
+        assert_eq!(board.total_filled_cells(), 4);
+        assert_eq!(board, game.bitboard());
+        assert!(board.is_occupied(4, GRID_HEIGHT - 2));
+    }
+}
+
+// ============================================================================
+// Clock Tests
+// ============================================================================
+
+mod clock {
+    use super::*;
+
+    #[test]
+    fn mock_clock_advances_shared_time() {
+        let clock = MockClock::new();
+        let handle = clock.clone();
+
+        handle.advance(250);
+
+        assert_eq!(clock.now_ms(), 250);
+    }
+
+    #[test]
+    fn frame_timer_reports_time_between_frames() {
+        let clock = MockClock::new();
+        let mut timer = FrameTimer::new(clock.clone());
+
+        clock.advance(16);
+        assert_eq!(timer.delta_ms(), 16);
+        assert_eq!(timer.delta_ms(), 0);
+        clock.advance(40);
+        assert_eq!(timer.delta_ms(), 40);
+    }
+
+    #[test]
+    fn simulated_frames_drive_gravity() {
+        let clock = MockClock::new();
+        let mut timer = FrameTimer::new(clock.clone());
+        let piece = Tetromino::new_at(TetrominoType::O, 4, 0);
+        let mut game = Game::with_grid(empty_grid(), piece);
+        let tick = game.tick_duration_ms();
+
+        // Frames shorter than a tick only advance the piece once enough
+        // time has built up across them
+        let frame_ms = tick / 4 + 1;
+        let mut rows_fallen = Vec::new();
+        for _ in 0..8 {
+            clock.advance(frame_ms);
+            game.tick_with_delta(timer.delta_ms());
+            rows_fallen.push(game.current_piece.position.y);
+        }
+
+        assert_eq!(rows_fallen, vec![0, 0, 0, 1, 1, 1, 1, 2]);
+    }
+}
+
+// ============================================================================
+// SRS and T-Spin Tests
+// ============================================================================
+
+mod tspin {
+    use super::*;
+
+    // Classic T-spin triple: the T slides under the overhang in the spawn
+    // orientation, then the last SRS kick drops it two rows into the slot
+    const TST_SETUP: &str = "
+        LL........
+        L.........
+        L.JJJJJJJJ
+        L..JJJJJJJ
+        L.JJJJJJJJ
+    ";
+
+    fn game_with(board: &str, rotation_system: RotationSystem) -> Game {
+        let grid = board_from_string(board).unwrap();
+        let mut game = Game::with_grid(grid, Tetromino::new(TetrominoType::T));
+        game.config.rotation_system = rotation_system;
+        game
+    }
+
+    /// Slides the spawned T under the overhang, resting at x = 1, y = 15
+    fn slide_under_overhang(game: &mut Game) {
+        while game.move_piece(0, 1) {}
+        while game.move_piece(-1, 0) {}
+        assert_eq!(game.current_piece.position, Position { x: 1, y: 15 });
+    }
+
+    #[test]
+    fn board_from_string_aligns_rows_to_bottom() {
+        let grid = board_from_string("T.........\nIIII.....O").unwrap();
+
+        assert_eq!(grid.len(), GRID_HEIGHT);
+        assert_eq!(grid[GRID_HEIGHT - 2][0], CellState::Filled(TetrominoType::T));
+        assert_eq!(grid[GRID_HEIGHT - 1][3], CellState::Filled(TetrominoType::I));
+        assert_eq!(grid[GRID_HEIGHT - 1][9], CellState::Filled(TetrominoType::O));
+        assert_eq!(grid[GRID_HEIGHT - 1][4], CellState::Empty);
+        assert!(grid[..GRID_HEIGHT - 2].iter().flatten().all(|c| *c == CellState::Empty));
+    }
+
+    #[test]
+    fn board_to_string_round_trips_through_board_from_string() {
+        let grid = board_from_string("T.........\nGGGG.GGGGG\nIIIISZJL.O").unwrap();
+
+        let text = board_to_string(&grid);
+
+        assert_eq!(text.lines().count(), GRID_HEIGHT);
+        assert!(text.ends_with("T.........\nGGGG.GGGGG\nIIIISZJL.O\n"));
+        assert_eq!(board_from_string(&text), Ok(grid));
+    }
+
+    #[test]
+    fn fill_cells_sets_an_l_shape_and_skips_out_of_bounds() {
+        let mut game = Game::with_grid(empty_grid(), Tetromino::new(TetrominoType::T));
+        let bottom = GRID_HEIGHT - 1;
+
+        game.fill_cells(&[
+            (0, bottom - 2, TetrominoType::L),
+            (0, bottom - 1, TetrominoType::L),
+            (0, bottom, TetrominoType::L),
+            (1, bottom, TetrominoType::L),
+            (GRID_WIDTH, bottom, TetrominoType::L),
+            (0, GRID_HEIGHT, TetrominoType::L),
+        ]);
+
+        assert_eq!(game.total_filled_cells(), 4);
+        assert!(board_to_string(&game.grid).ends_with("L.........\nL.........\nLL........\n"));
+    }
+
+    #[test]
+    fn board_from_string_rejects_malformed_rows() {
+        assert_eq!(board_from_string(".........."), Ok(empty_grid()));
+        assert_eq!(
+            board_from_string("..."),
+            Err(BoardParseError::WrongWidth { row: 0, width: 3 })
+        );
+        assert_eq!(
+            board_from_string("..........\n....X....."),
+            Err(BoardParseError::InvalidCell { row: 1, column: 4, found: 'X' })
+        );
+    }
+
+    #[test]
+    fn find_t_slots_spots_the_notch_under_an_overhang() {
+        let grid = board_from_string(
+            "
+            GG........
+            G...GGGGGG
+            GG.GGGGGGG
+            ",
+        )
+        .unwrap();
+        let game = Game::with_grid(grid, Tetromino::new(TetrominoType::T));
+        assert_eq!(game.find_t_slots(), vec![(2, GRID_HEIGHT - 2)]);
+
+        // Without the overhang a T just drops in, no spin needed
+        let grid = board_from_string("G...GGGGGG\nGG.GGGGGGG").unwrap();
+        let game = Game::with_grid(grid, Tetromino::new(TetrominoType::T));
+        assert!(game.find_t_slots().is_empty());
+    }
+
+    #[test]
+    fn board_from_file_starts_a_playable_game() {
+        let path = std::env::temp_dir().join(format!("tetris-board-{}.txt", std::process::id()));
+        std::fs::write(&path, "GGGG.GGGGG\nGGGG.GGGGG\n").unwrap();
+        let grid = board_from_file(&path).unwrap();
+
+        let provider = SequencePieceProvider::new(vec![TetrominoType::I]);
+        let mut game = Game::with_config(GameConfig::default(), Box::new(provider));
+        game.grid = grid;
+        assert_eq!(game.total_filled_cells(), 18);
+
+        // A vertical I down the well clears both garbage rows
+        game.rotate_piece(true);
+        while game.current_piece.blocks().iter().any(|block| block.x != 4) {
+            assert!(game.move_piece(-1, 0));
+        }
+        game.hard_drop();
+        assert_eq!(game.lines_cleared, 2);
+        assert_eq!(game.state, GameState::Playing);
+
+        std::fs::write(&path, "GGGG.GGGGG\nGGG\n").unwrap();
+        let err = board_from_file(&path).unwrap_err();
+        assert!(matches!(err, BoardFileError::Parse(BoardParseError::WrongWidth { row: 1, .. })));
+        assert!(err.to_string().contains("row 2 is 3 cells wide"));
+
+        std::fs::remove_file(&path).unwrap();
+        assert!(matches!(board_from_file(&path), Err(BoardFileError::Io(_))));
+    }
+
+    /// Draws the board the way the terminal renderer does, inside its
+    /// border and between a hold panel and a preview panel
+    fn rendered_screen(grid: &[Vec<CellState>]) -> String {
+        let mut screen = format!("┌── Hold ──┐┌{}┐┌── Next ──┐\n", "─".repeat(GRID_WIDTH * 2));
+        for (y, row) in grid.iter().enumerate() {
+            let cells: String = row
+                .iter()
+                .map(|cell| match cell {
+                    CellState::Empty => "  ",
+                    CellState::Filled(_) | CellState::Tinted(..) => "██",
+                    CellState::Garbage => "▓▓",
+                })
+                .collect();
+            let next = if y == 1 { "  ████    " } else { "          " };
+            screen.push_str(&format!("│          ││{}││{}│\n", cells, next));
+        }
+        screen.push_str(&format!("└──────────┘└{}┘└──────────┘\n", "─".repeat(GRID_WIDTH * 2)));
+        screen.push_str("WASD/JK: Move/Drop | C: Hold | Q/ESC: Quit\n");
+        screen
+    }
+
+    #[test]
+    fn board_from_rendered_round_trips_the_screen() {
+        let grid = board_from_string(
+            "
+            ...Z......
+            GGGG.GGGGG
+            TTTT.LLLLL
+            ",
+        )
+        .unwrap();
+
+        let imported = Game::board_from_rendered(&rendered_screen(&grid)).unwrap();
+
+        // Colors are lost, so pieces come back as one default type
+        let expected = board_from_string(
+            "
+            ...I......
+            GGGG.GGGGG
+            IIII.IIIII
+            ",
+        )
+        .unwrap();
+        assert_eq!(imported, expected);
+
+        // The imported board is playable: a vertical I fills the gap
+        let mut piece = Tetromino::new_at(TetrominoType::I, 4, 0);
+        piece.rotation = 1;
+        let mut game = Game::with_grid(imported, piece);
+        game.hard_drop();
+        assert_eq!(game.lines_cleared, 2);
+    }
+
+    #[test]
+    fn board_from_rendered_round_trips_a_screen_showing_the_held_ghost() {
+        let grid = board_from_string("GGGG.GGGGG").unwrap();
+        let mut lines: Vec<String> = rendered_screen(&grid).lines().map(String::from).collect();
+        // A held O's landing spot in the corner, just above the garbage
+        for y in [GRID_HEIGHT - 3, GRID_HEIGHT - 2] {
+            lines[y + 1] = lines[y + 1].replacen("││    ", "││····", 1);
+        }
+
+        let imported = Game::board_from_rendered(&lines.join("\n")).unwrap();
+
+        assert_eq!(imported, grid);
+    }
+
+    #[test]
+    fn board_from_rendered_accepts_bare_rows_and_ascii_borders() {
+        let text = "|                    |\n|██  ██    ░░[]▓▓    |";
+        let grid = Game::board_from_rendered(text).unwrap();
+
+        assert_eq!(grid.len(), GRID_HEIGHT);
+        let bottom = &grid[GRID_HEIGHT - 1];
+        assert_eq!(bottom[0], CellState::Filled(TetrominoType::I));
+        assert_eq!(bottom[1], CellState::Empty);
+        assert_eq!(bottom[2], CellState::Filled(TetrominoType::I));
+        assert_eq!(bottom[5], CellState::Empty);
+        assert_eq!(bottom[6], CellState::Empty);
+        assert_eq!(bottom[7], CellState::Garbage);
+        assert!(grid[GRID_HEIGHT - 2].iter().all(|cell| *cell == CellState::Empty));
+    }
+
+    #[test]
+    fn srs_rotates_around_center() {
+        let mut game = game_with("", RotationSystem::Srs);
+        game.current_piece = Tetromino::new_at(TetrominoType::T, 4, 5);
+
+        assert!(game.rotate_piece(true));
+
+        // Pointing right: the stem stays in the middle column of the 3x3 box
+        assert_eq!(game.current_piece.position, Position { x: 5, y: 5 });
+        assert_eq!(game.last_kick(), None);
+    }
+
+    #[test]
+    fn tspin_triple_with_srs() {
+        let mut game = game_with(TST_SETUP, RotationSystem::Srs);
+        slide_under_overhang(&mut game);
+        game.take_events();
+
+        assert!(game.rotate_piece(true));
+        assert_eq!(game.last_kick(), Some((-1, 2)));
+        game.hard_drop();
+
+        let events = game.take_events();
+        assert!(events.contains(&GameEvent::TSpin { lines: 3, mini: false }));
+        assert!(events.contains(&GameEvent::LinesCleared(3)));
+        assert_eq!(game.score, SCORE_TSPIN_TRIPLE);
+        assert_eq!(game.lines_cleared, 3);
+    }
+
+    #[test]
+    fn tspin_triple_slot_unreachable_with_classic_kicks() {
+        let mut game = game_with(TST_SETUP, RotationSystem::Classic);
+        slide_under_overhang(&mut game);
+
+        assert!(!game.rotate_piece(true));
+    }
+
+    #[test]
+    fn dropping_t_without_rotation_is_not_tspin() {
+        let board = "
+            JJJ.JJJJJJ
+        ";
+        let mut game = game_with(board, RotationSystem::Srs);
+        game.current_piece = Tetromino::new_at(TetrominoType::T, 2, 0);
+        game.current_piece.rotation = 2;
+        game.take_events();
+
+        game.hard_drop();
+
+        let events = game.take_events();
+        assert!(events.contains(&GameEvent::LinesCleared(1)));
+        assert!(!events.iter().any(|e| matches!(e, GameEvent::TSpin { .. })));
+        assert_eq!(game.score, SCORE_SINGLE);
+    }
+}
+
+// ============================================================================
+// Perfect Clear Tests
+// ============================================================================
+
+mod perfect_clear {
+    use super::*;
+
+    fn game_with(board: &str, pieces: Vec<TetrominoType>) -> Game {
+        let mut game = Game::with_provider(Box::new(SequencePieceProvider::new(pieces)));
+        game.grid = board_from_string(board).unwrap();
+        game
+    }
+
+    #[test]
+    fn o_piece_has_one_placement_per_column_pair_on_empty_board() {
+        let game = game_with("", vec![TetrominoType::O]);
+
+        let placements = game.legal_placements(TetrominoType::O);
+
+        // Identical rotations are counted once
+        assert_eq!(placements.len(), GRID_WIDTH - 1);
+        for placement in &placements {
+            assert!(game.is_valid_position(placement));
+            let lowest = placement.blocks().iter().map(|block| block.y).max().unwrap();
+            assert_eq!(lowest, GRID_HEIGHT as i16 - 1);
+        }
+    }
+
+    #[test]
+    fn placements_rest_on_the_stack_and_cover_every_shape() {
+        let game = game_with("IIII......", vec![TetrominoType::O]);
+
+        let placements = game.legal_placements(TetrominoType::T);
+
+        // Four rotations: two span three columns, two span two
+        assert_eq!(placements.len(), 8 + 8 + 9 + 9);
+        for placement in &placements {
+            let mut lower = placement.clone();
+            lower.position.y += 1;
+            assert!(game.is_valid_position(placement));
+            assert!(!game.is_valid_position(&lower), "{:?} is not resting", placement);
+        }
+    }
+
+    #[test]
+    fn one_piece_from_perfect_clear() {
+        // Current piece is the fifth drawn: I
+        let pieces = vec![
+            TetrominoType::S,
+            TetrominoType::Z,
+            TetrominoType::S,
+            TetrominoType::Z,
+            TetrominoType::I,
+        ];
+        let game = game_with("JJJ....LLL", pieces);
+        assert_eq!(game.current_piece.tetromino_type, TetrominoType::I);
+
+        assert!(game.is_perfect_clear_possible());
+    }
+
+    #[test]
+    fn perfect_clear_found_deeper_in_queue() {
+        let game = game_with("", vec![TetrominoType::O]);
+
+        // Five O pieces exactly fill two rows
+        assert!(game.is_perfect_clear_possible());
+    }
+
+    #[test]
+    fn impossible_cell_count_is_rejected() {
+        // One stray cell can never combine with 4-cell pieces into full rows
+        let game = game_with("....T.....", vec![TetrominoType::I]);
+
+        assert!(!game.is_perfect_clear_possible());
+    }
+}
+
+// ============================================================================
+// Hold Tests
+// ============================================================================
+
+mod hold {
+    use super::*;
+
+    /// Queue fills first, so the current piece is Z and the queue is I, O, T, S
+    fn game_with_sequence() -> Game {
+        let provider = Box::new(SequencePieceProvider::new(vec![
+            TetrominoType::I,
+            TetrominoType::O,
+            TetrominoType::T,
+            TetrominoType::S,
+            TetrominoType::Z,
+            TetrominoType::J,
+            TetrominoType::L,
+        ]));
+        Game::with_provider(provider)
+    }
+
+    #[test]
+    fn first_hold_takes_next_piece_from_queue() {
+        let mut game = game_with_sequence();
+        assert_eq!(game.current_piece.tetromino_type, TetrominoType::Z);
+
+        assert!(game.hold_piece());
+
+        assert_eq!(game.held_piece(), Some(TetrominoType::Z));
+        assert_eq!(game.current_piece.tetromino_type, TetrominoType::I);
+        assert_eq!(game.preview_queue.len(), PREVIEW_COUNT);
+    }
+
+    #[test]
+    fn hold_is_locked_out_until_next_piece_locks() {
+        let mut game = game_with_sequence();
+        assert!(game.can_hold);
+
+        assert!(game.hold_piece());
+        assert!(!game.can_hold);
+        assert!(!game.hold_piece(), "Second hold on the same piece should fail");
+        assert_eq!(game.current_piece.tetromino_type, TetrominoType::I);
+
+        game.hard_drop();
+        assert!(game.can_hold, "Locking a piece should re-enable hold");
+    }
+
+    #[test]
+    fn hold_swaps_with_held_piece() {
+        let mut game = game_with_sequence();
+        game.hold_piece();
+        game.hard_drop();
+        assert_eq!(game.current_piece.tetromino_type, TetrominoType::O);
+
+        assert!(game.hold_piece());
+
+        assert_eq!(game.held_piece(), Some(TetrominoType::O));
+        assert_eq!(game.current_piece.tetromino_type, TetrominoType::Z);
+        assert_eq!(game.current_piece.position, Tetromino::new(TetrominoType::Z).position);
+    }
+
+    #[test]
+    fn hold_emits_held_then_spawned_events() {
+        let mut game = game_with_sequence();
+        game.take_events();
+
+        game.hold_piece();
+
+        assert_eq!(
+            game.take_events(),
+            vec![
+                GameEvent::PieceHeld(TetrominoType::Z),
+                GameEvent::PieceSpawned(TetrominoType::I),
+            ]
+        );
+    }
+
+    #[test]
+    fn cannot_hold_while_paused() {
+        let mut game = game_with_sequence();
+        game.toggle_pause();
+
+        assert!(!game.hold_piece());
+        assert_eq!(game.held_piece(), None);
+    }
+
+    fn two_slot_game(hold_order: HoldOrder) -> Game {
+        let mut game = game_with_sequence();
+        game.config.hold_capacity = 2;
+        game.config.hold_order = hold_order;
+        game
+    }
+
+    /// Holds Z and O into the two slots, leaving S in play
+    fn fill_two_slots(game: &mut Game) {
+        assert!(game.hold_piece());
+        game.hard_drop();
+        assert_eq!(game.current_piece.tetromino_type, TetrominoType::O);
+        assert!(game.hold_piece());
+        // Not full yet, so the queue supplied the T
+        assert_eq!(game.current_piece.tetromino_type, TetrominoType::T);
+        game.hard_drop();
+        assert_eq!(game.current_piece.tetromino_type, TetrominoType::S);
+        assert_eq!(game.held_pieces, [TetrominoType::Z, TetrominoType::O]);
+    }
+
+    #[test]
+    fn full_lifo_hold_queue_swaps_back_the_latest_piece() {
+        let mut game = two_slot_game(HoldOrder::Lifo);
+        fill_two_slots(&mut game);
+        assert_eq!(game.held_piece(), Some(TetrominoType::O));
+
+        assert!(game.hold_piece());
+
+        assert_eq!(game.current_piece.tetromino_type, TetrominoType::O);
+        assert_eq!(game.held_pieces, [TetrominoType::Z, TetrominoType::S]);
+        game.assert_invariants();
+    }
+
+    #[test]
+    fn full_fifo_hold_queue_swaps_back_the_oldest_piece() {
+        let mut game = two_slot_game(HoldOrder::Fifo);
+        fill_two_slots(&mut game);
+        assert_eq!(game.held_piece(), Some(TetrominoType::Z));
+
+        assert!(game.hold_piece());
+
+        assert_eq!(game.current_piece.tetromino_type, TetrominoType::Z);
+        assert_eq!(game.held_pieces, [TetrominoType::O, TetrominoType::S]);
+        game.assert_invariants();
+    }
+
+    #[test]
+    fn hold_queue_still_allows_one_hold_per_piece() {
+        let mut game = two_slot_game(HoldOrder::Lifo);
+
+        assert!(game.hold_piece());
+        assert!(!game.hold_piece());
+
+        assert_eq!(game.held_pieces, [TetrominoType::Z]);
+    }
+
+    #[test]
+    fn zero_capacity_disables_hold() {
+        let mut game = game_with_sequence();
+        game.config.hold_capacity = 0;
+
+        assert!(!game.hold_piece());
+        assert!(game.held_pieces.is_empty());
+        assert_eq!(game.current_piece.tetromino_type, TetrominoType::Z);
+    }
+
+    #[test]
+    fn restart_empties_hold_slot() {
+        let mut game = game_with_sequence();
+        game.hold_piece();
+
+        game.restart();
+
+        assert_eq!(game.held_piece(), None);
+        assert!(game.can_hold);
+    }
+
+    #[test]
+    fn held_ghost_shows_where_the_held_piece_would_land() {
+        let mut game = game_with_sequence();
+        assert_eq!(game.held_ghost(), None, "nothing held yet");
+        game.hold_piece();
+        assert_eq!(game.held_ghost(), None, "hold already used for this piece");
+        game.hard_drop();
+
+        let ghost = game.held_ghost().unwrap();
+        assert_eq!(ghost.tetromino_type, TetrominoType::Z);
+        assert_eq!(ghost.position.x, Tetromino::new(TetrominoType::Z).position.x);
+        // Resting on the I that was just dropped
+        assert_eq!(ghost.bounding_box().3, GRID_HEIGHT as i16 - 2);
+
+        // Swapping it in and dropping lands exactly there
+        game.hold_piece();
+        assert_eq!(game.ghost_piece(), ghost);
+    }
+
+    #[test]
+    fn held_ghost_is_hidden_when_hold_would_take_from_the_queue() {
+        let mut game = game_with_sequence();
+        game.config.hold_capacity = 2;
+        game.hold_piece();
+        game.hard_drop();
+
+        assert_eq!(game.held_ghost(), None);
+        game.hold_piece();
+        game.hard_drop();
+        assert!(game.held_ghost().is_some());
+    }
+}
+
+// ============================================================================
+// Session Stats Tests
+// ============================================================================
+
+mod session_stats {
+    use super::*;
+
+    /// Bottom two rows filled except columns 0..4, each cleared by a flat I
+    fn game_with_two_i_slots() -> Game {
+        let mut grid = empty_grid();
+        for y in [GRID_HEIGHT - 2, GRID_HEIGHT - 1] {
+            for x in 4..GRID_WIDTH {
+                grid[y][x] = CellState::Filled(TetrominoType::T);
+            }
+        }
+        Game::with_grid(grid, Tetromino::new_at(TetrominoType::I, 0, 0))
+    }
+
+    #[test]
+    fn fresh_game_has_zeroed_stats() {
+        let game = Game::with_grid(empty_grid(), Tetromino::new(TetrominoType::O));
+        let stats = game.session_stats();
+
+        assert_eq!(stats.score, 0);
+        assert_eq!(stats.max_combo, 0);
+        assert_eq!(stats.pieces_placed.len(), 7);
+        assert!(stats.pieces_placed.values().all(|&count| count == 0));
+    }
+
+    #[test]
+    fn counts_pieces_placed_per_type() {
+        let mut game = Game::with_grid(empty_grid(), Tetromino::new(TetrominoType::O));
+        game.hard_drop();
+        game.current_piece = Tetromino::new(TetrominoType::O);
+        game.hard_drop();
+
+        let stats = game.session_stats();
+        assert_eq!(stats.pieces_placed[&TetrominoType::O], 2);
+        assert_eq!(stats.pieces_placed.values().sum::<u32>(), 2);
+    }
+
+    #[test]
+    fn consecutive_clears_build_max_combo() {
+        let mut game = game_with_two_i_slots();
+        game.hard_drop();
+        game.current_piece = Tetromino::new_at(TetrominoType::I, 0, 0);
+        game.hard_drop();
+        game.current_piece = Tetromino::new(TetrominoType::O);
+        game.hard_drop();
+
+        assert_eq!(game.session_stats().max_combo, 2);
+    }
+
+    #[test]
+    fn pieces_since_clear_counts_non_clearing_locks() {
+        let mut game = game_with_two_i_slots();
+        assert_eq!(game.pieces_since_clear(), 0);
+
+        for x in [6, 8, 6] {
+            game.current_piece = Tetromino::new_at(TetrominoType::O, x, 0);
+            game.hard_drop();
+        }
+        assert_eq!(game.lines_cleared, 0);
+        assert_eq!(game.pieces_since_clear(), 3);
+
+        game.current_piece = Tetromino::new_at(TetrominoType::I, 0, 0);
+        game.hard_drop();
+        assert_eq!(game.lines_cleared, 1);
+        assert_eq!(game.pieces_since_clear(), 0);
+
+        game.current_piece = Tetromino::new_at(TetrominoType::O, 6, 0);
+        game.hard_drop();
+        assert_eq!(game.pieces_since_clear(), 1);
+
+        game.score = 0;
+        game.restart();
+        assert_eq!(game.pieces_since_clear(), 0);
+    }
+
+    #[test]
+    fn tetris_is_counted() {
+        let mut grid = empty_grid();
+        for i in 0..4 {
+            fill_row_with_gap(&mut grid, GRID_HEIGHT - 1 - i, 0);
+        }
+        let mut piece = Tetromino::new_at(TetrominoType::I, 0, 0);
+        piece.rotation = 1;
+        let mut game = Game::with_grid(grid, piece);
+
+        game.hard_drop();
+
+        assert_eq!(game.session_stats().tetrises, 1);
+    }
+
+    #[test]
+    fn combo_and_back_to_back_follow_the_clears() {
+        let mut grid = empty_grid();
+        for i in 0..4 {
+            fill_row_with_gap(&mut grid, GRID_HEIGHT - 1 - i, 0);
+        }
+        let mut piece = Tetromino::new_at(TetrominoType::I, 0, 0);
+        piece.rotation = 1;
+        let mut game = Game::with_grid(grid, piece);
+        assert_eq!(game.combo(), -1);
+        assert!(!game.back_to_back());
+
+        game.hard_drop();
+        assert_eq!(game.lines_cleared, 4);
+        assert_eq!((game.combo(), game.back_to_back()), (0, true));
+        assert_eq!(game.consecutive_tetris(), 1);
+
+        // A lock that clears nothing breaks the combo but not back-to-back
+        game.current_piece = Tetromino::new_at(TetrominoType::O, 0, 0);
+        game.hard_drop();
+        assert_eq!((game.combo(), game.back_to_back()), (-1, true));
+
+        // A single, completed by an O landing on the first, ends
+        // back-to-back and starts a new combo
+        let row = GRID_HEIGHT - 3;
+        let cells: Vec<_> = (2..GRID_WIDTH).map(|x| (x, row, TetrominoType::T)).collect();
+        game.fill_cells(&cells);
+        game.current_piece = Tetromino::new_at(TetrominoType::O, 0, 0);
+        game.hard_drop();
+        assert_eq!(game.lines_cleared, 5);
+        assert_eq!((game.combo(), game.back_to_back()), (0, false));
+
+        game.score = 0;
+        game.restart();
+        assert_eq!((game.combo(), game.back_to_back()), (-1, false));
+    }
+
+    #[test]
+    fn restart_resets_stats() {
+        let mut game = game_with_two_i_slots();
+        game.hard_drop();
+        game.score = 0;
+
+        game.restart();
+
+        let stats = game.session_stats();
+        assert_eq!(stats.max_combo, 0);
+        assert!(stats.pieces_placed.values().all(|&count| count == 0));
+    }
+
+    #[test]
+    fn exported_json_round_trips() {
+        let mut game = game_with_two_i_slots();
+        game.hard_drop();
+        game.tick_with_delta(1234);
+        let path = std::env::temp_dir().join(format!("tetris-stats-{}.json", std::process::id()));
+
+        game.export_stats(&path).unwrap();
+        let loaded = SessionStats::load(&path).unwrap();
+        std::fs::remove_file(&path).unwrap();
+
+        assert_eq!(loaded, game.session_stats());
+        assert_eq!(loaded.duration_ms, 1234);
+        assert_eq!(loaded.pieces_placed[&TetrominoType::I], 1);
+    }
+}
+
+// ============================================================================
+// Entry Delay Tests
+// ============================================================================
+
+mod entry_delay {
+    use super::*;
+
+    const ARE_MS: u64 = 300;
+
+    /// O piece resting over the only gap in an otherwise full bottom row
+    fn game_about_to_clear() -> Game {
+        let config = GameConfig {
+            are_ms: ARE_MS,
+            ..GameConfig::default()
+        };
+        let provider = Box::new(SequencePieceProvider::new(vec![TetrominoType::O]));
+        let mut game = Game::with_config(config, provider);
+        for x in 0..GRID_WIDTH {
+            if x != 4 && x != 5 {
+                game.grid[GRID_HEIGHT - 1][x] = CellState::Filled(TetrominoType::T);
+            }
+        }
+        game.current_piece = Tetromino::new_at(TetrominoType::O, 4, 0);
+        game
+    }
+
+    #[test]
+    fn input_is_ignored_until_delay_passes() {
+        let mut game = game_about_to_clear();
+        game.hard_drop();
+        assert_eq!(game.are_remaining_ms(), ARE_MS);
+
+        let spawn = game.current_piece.clone();
+        assert!(!game.move_piece(-1, 0));
+        assert!(!game.rotate_piece(true));
+        game.hard_drop();
+        assert_eq!(game.current_piece, spawn, "Input during ARE should do nothing");
+
+        game.tick_with_delta(ARE_MS);
+
+        assert_eq!(game.are_remaining_ms(), 0);
+        assert!(game.move_piece(-1, 0));
+    }
+
+    #[test]
+    fn gravity_waits_for_delay() {
+        let mut game = game_about_to_clear();
+        game.hard_drop();
+        let spawn_y = game.current_piece.position.y;
+
+        game.tick_with_delta(ARE_MS + game.tick_duration_ms() - 1);
+        assert_eq!(game.current_piece.position.y, spawn_y);
+
+        game.tick_with_delta(1);
+        assert_eq!(game.current_piece.position.y, spawn_y + 1);
+    }
+
+    #[test]
+    fn no_delay_without_a_clear() {
+        let mut game = game_about_to_clear();
+        game.current_piece = Tetromino::new_at(TetrominoType::O, 0, 0);
+
+        game.hard_drop();
+
+        assert_eq!(game.are_remaining_ms(), 0);
+        assert!(game.move_piece(1, 0));
+    }
+
+    #[test]
+    fn delay_is_off_by_default() {
+        let mut grid = empty_grid();
+        fill_row_with_gap(&mut grid, GRID_HEIGHT - 1, 0);
+        let mut piece = Tetromino::new_at(TetrominoType::I, 0, 0);
+        piece.rotation = 1;
+        let mut game = Game::with_grid(grid, piece);
+
+        game.hard_drop();
+
+        assert_eq!(game.are_remaining_ms(), 0);
+    }
+
+    const SPAWN_DELAY_MS: u64 = 100;
+
+    fn game_with_spawn_delay() -> Game {
+        let mut game = game_about_to_clear();
+        game.config.spawn_delay_ms = SPAWN_DELAY_MS;
+        game
+    }
+
+    #[test]
+    fn spawn_delay_ignores_input_after_any_lock() {
+        let mut game = game_with_spawn_delay();
+        game.current_piece = Tetromino::new_at(TetrominoType::O, 0, 0);
+        game.hard_drop();
+        assert_eq!(game.lines_cleared, 0);
+        assert_eq!(game.are_remaining_ms(), SPAWN_DELAY_MS);
+
+        let spawn = game.current_piece.clone();
+        assert!(!game.move_piece(1, 0));
+        game.hard_drop();
+        assert_eq!(game.current_piece, spawn, "Input during the spawn delay should do nothing");
+
+        game.tick_with_delta(SPAWN_DELAY_MS);
+        assert!(game.move_piece(1, 0));
+    }
+
+    #[test]
+    fn spawn_delay_adds_to_the_line_clear_delay() {
+        let mut game = game_with_spawn_delay();
+        game.hard_drop();
+        assert_eq!(game.lines_cleared, 1);
+
+        assert_eq!(game.are_remaining_ms(), SPAWN_DELAY_MS + ARE_MS);
+    }
+}
+
+// ============================================================================
+// Game Mode Tests
+// ============================================================================
+
+mod game_mode {
+    use super::*;
+
+    fn game_in_mode(mode: GameMode) -> Game {
+        let config = GameConfig {
+            mode,
+            ..GameConfig::default()
+        };
+        Game::with_config(config, Box::new(SequencePieceProvider::new(vec![TetrominoType::O])))
+    }
+
+    /// Fills the bottom row except under an O dropped at x = 4
+    fn drop_o_to_clear_one(game: &mut Game) {
+        for x in 0..GRID_WIDTH {
+            game.grid[GRID_HEIGHT - 1][x] = if x == 4 || x == 5 {
+                CellState::Empty
+            } else {
+                CellState::Filled(TetrominoType::T)
+            };
+        }
+        game.current_piece = Tetromino::new_at(TetrominoType::O, 4, 0);
+        game.hard_drop();
+    }
+
+    #[test]
+    fn marathon_has_no_progress() {
+        let game = game_in_mode(GameMode::Marathon);
+        assert_eq!(game.mode_progress(), None);
+    }
+
+    #[test]
+    fn sprint_counts_down_lines() {
+        let mut game = game_in_mode(GameMode::Sprint { lines: 40 });
+        assert_eq!(game.mode_progress(), Some(ModeProgress::LinesRemaining(40)));
+
+        drop_o_to_clear_one(&mut game);
+        drop_o_to_clear_one(&mut game);
+        drop_o_to_clear_one(&mut game);
+
+        assert_eq!(game.lines_cleared, 3);
+        assert_eq!(game.mode_progress(), Some(ModeProgress::LinesRemaining(37)));
+    }
+
+    #[test]
+    fn sprint_ends_at_goal() {
+        let path = temp_high_score_file("sprint");
+        let mut game = game_in_mode(GameMode::Sprint { lines: 2 }).with_high_score_file(&path);
+
+        drop_o_to_clear_one(&mut game);
+        assert_eq!(game.state, GameState::Playing);
+        drop_o_to_clear_one(&mut game);
+
+        assert_eq!(game.state, GameState::GameOver);
+        assert_eq!(game.mode_progress(), Some(ModeProgress::LinesRemaining(0)));
+        assert!(game.take_events().contains(&GameEvent::GameOver));
+        let _ = std::fs::remove_file(&path);
+    }
+
+    #[test]
+    fn ultra_counts_down_time_and_ends() {
+        let mut game = game_in_mode(GameMode::Ultra { duration_ms: 120_000 });
+
+        game.tick_with_delta(30_000);
+        assert_eq!(game.mode_progress(), Some(ModeProgress::TimeRemainingMs(90_000)));
+
+        game.tick_with_delta(100_000);
+        assert_eq!(game.state, GameState::GameOver);
+        assert_eq!(game.elapsed_ms(), 120_000);
+        assert_eq!(game.mode_progress(), Some(ModeProgress::TimeRemainingMs(0)));
+    }
+}
+
+// ============================================================================
+// Custom Piece Tests
+// ============================================================================
+
+mod custom_pieces {
+    use super::*;
+
+    /// Five-cell plus sign; every rotation looks the same
+    fn plus_pentomino() -> PieceDefinition {
+        PieceDefinition {
+            name: "X".to_string(),
+            rotations: vec![vec![(1, 0), (0, 1), (1, 1), (2, 1), (1, 2)]],
+            color: (200, 200, 200),
+        }
+    }
+
+    #[test]
+    fn registered_pentomino_drops_to_floor() {
+        let plus = register_piece(plus_pentomino()).unwrap();
+        let provider = Box::new(SequencePieceProvider::new(vec![plus]));
+        let mut game = Game::with_provider(provider);
+        assert_eq!(game.current_piece.tetromino_type, plus);
+        assert_eq!(game.current_piece.blocks().len(), 5);
+
+        game.hard_drop();
+
+        let x = (GRID_WIDTH / 2 - 1) + 1;
+        assert_eq!(game.grid[GRID_HEIGHT - 3][x], CellState::Filled(plus));
+        assert_eq!(game.grid[GRID_HEIGHT - 2][x - 1], CellState::Filled(plus));
+        assert_eq!(game.grid[GRID_HEIGHT - 2][x + 1], CellState::Filled(plus));
+        assert_eq!(game.grid[GRID_HEIGHT - 1][x], CellState::Filled(plus));
+        assert_eq!(game.total_filled_cells(), 5);
+        assert_eq!(game.session_stats().pieces_placed[&plus], 1);
+    }
+
+    #[test]
+    fn rotation_states_are_normalized() {
+        let mut bar = PieceDefinition {
+            name: "I5".to_string(),
+            rotations: vec![
+                vec![(0, 2), (1, 2), (2, 2), (3, 2), (4, 2)],
+                vec![(2, 0), (2, 1), (2, 2), (2, 3), (2, 4)],
+            ],
+            color: (0, 128, 255),
+        };
+        let bar_type = register_piece(bar.clone()).unwrap();
+
+        bar.rotations = vec![
+            vec![(0, 0), (1, 0), (2, 0), (3, 0), (4, 0)],
+            vec![(0, 0), (0, 1), (0, 2), (0, 3), (0, 4)],
+        ];
+        assert_eq!(bar_type.shapes(), bar.rotations);
+    }
+
+    #[test]
+    fn custom_piece_rotates_through_its_states() {
+        let l_tromino = register_piece(PieceDefinition {
+            name: "L3".to_string(),
+            rotations: vec![
+                vec![(0, 0), (0, 1), (1, 1)],
+                vec![(0, 0), (1, 0), (0, 1)],
+                vec![(0, 0), (1, 0), (1, 1)],
+                vec![(1, 0), (0, 1), (1, 1)],
+            ],
+            color: (255, 0, 255),
+        })
+        .unwrap();
+        let piece = Tetromino::new_at(l_tromino, 4, 5);
+        let mut game = Game::with_grid(empty_grid(), piece);
+
+        assert!(game.rotate_piece(true));
+
+        assert_eq!(game.current_piece.rotation, 1);
+        assert_eq!(game.current_piece.blocks().len(), 3);
+    }
+
+    #[test]
+    fn rejects_pieces_without_cells() {
+        let mut empty = plus_pentomino();
+        empty.rotations.clear();
+        assert_eq!(register_piece(empty), Err(PieceError::NoRotations));
+
+        let mut hollow = plus_pentomino();
+        hollow.rotations.push(Vec::new());
+        assert_eq!(register_piece(hollow), Err(PieceError::EmptyRotation(1)));
+    }
+}
+
+// ============================================================================
+// Finesse Tests
+// ============================================================================
+
+mod finesse {
+    use super::*;
+
+    const SPAWN_COLUMN: i16 = GRID_WIDTH as i16 / 2 - 1;
+
+    fn from_spawn(
+        system: RotationSystem,
+        piece: TetrominoType,
+        column: i16,
+        rotation: usize,
+    ) -> u32 {
+        let start = Placement {
+            column: SPAWN_COLUMN,
+            rotation: 0,
+        };
+        optimal_inputs(system, piece, start, Placement { column, rotation })
+    }
+
+    #[test]
+    fn straight_slide_costs_one_tap_per_column() {
+        assert_eq!(from_spawn(RotationSystem::Classic, TetrominoType::T, 0, 0), 4);
+        assert_eq!(from_spawn(RotationSystem::Classic, TetrominoType::T, 7, 0), 3);
+        assert_eq!(from_spawn(RotationSystem::Classic, TetrominoType::T, SPAWN_COLUMN, 0), 0);
+    }
+
+    #[test]
+    fn rotation_takes_shorter_direction() {
+        assert_eq!(from_spawn(RotationSystem::Classic, TetrominoType::T, SPAWN_COLUMN, 1), 1);
+        assert_eq!(from_spawn(RotationSystem::Classic, TetrominoType::T, SPAWN_COLUMN, 2), 2);
+        assert_eq!(from_spawn(RotationSystem::Classic, TetrominoType::T, SPAWN_COLUMN, 3), 1);
+        assert_eq!(from_spawn(RotationSystem::Classic, TetrominoType::J, 0, 3), 5);
+    }
+
+    #[test]
+    fn identical_rotation_states_are_interchangeable() {
+        // A flat I in state 2 looks the same as state 0
+        assert_eq!(from_spawn(RotationSystem::Classic, TetrominoType::I, 0, 2), 4);
+        // Every O state is the same shape
+        assert_eq!(from_spawn(RotationSystem::Classic, TetrominoType::O, 8, 3), 4);
+        // A vertical S can finish in state 1 or 3, whichever is closer
+        assert_eq!(from_spawn(RotationSystem::Classic, TetrominoType::S, 0, 1), 5);
+    }
+
+    #[test]
+    fn srs_rotation_shift_is_accounted_for() {
+        // Rotating an I clockwise under SRS moves it two columns right
+        assert_eq!(from_spawn(RotationSystem::Srs, TetrominoType::I, SPAWN_COLUMN + 2, 1), 1);
+        assert_eq!(from_spawn(RotationSystem::Classic, TetrominoType::I, SPAWN_COLUMN + 2, 1), 3);
+    }
+
+    #[test]
+    fn lock_reports_actual_against_optimal() {
+        let provider = Box::new(SequencePieceProvider::new(vec![TetrominoType::T]));
+        let mut game = Game::with_provider(provider);
+        game.move_piece(-1, 0);
+        game.move_piece(-1, 0);
+        game.move_piece(1, 0);
+        game.take_events();
+
+        game.hard_drop();
+
+        let events = game.take_events();
+        assert!(events.contains(&GameEvent::Finesse {
+            optimal: 1,
+            actual: 3
+        }));
+    }
+
+    #[test]
+    fn gravity_and_soft_drop_are_not_inputs() {
+        let provider = Box::new(SequencePieceProvider::new(vec![TetrominoType::T]));
+        let mut game = Game::with_provider(provider);
+        game.rotate_piece(true);
+        game.soft_drop();
+        game.tick();
+        game.take_events();
+
+        game.hard_drop();
+
+        assert!(game.take_events().contains(&GameEvent::Finesse {
+            optimal: 1,
+            actual: 1
+        }));
+    }
+}
+
+// ============================================================================
+// Board Metric Tests
+// ============================================================================
+
+mod board_metrics {
+    use super::*;
+
+    fn game_with_board(board: &str) -> Game {
+        let grid = board_from_string(board).unwrap();
+        Game::with_grid(grid, Tetromino::new(TetrominoType::O))
+    }
+
+    #[test]
+    fn column_heights_measure_from_floor() {
+        let game = game_with_board(
+            "
+            ..........
+            L.........
+            L..T......
+            L.TTT....Z
+            ",
+        );
+
+        assert_eq!(game.column_heights(), [3, 0, 1, 2, 1, 0, 0, 0, 0, 1]);
+    }
+
+    #[test]
+    fn bumpiness_sums_neighbor_height_differences() {
+        let game = game_with_board(
+            "
+            ..........
+            L.........
+            L..T......
+            L.TTT....Z
+            ",
+        );
+
+        // Heights [3, 0, 1, 2, 1, 0, 0, 0, 0, 1]: 3 + 1 + 1 + 1 + 1 + 0 + 0 + 0 + 1
+        assert_eq!(game.bumpiness(), 8);
+    }
+
+    #[test]
+    fn transitions_count_walls_and_floor_as_filled() {
+        let game = game_with_board(
+            "
+            T.........
+            .T.......T
+            ",
+        );
+
+        // 18 empty rows at 2 each, then 2 and 4 for the two drawn rows
+        assert_eq!(game.row_transitions(), 18 * 2 + 2 + 4);
+        // Column 0 has a hole under its block (3), the other nine end at the floor (1 each)
+        assert_eq!(game.column_transitions(), 3 + 9);
+    }
+
+    #[test]
+    fn empty_board_transitions() {
+        let game = Game::with_grid(empty_grid(), Tetromino::new_at(TetrominoType::O, 4, 10));
+
+        assert_eq!(game.row_transitions(), GRID_HEIGHT * 2);
+        assert_eq!(game.column_transitions(), GRID_WIDTH);
+    }
+
+    #[test]
+    fn bumpiness_ignores_the_current_piece() {
+        let grid = board_from_string("IIII......").unwrap();
+        let piece = Tetromino::new_at(TetrominoType::O, 6, GRID_HEIGHT as i16 - 2);
+        let mut game = Game::with_grid(grid, piece);
+
+        assert_eq!(game.bumpiness(), 1);
+        game.grid = empty_grid();
+        assert_eq!(game.bumpiness(), 0);
+    }
+
+    #[test]
+    fn empty_board_has_no_well() {
+        let game = Game::with_grid(empty_grid(), Tetromino::new(TetrominoType::O));
+
+        assert_eq!(game.column_heights(), [0; GRID_WIDTH]);
+        assert_eq!(game.deepest_well(), (0, 0));
+    }
+
+    #[test]
+    fn finds_single_column_well() {
+        let game = game_with_board(
+            "
+            JJJJ.JJJJJ
+            JJJJ.JJJJJ
+            JJJJ.JJJJJ
+            JJJJ.JJJJJ
+            ",
+        );
+
+        assert_eq!(game.deepest_well(), (4, 4));
+    }
+
+    #[test]
+    fn well_depth_uses_lower_neighbor() {
+        let game = game_with_board(
+            "
+            ...S......
+            ...S......
+            ...S.Z....
+            ...S.Z....
+            ",
+        );
+
+        assert_eq!(game.deepest_well(), (4, 2));
+    }
+
+    #[test]
+    fn edge_column_uses_single_neighbor() {
+        let game = game_with_board(
+            "
+            .I........
+            .I........
+            .I........
+            ",
+        );
+
+        assert_eq!(game.deepest_well(), (0, 3));
+    }
+}
+
+// ============================================================================
+// Replay Tests
+// ============================================================================
+
+mod replay {
+    use super::*;
+
+    /// A few pieces' worth of play spread over 400 frames
+    fn recorded_replay() -> Replay {
+        let mut replay = Replay::new(42, GameConfig::default());
+        let script = [
+            (5, Input::MoveLeft),
+            (6, Input::MoveLeft),
+            (20, Input::RotateCw),
+            (40, Input::HardDrop),
+            (60, Input::MoveRight),
+            (60, Input::MoveRight),
+            (61, Input::MoveRight),
+            (90, Input::Hold),
+            (120, Input::RotateCcw),
+            (150, Input::SoftDrop),
+            (180, Input::HardDrop),
+            (250, Input::MoveLeft),
+            (399, Input::HardDrop),
+        ];
+        for (frame, input) in script {
+            replay.record(frame, input);
+        }
+        replay
+    }
+
+    fn assert_same_state(a: &Game, b: &Game) {
+        assert_eq!(a.grid, b.grid);
+        assert_eq!(a.current_piece, b.current_piece);
+        assert_eq!(a.preview_queue, b.preview_queue);
+        assert_eq!(a.held_pieces, b.held_pieces);
+        assert_eq!(a.score, b.score);
+        assert_eq!(a.lines_cleared, b.lines_cleared);
+        assert_eq!(a.state, b.state);
+        assert_eq!(a.elapsed_ms(), b.elapsed_ms());
+        assert_eq!(a.time_until_tick_ms(), b.time_until_tick_ms());
+    }
+
+    #[test]
+    fn seeded_provider_is_deterministic() {
+        let mut a = SeededPieceProvider::new(7);
+        let mut b = SeededPieceProvider::new(7);
+
+        let first: Vec<_> = (0..20).map(|_| a.next_piece()).collect();
+        let second: Vec<_> = (0..20).map(|_| b.next_piece()).collect();
+
+        assert_eq!(first, second);
+    }
+
+    #[test]
+    fn seek_matches_stepping_to_midpoint() {
+        let replay = recorded_replay();
+        let midpoint = replay.frame_count() / 2;
+
+        let mut stepped = replay.new_game();
+        for frame in 0..midpoint {
+            replay.step(&mut stepped, frame);
+        }
+        let sought = replay.seek(midpoint);
+
+        assert_same_state(&sought, &stepped);
+        assert_eq!(sought.elapsed_ms(), midpoint * FRAME_MS);
+        assert!(sought.held_piece().is_some());
+    }
+
+    #[test]
+    fn seeking_is_repeatable() {
+        let replay = recorded_replay();
+
+        let first = replay.seek(replay.frame_count());
+        let second = replay.seek(replay.frame_count());
+
+        assert_same_state(&first, &second);
+        assert!(first.total_filled_cells() > 0);
+    }
+
+    #[test]
+    fn seek_to_zero_is_the_starting_game() {
+        let replay = recorded_replay();
+
+        assert_same_state(&replay.seek(0), &replay.new_game());
+    }
+
+    #[test]
+    fn frame_applies_its_inputs_before_time_passes() {
+        let mut replay = Replay::new(1, GameConfig::default());
+        replay.record(0, Input::MoveLeft);
+        replay.record(0, Input::MoveLeft);
+        let start_x = replay.new_game().current_piece.position.x;
+
+        let game = replay.seek(1);
+
+        assert_eq!(game.current_piece.position.x, start_x - 2);
+        assert_eq!(game.elapsed_ms(), FRAME_MS);
+    }
+
+    #[test]
+    fn playback_slowly_plays_the_tail_into_the_final_state() {
+        let replay = recorded_replay();
+        let end = replay.frame_count();
+        let mut playback = Playback::tail(replay.clone(), end, 100 * FRAME_MS, 2);
+        assert_eq!(playback.frame(), end - 100);
+        assert_same_state(playback.game(), &replay.seek(end - 100));
+
+        // At half speed one live frame of real time is only half a frame
+        playback.advance(FRAME_MS);
+        assert_eq!(playback.frame(), end - 100);
+        assert_eq!(playback.time_until_frame_ms(), FRAME_MS);
+        playback.advance(FRAME_MS);
+        assert_eq!(playback.frame(), end - 99);
+
+        playback.advance(1000 * FRAME_MS);
+        assert!(playback.is_finished());
+        assert_same_state(playback.game(), &replay.seek(end));
+    }
+
+    #[test]
+    fn restarting_on_a_seeded_provider_matches_a_new_replay_game() {
+        let mut game = Game::new();
+        game.hard_drop();
+        game.score = 0;
+
+        game.set_piece_provider(Box::new(SeededPieceProvider::new(42)));
+        game.restart();
+
+        assert_same_state(&game, &Replay::new(42, GameConfig::default()).new_game());
+    }
+
+    #[test]
+    fn share_code_round_trips_seed_config_and_inputs() {
+        let mut replay = recorded_replay();
+        replay.config.mode = GameMode::Sprint { lines: 40 };
+        replay.config.cheese = Some(CheeseConfig { rows: 4, seed: 9 });
+
+        let code = replay.to_share_code();
+
+        assert!(code.chars().all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_'));
+        let decoded = Replay::from_share_code(&code).unwrap();
+        assert_eq!(decoded, replay);
+        assert_same_state(&decoded.seek(400), &replay.seek(400));
+    }
+
+    #[test]
+    fn malformed_share_codes_are_rejected() {
+        let code = recorded_replay().to_share_code();
+
+        assert_eq!(Replay::from_share_code("not a code!"), Err(ShareCodeError::NotBase64));
+        assert_eq!(Replay::from_share_code(""), Err(ShareCodeError::Corrupt));
+        // Cut off partway through the compressed replay
+        assert_eq!(Replay::from_share_code(&code[..16]), Err(ShareCodeError::Corrupt));
+
+        // Same payload under a future version byte
+        let mut bytes = URL_SAFE_NO_PAD.decode(&code).unwrap();
+        bytes[0] = SHARE_CODE_VERSION + 1;
+        let future = URL_SAFE_NO_PAD.encode(bytes);
+        assert_eq!(
+            Replay::from_share_code(&future),
+            Err(ShareCodeError::UnsupportedVersion(SHARE_CODE_VERSION + 1))
+        );
+    }
+
+    #[test]
+    fn sealed_replay_verifies_and_tampered_one_does_not() {
+        let mut replay = recorded_replay();
+        replay.seal();
+        assert!(replay.verify());
+        let shared = Replay::from_share_code(&replay.to_share_code()).unwrap();
+        assert!(shared.verify());
+
+        let mut tampered = replay.clone();
+        tampered.record(400, Input::HardDrop);
+
+        assert_eq!(tampered.checksum(), replay.checksum());
+        assert!(!tampered.verify());
+    }
+
+    #[test]
+    fn unsealed_replay_never_verifies() {
+        let replay = recorded_replay();
+
+        assert_eq!(replay.checksum(), None);
+        assert!(!replay.verify());
+    }
+
+    #[test]
+    fn seeking_to_game_over_leaves_the_local_high_score_alone() {
+        let before = std::fs::read_to_string("highscore.txt").ok();
+        let config = GameConfig {
+            soft_drop_scoring: true,
+            ..GameConfig::default()
+        };
+        // Score a point per piece and stack them up until the run tops out
+        let mut replay = Replay::new(42, config);
+        for piece in 0..100 {
+            replay.record(piece * 2, Input::SoftDrop);
+            replay.record(piece * 2 + 1, Input::HardDrop);
+        }
+
+        let game = replay.seek(replay.frame_count());
+
+        assert_eq!(game.state, GameState::GameOver);
+        assert!(game.score > 0);
+        assert_eq!(game.high_score, game.score);
+        assert_eq!(std::fs::read_to_string("highscore.txt").ok(), before);
+    }
+
+    #[test]
+    fn state_checksum_tracks_board_and_score() {
+        let game = Game::with_grid(empty_grid(), Tetromino::new(TetrominoType::O));
+        let mut scored = Game::with_grid(empty_grid(), Tetromino::new(TetrominoType::O));
+        assert_eq!(state_checksum(&game), state_checksum(&scored));
+
+        scored.score = 100;
+        assert_ne!(state_checksum(&game), state_checksum(&scored));
+
+        let mut filled = Game::with_grid(empty_grid(), Tetromino::new(TetrominoType::O));
+        filled.grid[GRID_HEIGHT - 1][0] = CellState::Garbage;
+        assert_ne!(state_checksum(&game), state_checksum(&filled));
+    }
+}
+
+// ============================================================================
+// Invariant Tests
+// ============================================================================
+
+mod invariants {
+    use super::*;
+    use rand::{rngs::StdRng, Rng, SeedableRng};
+
+    const INPUTS: [Input; 7] = [
+        Input::MoveLeft,
+        Input::MoveRight,
+        Input::SoftDrop,
+        Input::HardDrop,
+        Input::RotateCw,
+        Input::RotateCcw,
+        Input::Hold,
+    ];
+
+    #[test]
+    fn fresh_game_is_consistent() {
+        Game::with_provider(Box::new(SeededPieceProvider::new(0))).assert_invariants();
+    }
 
-        // Soft drop all the way down
-        for _ in 0..30 {
-            game.soft_drop();
-            if game.current_piece.position.y == 0 {
-                // New piece spawned
-                break;
-            }
-        }
+    #[test]
+    fn long_runs_do_not_overflow_the_score_check() {
+        let mut game = Game::with_provider(Box::new(SeededPieceProvider::new(0)));
+        game.lines_cleared = u32::MAX / 10;
+        game.level = game.lines_cleared / LINES_PER_LEVEL + 1;
+        game.score = u32::MAX;
 
-        // Should have locked and spawned new piece
-        assert!(game.total_filled_cells() > 0);
+        game.assert_invariants();
     }
 
     #[test]
-    fn game_state_consistent_after_many_operations() {
-        let pieces: Vec<TetrominoType> = vec![
-            TetrominoType::T,
-            TetrominoType::S,
-            TetrominoType::Z,
-            TetrominoType::L,
-            TetrominoType::J,
-            TetrominoType::I,
-            TetrominoType::O,
-        ];
-        let provider = Box::new(SequencePieceProvider::new(pieces));
-        let mut game = Game::with_provider(provider);
-
-        // Simulate some gameplay
-        for _ in 0..10 {
-            game.move_piece(-1, 0);
-            game.move_piece(1, 0);
-            game.rotate_piece(true);
-            game.hard_drop();
+    fn invariants_hold_through_random_play() {
+        let path = temp_high_score_file("invariants");
+        let mut rng = StdRng::seed_from_u64(2024);
+        let config = GameConfig {
+            rotation_system: RotationSystem::Srs,
+            ..GameConfig::default()
+        };
+        let mut game = Game::with_config(config, Box::new(SeededPieceProvider::new(99)))
+            .with_high_score_file(&path);
+
+        for _ in 0..5000 {
+            match rng.gen_range(0..20) {
+                0 => game.tick_with_delta(rng.gen_range(0..1000)),
+                1 => game.toggle_pause(),
+                2 => game.add_garbage_lines(&[rng.gen_range(0..GRID_WIDTH)]),
+                _ => game.apply_input(INPUTS[rng.gen_range(0..INPUTS.len())]),
+            }
+            game.assert_invariants();
 
             if game.is_game_over() {
-                break;
+                game.restart();
+                game.assert_invariants();
             }
         }
 
-        // Verify render_grid is valid
-        let visual = game.render_grid();
-        assert_eq!(visual.len(), GRID_HEIGHT);
-        assert_eq!(visual[0].len(), GRID_WIDTH);
-
-        // Every cell should be a valid CellState
-        for row in &visual {
-            for cell in row {
-                match cell {
-                    CellState::Empty => {}
-                    CellState::Filled(_) => {}
-                }
-            }
-        }
+        let _ = std::fs::remove_file(&path);
     }
 }
 
 // ============================================================================
-// Edge Cases
+// Theme Tests
 // ============================================================================
 
-mod edge_cases {
+mod themes {
     use super::*;
 
     #[test]
-    fn i_piece_at_top_row() {
-        // I piece horizontal at y=0 should be valid
-        let piece = Tetromino::new_at(TetrominoType::I, 0, 0);
-        let game = Game::with_grid(empty_grid(), piece);
+    fn cycling_visits_every_theme_and_wraps() {
+        let mut index = 0;
+        let mut seen = vec![THEMES[index].name];
+        for _ in 1..THEMES.len() {
+            index = next_theme_index(index);
+            seen.push(THEMES[index].name);
+        }
 
-        assert!(game.is_valid_position(&game.current_piece));
+        seen.sort();
+        seen.dedup();
+        assert_eq!(seen.len(), THEMES.len());
+        assert_eq!(next_theme_index(index), 0);
     }
 
     #[test]
-    fn piece_at_exact_boundaries() {
-        // Test pieces at exact grid boundaries
-        let test_cases = vec![
-            (TetrominoType::O, 0, 0),                                         // Top-left
-            (TetrominoType::O, GRID_WIDTH as i16 - 2, 0),                     // Top-right
-            (TetrominoType::O, 0, GRID_HEIGHT as i16 - 2),                    // Bottom-left
-            (TetrominoType::O, GRID_WIDTH as i16 - 2, GRID_HEIGHT as i16 - 2), // Bottom-right
-        ];
+    fn a_piece_color_overrides_the_theme() {
+        let theme = THEMES[0];
 
-        for (piece_type, x, y) in test_cases {
-            let piece = Tetromino::new_at(piece_type, x, y);
-            let game = Game::with_grid(empty_grid(), piece);
-            assert!(
-                game.is_valid_position(&game.current_piece),
-                "Piece at ({}, {}) should be valid",
-                x,
-                y
-            );
+        assert_eq!(theme.cell_color(TetrominoType::T, None), theme.piece_color(TetrominoType::T));
+        assert_eq!(theme.cell_color(TetrominoType::T, Some((10, 20, 30))), Color::Rgb(10, 20, 30));
+        assert_eq!(
+            theme.cell_highlight_color(TetrominoType::T, Some((10, 20, 30))),
+            Color::Rgb(133, 138, 143)
+        );
+    }
+
+    #[test]
+    fn themes_are_found_by_name() {
+        for (index, theme) in THEMES.iter().enumerate() {
+            assert_eq!(theme_index(theme.name), Some(index));
         }
+        assert_eq!(theme_index("no-such-theme"), None);
     }
 
     #[test]
-    fn clear_top_row() {
-        let mut grid = empty_grid();
-        fill_row(&mut grid, 0); // Fill top row
+    fn original_themes_keep_the_terminal_background_and_borders() {
+        for name in ["classic", "pastel", "monochrome"] {
+            let theme = THEMES[theme_index(name).unwrap()];
+            assert_eq!(theme.background, Color::Reset);
+            assert_eq!(theme.border, Color::Reset);
+            assert_eq!(theme.title, Color::Reset);
+        }
+    }
 
-        let piece = Tetromino::new_at(TetrominoType::O, 4, 10);
-        let mut game = Game::with_grid(grid, piece);
+    #[test]
+    fn dark_and_light_themes_are_selectable_and_color_the_board() {
+        assert_eq!(THEMES[theme_index("dark").unwrap()], Theme::dark());
+        assert_eq!(THEMES[theme_index("light").unwrap()], Theme::light());
+
+        for theme in [Theme::dark(), Theme::light()] {
+            assert_ne!(theme.background, Color::Reset);
+            // Pieces must stand out from the board they're drawn on
+            assert!(theme.pieces.iter().all(|&color| color != theme.background));
+        }
+    }
 
-        let cleared = game.clear_lines();
+    #[test]
+    fn default_settings_use_the_first_theme() {
+        assert_eq!(theme_index(&Settings::default().theme), Some(0));
+    }
 
-        assert_eq!(cleared, 1);
-        assert_eq!(game.filled_count_in_row(0), 0);
+    #[test]
+    fn settings_round_trip_through_a_file() {
+        let path = std::env::temp_dir().join(format!("tetris-settings-{}.json", std::process::id()));
+        let settings = Settings {
+            theme: THEMES[THEMES.len() - 1].name.to_string(),
+            ghost_style: GhostStyle::Outline,
+            cell_shape: CellShape::HalfBlock,
+            bell: true,
+            hide_preview: true,
+            preview_shown: 2,
+            controls: KeyPreset::LeftHand,
+            mirror_controls: true,
+            auto_pause: false,
+            settle_animation: false,
+            held_ghost: true,
+            intro: false,
+            invisible_blocks: true,
+            reveal_on_clear: false,
+        };
+
+        settings.save(&path).unwrap();
+        let loaded = Settings::load(&path);
+        std::fs::remove_file(&path).unwrap();
+
+        assert_eq!(loaded, settings);
     }
 
     #[test]
-    fn all_rows_filled_and_cleared() {
-        let mut grid = empty_grid();
-        for y in 0..GRID_HEIGHT {
-            fill_row(&mut grid, y);
-        }
+    fn ghost_style_defaults_to_dimmed_when_not_in_the_file() {
+        let path = std::env::temp_dir().join(format!("tetris-ghost-{}.json", std::process::id()));
+        std::fs::write(&path, r#"{ "theme": "pastel" }"#).unwrap();
+        let loaded = Settings::load(&path);
+        std::fs::remove_file(&path).unwrap();
+
+        assert_eq!(loaded.theme, "pastel");
+        assert_eq!(loaded.ghost_style, GhostStyle::Dimmed);
+    }
 
-        let piece = Tetromino::new_at(TetrominoType::O, 4, 0);
-        let mut game = Game::with_grid(grid, piece);
+    #[test]
+    fn auto_pause_is_on_unless_the_file_turns_it_off() {
+        let path = std::env::temp_dir().join(format!("tetris-focus-{}.json", std::process::id()));
+        std::fs::write(&path, r#"{ "bell": true }"#).unwrap();
+        let loaded = Settings::load(&path);
+        std::fs::write(&path, r#"{ "auto_pause": false }"#).unwrap();
+        let disabled = Settings::load(&path);
+        std::fs::remove_file(&path).unwrap();
+
+        assert!(loaded.auto_pause);
+        assert!(!disabled.auto_pause);
+    }
 
-        let cleared = game.clear_lines();
+    #[test]
+    fn ghost_styles_are_written_in_lowercase() {
+        let settings = Settings {
+            ghost_style: GhostStyle::Hidden,
+            ..Settings::default()
+        };
+        let json = serde_json::to_string(&settings).unwrap();
+        assert!(json.contains(r#""ghost_style":"hidden""#));
+    }
 
-        assert_eq!(cleared, GRID_HEIGHT as u32);
-        // After clearing all rows, grid should be empty
-        for y in 0..GRID_HEIGHT {
-            assert!(!game.is_row_complete(y));
-        }
+    #[test]
+    fn cell_shapes_are_written_in_kebab_case() {
+        let settings = Settings {
+            cell_shape: CellShape::HalfBlock,
+            ..Settings::default()
+        };
+        let json = serde_json::to_string(&settings).unwrap();
+        assert!(json.contains(r#""cell_shape":"half-block""#));
+        assert_eq!(Settings::default().cell_shape, CellShape::Wide);
+    }
+
+    #[test]
+    fn missing_settings_file_loads_defaults() {
+        let path = std::env::temp_dir().join("tetris-settings-does-not-exist.json");
+        assert_eq!(Settings::load(path), Settings::default());
     }
 }
 
 // ============================================================================
-// Pause Tests
+// Gravity Direction Tests
 // ============================================================================
 
-mod pause {
+mod gravity_direction {
     use super::*;
 
+    fn sideways_game(piece: TetrominoType) -> Game {
+        let config = GameConfig {
+            gravity: GravityDirection::Left,
+            ..GameConfig::default()
+        };
+        Game::with_config(config, Box::new(SequencePieceProvider::new(vec![piece])))
+    }
+
+    /// Column 0 filled except rows 8..12, with a marker in column 1
+    fn sideways_game_with_vertical_slot() -> Game {
+        let mut game = sideways_game(TetrominoType::O);
+        for y in 0..GRID_HEIGHT {
+            if !(8..12).contains(&y) {
+                game.grid[y][0] = CellState::Filled(TetrominoType::T);
+            }
+        }
+        game.grid[GRID_HEIGHT - 1][1] = CellState::Filled(TetrominoType::J);
+        let mut piece = Tetromino::new_at(TetrominoType::I, 5, 8);
+        piece.rotation = 1;
+        game.current_piece = piece;
+        game
+    }
+
     #[test]
-    fn pause_from_playing_state() {
-        let mut game = Game::new();
+    fn moving_left_stops_at_the_ceiling() {
+        let mut game = sideways_game(TetrominoType::I);
+        game.config.buffer_rows = 2;
+
+        for _ in 0..GRID_HEIGHT {
+            game.apply_input(Input::MoveLeft);
+        }
+        assert!(game.current_piece.blocks().iter().all(|block| block.y >= 0));
+
+        game.hard_drop();
         assert_eq!(game.state, GameState::Playing);
-        game.take_events();
+    }
 
-        game.toggle_pause();
+    #[test]
+    fn pieces_spawn_against_the_right_wall() {
+        let game = sideways_game(TetrominoType::O);
+        let blocks = game.current_piece.blocks();
 
-        assert_eq!(game.state, GameState::Paused);
-        let events = game.take_events();
-        assert!(events.contains(&GameEvent::Paused));
+        assert_eq!(blocks.iter().map(|b| b.x).max(), Some(GRID_WIDTH as i16 - 1));
+        assert!(game.is_valid_position(&game.current_piece));
     }
 
     #[test]
-    fn unpause_from_paused_state() {
-        let mut game = Game::new();
-        game.toggle_pause(); // Pause first
-        game.take_events();
+    fn tick_and_soft_drop_move_left() {
+        let mut game = sideways_game(TetrominoType::O);
+        game.current_piece = Tetromino::new_at(TetrominoType::O, 6, 5);
 
-        game.toggle_pause(); // Unpause
+        game.tick();
+        assert_eq!(game.current_piece.position, Position { x: 5, y: 5 });
+        game.soft_drop();
+        assert_eq!(game.current_piece.position, Position { x: 4, y: 5 });
+    }
 
-        assert_eq!(game.state, GameState::Playing);
-        let events = game.take_events();
-        assert!(events.contains(&GameEvent::Unpaused));
+    #[test]
+    fn move_inputs_shift_along_the_wall() {
+        let mut game = sideways_game(TetrominoType::O);
+        game.current_piece = Tetromino::new_at(TetrominoType::O, 6, 5);
+
+        game.apply_input(Input::MoveLeft);
+        assert_eq!(game.current_piece.position, Position { x: 6, y: 4 });
+        game.apply_input(Input::MoveRight);
+        game.apply_input(Input::MoveRight);
+        assert_eq!(game.current_piece.position, Position { x: 6, y: 6 });
     }
 
     #[test]
-    fn cannot_pause_when_game_over() {
-        let mut game = Game::new();
-        game.state = GameState::GameOver;
+    fn hard_drop_lands_against_the_left_wall() {
+        let mut game = sideways_game(TetrominoType::O);
+        game.current_piece = Tetromino::new_at(TetrominoType::O, 6, 5);
+        assert_eq!(game.ghost_piece().position, Position { x: 0, y: 5 });
 
-        game.toggle_pause();
+        game.hard_drop();
 
-        assert_eq!(game.state, GameState::GameOver);
+        assert_eq!(game.grid[5][0], CellState::Filled(TetrominoType::O));
+        assert_eq!(game.grid[6][1], CellState::Filled(TetrominoType::O));
     }
 
     #[test]
-    fn tick_does_nothing_when_paused() {
-        let piece = Tetromino::new_at(TetrominoType::O, 4, 5);
-        let mut game = Game::with_grid(empty_grid(), piece);
-        let initial_y = game.current_piece.position.y;
+    fn full_column_clears_and_shifts_the_rest_left() {
+        let mut game = sideways_game_with_vertical_slot();
 
-        game.toggle_pause();
-        game.tick();
+        game.hard_drop();
 
-        assert_eq!(game.current_piece.position.y, initial_y);
+        assert_eq!(game.lines_cleared, 1);
+        assert_eq!(game.score, SCORE_SINGLE);
+        // The marker from column 1 moved into column 0
+        assert_eq!(game.grid[GRID_HEIGHT - 1][0], CellState::Filled(TetrominoType::J));
+        assert!(game.grid.iter().all(|row| row[GRID_WIDTH - 1] == CellState::Empty));
+        assert_eq!(game.grid.iter().filter(|row| row[0] != CellState::Empty).count(), 1);
     }
 
     #[test]
-    fn cannot_move_piece_when_paused() {
-        let piece = Tetromino::new_at(TetrominoType::O, 4, 5);
-        let mut game = Game::with_grid(empty_grid(), piece);
-        let initial_x = game.current_piece.position.x;
+    fn cleared_column_indices_are_reported_left_to_right() {
+        let mut game = sideways_game(TetrominoType::O);
+        for row in &mut game.grid {
+            row[2] = CellState::Filled(TetrominoType::T);
+            row[0] = CellState::Filled(TetrominoType::T);
+        }
+        game.take_events();
 
-        game.toggle_pause();
-        let moved = game.move_piece(1, 0);
+        assert_eq!(game.clear_lines(), 2);
 
-        assert!(!moved);
-        assert_eq!(game.current_piece.position.x, initial_x);
+        assert!(game.take_events().contains(&GameEvent::LinesClearedAt(vec![0, 2])));
     }
 
     #[test]
-    fn cannot_rotate_piece_when_paused() {
-        let piece = Tetromino::new_at(TetrominoType::T, 4, 5);
-        let mut game = Game::with_grid(empty_grid(), piece);
-        let initial_rotation = game.current_piece.rotation;
+    fn full_rows_do_not_clear_sideways() {
+        let mut game = sideways_game(TetrominoType::O);
+        fill_row(&mut game.grid, GRID_HEIGHT - 1);
 
-        game.toggle_pause();
-        let rotated = game.rotate_piece(true);
+        assert_eq!(game.clear_lines(), 0);
+        assert!(game.grid[GRID_HEIGHT - 1].iter().all(|cell| *cell != CellState::Empty));
+    }
+}
 
-        assert!(!rotated);
-        assert_eq!(game.current_piece.rotation, initial_rotation);
+// ============================================================================
+// Puzzle Tests
+// ============================================================================
+
+mod puzzle {
+    use super::*;
+
+    /// One row with a two-wide gap that the O spawns right above
+    const ONE_O_PUZZLE: &str = r#"{
+        "board": "TTTT..TTTT",
+        "sequence": ["O", "I"],
+        "goal": { "lines": 1, "pieces": 2 }
+    }"#;
+
+    #[test]
+    fn loads_board_sequence_and_goal_from_json() {
+        let puzzle = Puzzle::from_json(ONE_O_PUZZLE).unwrap();
+        let game = Game::from_puzzle(&puzzle).unwrap();
+
+        assert_eq!(game.current_piece.tetromino_type, TetrominoType::O);
+        assert_eq!(
+            game.preview_queue.iter().copied().collect::<Vec<_>>(),
+            vec![TetrominoType::I, TetrominoType::O, TetrominoType::I, TetrominoType::O]
+        );
+        assert_eq!(game.grid[GRID_HEIGHT - 1][0], CellState::Filled(TetrominoType::T));
+        assert_eq!(game.grid[GRID_HEIGHT - 1][4], CellState::Empty);
+        assert_eq!(game.mode_progress(), Some(ModeProgress::LinesRemaining(1)));
+        assert_eq!(game.puzzle_solved(), None);
     }
 
     #[test]
-    fn pause_and_unpause_multiple_times() {
-        let mut game = Game::new();
+    fn reaching_the_goal_solves_the_puzzle() {
+        let path = temp_high_score_file("puzzle");
+        let puzzle = Puzzle::from_json(ONE_O_PUZZLE).unwrap();
+        let mut game = Game::from_puzzle(&puzzle).unwrap().with_high_score_file(&path);
 
-        game.toggle_pause();
-        assert_eq!(game.state, GameState::Paused);
+        game.hard_drop();
 
-        game.toggle_pause();
+        assert_eq!(game.state, GameState::GameOver);
+        assert_eq!(game.puzzle_solved(), Some(true));
+        let _ = std::fs::remove_file(&path);
+    }
+
+    #[test]
+    fn running_out_of_pieces_fails_the_puzzle() {
+        let puzzle = Puzzle {
+            board: "TTTT..TTTT".to_string(),
+            sequence: vec![TetrominoType::I],
+            goal: PuzzleGoal { lines: 1, pieces: 2 },
+        };
+        let mut game = Game::from_puzzle(&puzzle).unwrap();
+
+        game.hard_drop();
         assert_eq!(game.state, GameState::Playing);
+        assert_eq!(game.puzzle_solved(), None);
+        game.hard_drop();
 
-        game.toggle_pause();
-        assert_eq!(game.state, GameState::Paused);
+        assert_eq!(game.state, GameState::GameOver);
+        assert_eq!(game.puzzle_solved(), Some(false));
     }
 
     #[test]
-    fn no_move_events_when_paused() {
-        let piece = Tetromino::new_at(TetrominoType::O, 4, 5);
-        let mut game = Game::with_grid(empty_grid(), piece);
-        game.toggle_pause();
-        game.take_events(); // Clear pause event
+    fn rejects_bad_boards_unknown_pieces_and_empty_sequences() {
+        let bad_board = Puzzle {
+            board: "TTT".to_string(),
+            sequence: vec![TetrominoType::O],
+            goal: PuzzleGoal { lines: 1, pieces: 1 },
+        };
+        assert!(matches!(
+            Game::from_puzzle(&bad_board),
+            Err(PuzzleError::Board(BoardParseError::WrongWidth { .. }))
+        ));
+
+        let unknown_piece = Puzzle {
+            board: "TTTT..TTTT".to_string(),
+            sequence: vec![TetrominoType::O, TetrominoType::Custom(250)],
+            goal: PuzzleGoal { lines: 1, pieces: 2 },
+        };
+        assert!(matches!(
+            Game::from_puzzle(&unknown_piece),
+            Err(PuzzleError::UnknownPiece(TetrominoType::Custom(250)))
+        ));
+
+        let no_pieces = Puzzle {
+            sequence: Vec::new(),
+            board: String::new(),
+            ..bad_board
+        };
+        assert!(matches!(Game::from_puzzle(&no_pieces), Err(PuzzleError::EmptySequence)));
+    }
+}
 
-        game.move_piece(-1, 0);
-        game.move_piece(1, 0);
-        game.move_piece(0, 1);
+// ============================================================================
+// Sound Tests
+// ============================================================================
 
-        let events = game.take_events();
-        assert!(events.is_empty());
-        assert!(!events.contains(&GameEvent::PieceMoved));
+mod sound {
+    use super::*;
+    use std::collections::BTreeMap;
+
+    /// Counts calls per sink method
+    #[derive(Default)]
+    struct CountingSink {
+        calls: BTreeMap<&'static str, u32>,
     }
 
-    #[test]
-    fn no_rotation_events_when_paused() {
-        let piece = Tetromino::new_at(TetrominoType::T, 4, 5);
-        let mut game = Game::with_grid(empty_grid(), piece);
-        game.toggle_pause();
-        game.take_events(); // Clear pause event
+    impl CountingSink {
+        fn count(&mut self, name: &'static str) {
+            *self.calls.entry(name).or_insert(0) += 1;
+        }
+    }
 
-        game.rotate_piece(true);
-        game.rotate_piece(false);
+    impl SoundSink for CountingSink {
+        fn piece_moved(&mut self) {
+            self.count("moved");
+        }
+        fn piece_rotated(&mut self) {
+            self.count("rotated");
+        }
+        fn piece_locked(&mut self) {
+            self.count("locked");
+        }
+        fn hard_drop(&mut self) {
+            self.count("hard_drop");
+        }
+        fn piece_held(&mut self) {
+            self.count("held");
+        }
+        fn lines_cleared(&mut self, _lines: u32) {
+            self.count("lines");
+        }
+        fn tspin(&mut self, _lines: u32, _mini: bool) {
+            self.count("tspin");
+        }
+        fn level_up(&mut self, _level: u32) {
+            self.count("level_up");
+        }
+        fn game_over(&mut self) {
+            self.count("game_over");
+        }
+    }
+
+    fn every_event() -> Vec<GameEvent> {
+        vec![
+            GameEvent::PieceMoved,
+            GameEvent::PieceRotated,
+            GameEvent::PieceLocked,
+            GameEvent::HardDrop(Tetromino::new(TetrominoType::O)),
+            GameEvent::Finesse { optimal: 1, actual: 1 },
+            GameEvent::PieceSpawned(TetrominoType::T),
+            GameEvent::PieceHeld(TetrominoType::I),
+            GameEvent::LinesCleared(2),
+            GameEvent::TSpin { lines: 1, mini: false },
+            GameEvent::LevelUp(2),
+            GameEvent::Paused,
+            GameEvent::Unpaused,
+            GameEvent::GameRestarted,
+            GameEvent::GameOver,
+        ]
+    }
+
+    #[test]
+    fn each_sound_event_reaches_its_method_once() {
+        let mut sink = CountingSink::default();
+        play_events(&mut sink, &every_event());
 
-        let events = game.take_events();
-        assert!(events.is_empty());
-        assert!(!events.contains(&GameEvent::PieceRotated));
+        let expected = [
+            "game_over", "hard_drop", "held", "level_up", "lines", "locked", "moved", "rotated",
+            "tspin",
+        ];
+        assert_eq!(sink.calls.keys().copied().collect::<Vec<_>>(), expected);
+        assert!(sink.calls.values().all(|&count| count == 1));
     }
 
     #[test]
-    fn no_drop_events_when_paused() {
-        let piece = Tetromino::new_at(TetrominoType::O, 4, 0);
-        let mut game = Game::with_grid(empty_grid(), piece);
-        game.toggle_pause();
-        game.take_events(); // Clear pause event
+    fn no_sound_sink_accepts_every_event() {
+        play_events(&mut NoSound, &every_event());
+    }
 
-        game.soft_drop();
-        game.hard_drop();
+    #[test]
+    fn sink_follows_a_real_game() {
+        let mut game = Game::with_grid(empty_grid(), Tetromino::new(TetrominoType::O));
+        game.take_events();
+        game.move_piece(1, 0);
+        let mut sink = CountingSink::default();
+        play_events(&mut sink, &game.take_events());
+        assert_eq!(sink.calls.get("moved"), Some(&1));
 
-        let events = game.take_events();
-        assert!(events.is_empty());
-        assert!(!events.contains(&GameEvent::PieceMoved));
-        assert!(!events.contains(&GameEvent::PieceLocked));
+        game.hard_drop();
+        play_events(&mut sink, &game.take_events());
+        assert_eq!(sink.calls.get("hard_drop"), Some(&1));
+        assert_eq!(sink.calls.get("locked"), Some(&1));
     }
 }
 
 // ============================================================================
-// Restart Tests
+// Versus Tests
 // ============================================================================
 
-mod restart {
+mod versus {
     use super::*;
 
-    #[test]
-    fn restart_resets_score() {
-        let mut game = Game::new();
-        game.score = 5000;
-        game.state = GameState::GameOver;
+    fn player() -> Game {
+        Game::with_config(
+            GameConfig::default(),
+            Box::new(SequencePieceProvider::new(vec![TetrominoType::O])),
+        )
+    }
 
-        game.restart();
+    /// Four rows full except column 0, with a vertical I above the gap
+    fn set_up_tetris(game: &mut Game) {
+        for y in GRID_HEIGHT - 4..GRID_HEIGHT {
+            for x in 1..GRID_WIDTH {
+                game.grid[y][x] = CellState::Filled(TetrominoType::J);
+            }
+        }
+        let mut piece = Tetromino::new_at(TetrominoType::I, 0, 0);
+        piece.rotation = 1;
+        game.current_piece = piece;
+    }
 
-        assert_eq!(game.score, 0);
+    fn garbage_rows(game: &Game) -> usize {
+        game.grid
+            .iter()
+            .filter(|row| row.contains(&CellState::Garbage))
+            .count()
     }
 
     #[test]
-    fn restart_resets_lines_and_level() {
-        let mut game = Game::new();
-        game.lines_cleared = 50;
-        game.level = 6;
-        game.state = GameState::GameOver;
-
-        game.restart();
-
-        assert_eq!(game.lines_cleared, 0);
-        assert_eq!(game.level, 1);
+    fn garbage_table_rewards_bigger_clears() {
+        let sent: Vec<u32> = (0..=4).map(garbage_for_lines).collect();
+        assert_eq!(sent, vec![0, 0, 1, 2, 4]);
     }
 
     #[test]
-    fn restart_clears_grid() {
-        let mut grid = empty_grid();
-        // Fill some cells
-        grid[GRID_HEIGHT - 1][0] = CellState::Filled(TetrominoType::T);
-        grid[GRID_HEIGHT - 1][1] = CellState::Filled(TetrominoType::T);
-
-        let piece = Tetromino::new_at(TetrominoType::O, 4, 0);
-        let mut game = Game::with_grid(grid, piece);
-        game.state = GameState::GameOver;
-
-        game.restart();
-
-        // Grid should be empty
-        for row in &game.grid {
-            for cell in row {
-                assert_eq!(*cell, CellState::Empty);
-            }
+    fn tetris_on_one_board_sends_four_rows_to_the_other() {
+        let mut versus = VersusMatch::new(player(), player(), 1);
+        set_up_tetris(&mut versus.players[0]);
+
+        versus.players[0].hard_drop();
+        let events = versus.update(0);
+
+        assert!(events[0].contains(&GameEvent::LinesCleared(4)));
+        assert_eq!(garbage_rows(&versus.players[1]), 4);
+        assert_eq!(garbage_rows(&versus.players[0]), 0);
+        // Each garbage row has exactly one hole
+        for row in &versus.players[1].grid[GRID_HEIGHT - 4..] {
+            assert_eq!(row.iter().filter(|cell| **cell == CellState::Empty).count(), 1);
         }
     }
 
     #[test]
-    fn restart_sets_state_to_playing() {
-        let mut game = Game::new();
-        game.state = GameState::GameOver;
+    fn garbage_is_sent_once() {
+        let mut versus = VersusMatch::new(player(), player(), 1);
+        set_up_tetris(&mut versus.players[1]);
 
-        game.restart();
+        versus.players[1].hard_drop();
+        versus.update(0);
+        versus.update(0);
 
-        assert_eq!(game.state, GameState::Playing);
+        assert_eq!(garbage_rows(&versus.players[0]), 4);
     }
 
     #[test]
-    fn restart_emits_event() {
-        let mut game = Game::new();
-        game.state = GameState::GameOver;
-        game.take_events();
-
-        game.restart();
+    fn the_player_left_standing_wins() {
+        let mut versus = VersusMatch::new(player(), player(), 1);
+        assert_eq!(versus.winner(), None);
 
-        let events = game.take_events();
-        assert!(events.contains(&GameEvent::GameRestarted));
+        versus.players[1].state = GameState::GameOver;
+        assert_eq!(versus.winner(), Some(0));
     }
+}
 
-    #[test]
-    fn restart_spawns_new_piece() {
-        let mut game = Game::new();
-        game.state = GameState::GameOver;
+// ============================================================================
+// Autosave Snapshot Tests
+// ============================================================================
 
-        game.restart();
+mod snapshot {
+    use super::*;
 
-        // New piece should be spawned at spawn position
-        assert_eq!(game.current_piece.position.x, (GRID_WIDTH as i16 / 2) - 1);
-        assert_eq!(game.current_piece.position.y, 0);
+    fn snapshot_path(name: &str) -> std::path::PathBuf {
+        std::env::temp_dir().join(format!("tetris-{}-{}.json", name, std::process::id()))
+    }
+
+    fn game_in_progress() -> Game {
+        let mut game = Game::with_provider(Box::new(SeededPieceProvider::new(5)));
+        game.hard_drop();
+        game.hold_piece();
+        game.apply_input(Input::MoveLeft);
+        game.score = 1200;
+        game
     }
 
     #[test]
-    fn restart_with_deterministic_provider() {
-        // Test with known piece sequence to verify provider is preserved
-        let pieces = vec![
-            TetrominoType::T,
-            TetrominoType::O,
-            TetrominoType::I,
-            TetrominoType::L,
-            TetrominoType::J,
-            TetrominoType::S,
-            TetrominoType::Z,
-        ];
-        let provider = Box::new(SequencePieceProvider::new(pieces));
-        let mut game = Game::with_provider(provider);
+    fn snapshot_round_trips_through_the_autosave_file() {
+        let mut game = game_in_progress();
+        game.toggle_pause();
+        assert!(game.take_events().contains(&GameEvent::Paused));
+        let path = snapshot_path("autosave");
+
+        game.to_snapshot().save(&path).unwrap();
+        let loaded = GameSnapshot::load(&path).unwrap();
+        let _ = std::fs::remove_file(&path);
+
+        assert_eq!(loaded, game.to_snapshot());
+
+        let mut resumed = Game::with_provider(Box::new(SeededPieceProvider::new(77)));
+        resumed.restore_snapshot(&loaded);
+        assert_eq!(resumed.grid, game.grid);
+        assert_eq!(resumed.current_piece, game.current_piece);
+        assert_eq!(resumed.preview_queue, game.preview_queue);
+        assert_eq!(resumed.held_pieces, game.held_pieces);
+        assert_eq!(resumed.can_hold, game.can_hold);
+        assert_eq!(resumed.score, 1200);
+        assert_eq!(resumed.session_stats(), game.session_stats());
+        assert_eq!(resumed.to_snapshot(), game.to_snapshot());
+    }
 
-        // Use up first 5 pieces (4 for preview + 1 current)
-        game.state = GameState::GameOver;
+    #[test]
+    fn restored_game_waits_paused() {
+        let snapshot = game_in_progress().to_snapshot();
+        let mut resumed = Game::new();
 
-        game.restart();
+        resumed.restore_snapshot(&snapshot);
 
-        // After restart, should get new pieces from the same provider
-        assert_eq!(game.state, GameState::Playing);
-        assert_eq!(game.preview_queue.len(), PREVIEW_COUNT);
+        assert_eq!(resumed.state, GameState::Paused);
+        assert!(resumed.take_events().is_empty());
+        resumed.assert_invariants();
+        resumed.toggle_pause();
+        assert_eq!(resumed.state, GameState::Playing);
     }
 
     #[test]
-    fn restart_game_can_be_played() {
-        let mut game = Game::new();
-        game.state = GameState::GameOver;
-
-        game.restart();
+    fn snapshot_for_another_board_size_is_rejected() {
+        let mut snapshot = game_in_progress().to_snapshot();
+        snapshot.grid.pop();
+        let path = snapshot_path("short-board");
+        snapshot.save(&path).unwrap();
 
-        // Should be able to move pieces after restart
-        game.move_piece(1, 0);
-        // May or may not move depending on piece type and boundaries
+        let loaded = GameSnapshot::load(&path);
+        let _ = std::fs::remove_file(&path);
 
-        // Should be able to tick
-        game.tick();
+        assert_eq!(loaded.unwrap_err().kind(), std::io::ErrorKind::InvalidData);
+    }
 
-        // Game should still be playing (not immediately game over)
-        assert_eq!(game.state, GameState::Playing);
+    #[test]
+    fn snapshot_with_an_unregistered_custom_piece_is_rejected() {
+        // No test registers anywhere near this many pieces
+        let unknown = TetrominoType::Custom(250);
+        assert!(!is_known(unknown));
+        let mut snapshot = game_in_progress().to_snapshot();
+        snapshot.preview_queue[0] = unknown;
+        let path = snapshot_path("unknown-piece");
+        snapshot.save(&path).unwrap();
+
+        let loaded = GameSnapshot::load(&path);
+        let _ = std::fs::remove_file(&path);
+
+        let err = loaded.unwrap_err();
+        assert_eq!(err.kind(), std::io::ErrorKind::InvalidData);
+        assert!(err.to_string().contains("Custom(250)"));
     }
 }
 
 // ============================================================================
-// High Score Tests
+// Lock Flash Tests
 // ============================================================================
 
-mod high_score {
+mod lock_flash {
     use super::*;
 
-    // Helper to clean up high score file after tests
-    fn cleanup_high_score_file() {
-        let _ = std::fs::remove_file("highscore.txt");
-    }
+    const FLASH_MS: u64 = 100;
 
     #[test]
-    #[serial]
-    fn high_score_initializes_to_zero_when_no_file() {
-        cleanup_high_score_file();
-        let game = Game::new();
-        assert_eq!(game.high_score, 0);
-        cleanup_high_score_file();
+    fn flash_covers_the_locked_cells_for_its_duration() {
+        let piece = Tetromino::new_at(TetrominoType::O, 4, GRID_HEIGHT as i16 - 2);
+        let mut game = Game::with_grid(empty_grid(), piece.clone());
+        let mut flash = LockFlash::default();
+
+        game.soft_drop();
+        flash.handle_events(&game.take_events(), &game, FLASH_MS);
+
+        assert_eq!(game.last_locked(), Some(&piece));
+        assert_eq!(flash.cells(), piece.blocks().as_slice());
+        flash.advance(FLASH_MS - 1);
+        assert!(flash.is_active());
+        assert_eq!(flash.remaining_ms(), Some(1));
+
+        flash.advance(1);
+        assert!(!flash.is_active());
+        assert!(flash.cells().is_empty());
+        assert_eq!(flash.remaining_ms(), None);
     }
 
     #[test]
-    #[serial]
-    fn high_score_loads_from_file() {
-        cleanup_high_score_file();
-        std::fs::write("highscore.txt", "5000").unwrap();
+    fn line_clear_takes_priority_over_the_flash() {
+        let mut grid = empty_grid();
+        fill_row(&mut grid, GRID_HEIGHT - 1);
+        grid[GRID_HEIGHT - 1][4] = CellState::Empty;
+        grid[GRID_HEIGHT - 1][5] = CellState::Empty;
+        let piece = Tetromino::new_at(TetrominoType::O, 4, 0);
+        let mut game = Game::with_grid(grid, piece);
+        let mut flash = LockFlash::default();
 
-        let game = Game::new();
+        game.hard_drop();
+        let events = game.take_events();
+        assert!(events.contains(&GameEvent::LinesCleared(1)));
+        flash.handle_events(&events, &game, FLASH_MS);
 
-        assert_eq!(game.high_score, 5000);
-        cleanup_high_score_file();
+        assert!(!flash.is_active());
+        assert!(flash.cells().is_empty());
     }
 
     #[test]
-    #[serial]
-    fn high_score_updates_on_game_over_when_beaten() {
-        cleanup_high_score_file();
-        std::fs::write("highscore.txt", "1000").unwrap();
+    fn zero_duration_disables_the_flash() {
+        let piece = Tetromino::new_at(TetrominoType::O, 4, GRID_HEIGHT as i16 - 2);
+        let mut game = Game::with_grid(empty_grid(), piece);
+        let mut flash = LockFlash::default();
 
-        let mut grid = empty_grid();
-        // Fill the spawn area to trigger game over
-        for x in 0..GRID_WIDTH {
-            grid[0][x] = CellState::Filled(TetrominoType::T);
-            grid[1][x] = CellState::Filled(TetrominoType::T);
-        }
+        game.soft_drop();
+        flash.handle_events(&game.take_events(), &game, 0);
 
-        let piece = Tetromino::new_at(TetrominoType::O, 4, 10);
-        let mut game = Game::with_grid(grid, piece);
-        game.score = 2000; // Set score higher than high score
+        assert!(!flash.is_active());
+        assert!(flash.cells().is_empty());
+    }
 
-        game.spawn_next_piece(); // This will trigger game over
+    #[test]
+    fn hard_drop_squashes_the_top_face_for_one_frame() {
+        let mut game = Game::with_grid(empty_grid(), Tetromino::new_at(TetrominoType::O, 4, 0));
+        let mut settle = Settle::default();
 
-        assert_eq!(game.state, GameState::GameOver);
-        assert_eq!(game.high_score, 2000);
+        game.hard_drop();
+        settle.handle_events(&game.take_events(), true);
 
-        // Verify file was updated
-        let saved_score = std::fs::read_to_string("highscore.txt")
-            .unwrap()
-            .trim()
-            .parse::<u32>()
-            .unwrap();
-        assert_eq!(saved_score, 2000);
+        let top = GRID_HEIGHT as i16 - 2;
+        assert_eq!(
+            settle.squashed_cells(),
+            &[Position { x: 4, y: top }, Position { x: 5, y: top }]
+        );
+        assert_eq!(settle.remaining_ms(), Some(SETTLE_MS));
 
-        cleanup_high_score_file();
+        settle.advance(SETTLE_MS);
+        assert!(!settle.is_active());
+        assert!(settle.squashed_cells().is_empty());
     }
 
     #[test]
-    #[serial]
-    fn high_score_does_not_update_when_not_beaten() {
-        cleanup_high_score_file();
-        std::fs::write("highscore.txt", "5000").unwrap();
+    fn settle_only_follows_enabled_hard_drops() {
+        let mut game = Game::with_grid(empty_grid(), Tetromino::new_at(TetrominoType::O, 4, 0));
+        let mut settle = Settle::default();
 
-        let mut grid = empty_grid();
-        // Fill the spawn area to trigger game over
-        for x in 0..GRID_WIDTH {
-            grid[0][x] = CellState::Filled(TetrominoType::T);
-            grid[1][x] = CellState::Filled(TetrominoType::T);
-        }
-
-        let piece = Tetromino::new_at(TetrominoType::O, 4, 10);
-        let mut game = Game::with_grid(grid, piece);
-        game.score = 3000; // Set score lower than high score
+        game.hard_drop();
+        settle.handle_events(&game.take_events(), false);
+        assert!(!settle.is_active());
 
-        game.spawn_next_piece(); // This will trigger game over
+        game.current_piece = Tetromino::new_at(TetrominoType::O, 0, GRID_HEIGHT as i16 - 2);
+        game.soft_drop();
+        settle.handle_events(&game.take_events(), true);
+        assert!(!settle.is_active());
+    }
 
-        assert_eq!(game.state, GameState::GameOver);
-        assert_eq!(game.high_score, 5000); // Should remain 5000
+    #[test]
+    fn line_clear_cancels_the_settle() {
+        let mut grid = empty_grid();
+        fill_row(&mut grid, GRID_HEIGHT - 1);
+        grid[GRID_HEIGHT - 1][4] = CellState::Empty;
+        grid[GRID_HEIGHT - 1][5] = CellState::Empty;
+        let mut game = Game::with_grid(grid, Tetromino::new_at(TetrominoType::O, 4, 0));
+        let mut settle = Settle::default();
 
-        // Verify file was not changed
-        let saved_score = std::fs::read_to_string("highscore.txt")
-            .unwrap()
-            .trim()
-            .parse::<u32>()
-            .unwrap();
-        assert_eq!(saved_score, 5000);
+        game.hard_drop();
+        settle.handle_events(&game.take_events(), true);
 
-        cleanup_high_score_file();
+        assert!(!settle.is_active());
     }
 
     #[test]
-    #[serial]
-    fn high_score_persists_across_game_restarts() {
-        cleanup_high_score_file();
+    fn locked_piece_turns_invisible_after_the_delay() {
+        let piece = Tetromino::new_at(TetrominoType::O, 4, GRID_HEIGHT as i16 - 2);
+        let mut game = Game::with_grid(empty_grid(), piece.clone());
+        let mut invisible = InvisibleStack::new(true);
+        let cell = piece.blocks()[0];
+        assert!(invisible.hides(cell), "cells locked before tracking started stay hidden");
 
-        // First game session - set high score
-        {
+        game.soft_drop();
+        invisible.handle_events(&game.take_events(), &game);
+        assert!(!invisible.hides(cell));
+        assert_eq!(invisible.remaining_ms(), Some(INVISIBLE_AFTER_MS));
+
+        invisible.advance(INVISIBLE_AFTER_MS);
+        assert!(invisible.hides(cell));
+        assert_eq!(invisible.remaining_ms(), None);
+        assert_ne!(game.grid[cell.y as usize][cell.x as usize], CellState::Empty);
+    }
+
+    #[test]
+    fn line_clear_reveals_the_stack_when_enabled() {
+        for reveal_on_clear in [true, false] {
             let mut grid = empty_grid();
-            for x in 0..GRID_WIDTH {
-                grid[0][x] = CellState::Filled(TetrominoType::T);
-                grid[1][x] = CellState::Filled(TetrominoType::T);
-            }
+            fill_row(&mut grid, GRID_HEIGHT - 1);
+            grid[GRID_HEIGHT - 1][4] = CellState::Empty;
+            grid[GRID_HEIGHT - 1][5] = CellState::Empty;
+            let mut game = Game::with_grid(grid, Tetromino::new_at(TetrominoType::O, 4, 0));
+            let mut invisible = InvisibleStack::new(reveal_on_clear);
 
-            let piece = Tetromino::new_at(TetrominoType::O, 4, 10);
-            let mut game = Game::with_grid(grid, piece);
-            game.score = 8000;
-            game.spawn_next_piece(); // Game over, saves high score
-        }
+            game.hard_drop();
+            invisible.handle_events(&game.take_events(), &game);
 
-        // Second game session - verify high score loaded
-        {
-            let game = Game::new();
-            assert_eq!(game.high_score, 8000);
+            let cell = Position { x: 4, y: GRID_HEIGHT as i16 - 1 };
+            assert_eq!(invisible.hides(cell), !reveal_on_clear);
+            invisible.advance(REVEAL_MS);
+            assert!(invisible.hides(cell));
         }
+    }
+}
+
+// ============================================================================
+// KEY BINDING TESTS
+// ============================================================================
 
-        cleanup_high_score_file();
+mod key_bindings {
+    use super::*;
+
+    #[test]
+    fn mirrored_preset_swaps_move_left_and_move_right_keys() {
+        let bindings = KeyBindings::default();
+        let mirrored = bindings.mirrored();
+
+        assert_eq!(mirrored.move_left, bindings.move_right);
+        assert_eq!(mirrored.move_right, bindings.move_left);
+        assert_eq!(mirrored.input_for_key(KeyCode::Char('a')), Some(Input::MoveRight));
+        assert_eq!(mirrored.input_for_key(KeyCode::Char('d')), Some(Input::MoveLeft));
     }
 
     #[test]
-    #[serial]
-    fn high_score_handles_invalid_file_content() {
-        cleanup_high_score_file();
-        std::fs::write("highscore.txt", "not_a_number").unwrap();
+    fn mirrored_preset_swaps_rotation_and_keeps_drops() {
+        let mirrored = KeyBindings::default().mirrored();
 
-        let game = Game::new();
+        assert_eq!(mirrored.input_for_key(KeyCode::Left), Some(Input::RotateCw));
+        assert_eq!(mirrored.input_for_key(KeyCode::Right), Some(Input::RotateCcw));
+        assert_eq!(mirrored.input_for_key(KeyCode::Char('w')), Some(Input::HardDrop));
+        assert_eq!(mirrored.input_for_key(KeyCode::Char('c')), Some(Input::Hold));
+    }
 
-        // Should default to 0 if file contains invalid data
-        assert_eq!(game.high_score, 0);
-        cleanup_high_score_file();
+    #[test]
+    fn letter_bindings_match_either_case() {
+        let bindings = KeyBindings::default();
+
+        assert_eq!(bindings.input_for_key(KeyCode::Char('S')), Some(Input::SoftDrop));
+        assert_eq!(bindings.input_for_key(KeyCode::Char('x')), None);
+    }
+
+    #[test]
+    fn one_handed_presets_bind_every_action() {
+        for bindings in [KeyBindings::left_hand(), KeyBindings::right_hand()] {
+            for keys in [
+                &bindings.move_left,
+                &bindings.move_right,
+                &bindings.soft_drop,
+                &bindings.hard_drop,
+                &bindings.rotate_cw,
+                &bindings.rotate_ccw,
+                &bindings.hold,
+            ] {
+                assert!(!keys.is_empty());
+            }
+        }
+    }
+
+    #[test]
+    fn settings_apply_preset_then_mirror() {
+        let settings = Settings {
+            controls: KeyPreset::RightHand,
+            mirror_controls: true,
+            ..Settings::default()
+        };
+
+        assert_eq!(settings.key_bindings(), KeyBindings::right_hand().mirrored());
+        assert_eq!(Settings::default().key_bindings(), KeyBindings::default());
+    }
+
+    #[test]
+    fn hint_names_the_active_keys() {
+        assert_eq!(
+            KeyBindings::default().hint(),
+            "A D: Move | S/J W/K: Drop | ←/H →/L: Rotate | C: Hold"
+        );
+        assert_eq!(
+            KeyBindings::left_hand().hint(),
+            "A D: Move | S W: Drop | Z X: Rotate | C: Hold"
+        );
+        assert_eq!(
+            KeyBindings::right_hand().mirrored().hint(),
+            "L J: Move | K I: Drop | O U: Rotate | M: Hold"
+        );
     }
 }